@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mtty::{config::Config, terminal::HeadlessPipeline};
+
+fuzz_target!(|data: &[u8]| {
+    let config = Config {
+        cols: 20,
+        rows: 5,
+        ..Config::default()
+    };
+    let mut pipeline = HeadlessPipeline::new(&config);
+
+    pipeline.feed(data);
+
+    if let Err(e) = pipeline.terminal.grid.check_invariants() {
+        panic!("grid invariant violated: {}", e);
+    }
+});
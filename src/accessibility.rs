@@ -0,0 +1,139 @@
+use std::process::{Command, Stdio};
+
+use crate::{
+    config::{ColorFilter, HighContrast},
+    styles::srgb_to_linear,
+};
+
+#[cfg(test)]
+mod tests;
+
+type Matrix = [[f32; 3]; 3];
+
+const IDENTITY: Matrix = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+// Dichromacy simulation in linear RGB (Machado, Oliveira & Fernandes 2009, severity 1.0)
+const PROTANOPIA: Matrix = [
+    [0.152286, 1.052583, -0.204868],
+    [0.114503, 0.786281, 0.099216],
+    [-0.003882, -0.048116, 1.051998],
+];
+const DEUTERANOPIA: Matrix = [
+    [0.367322, 0.860646, -0.227968],
+    [0.280085, 0.672501, 0.047413],
+    [-0.011820, 0.042940, 0.968881],
+];
+const TRITANOPIA: Matrix = [
+    [1.255528, -0.076749, -0.178779],
+    [-0.078411, 0.930809, 0.147602],
+    [0.004733, 0.691367, 0.303900],
+];
+
+/// How daltonization spreads the color error the viewer cannot see onto channels they can
+const ERROR_SHIFT: Matrix = [[0.0, 0.0, 0.0], [0.7, 1.0, 0.0], [0.7, 0.0, 1.0]];
+
+/// Minimum text contrast enforced in high-contrast mode (WCAG AAA for normal text)
+pub const HIGH_CONTRAST_RATIO: f32 = 7.0;
+
+/// Resolve the configured high-contrast mode, asking the OS when set to `system`
+pub fn high_contrast_enabled(mode: HighContrast) -> bool {
+    match mode {
+        HighContrast::Off => false,
+        HighContrast::On => true,
+        HighContrast::System => system_prefers_high_contrast(),
+    }
+}
+
+/// Whether the desktop's increase-contrast / high-contrast setting is on, where it can be read
+fn system_prefers_high_contrast() -> bool {
+    #[cfg(target_os = "macos")]
+    let (program, args) = (
+        "defaults",
+        ["read", "com.apple.universalaccess", "increaseContrast"],
+    );
+    #[cfg(not(target_os = "macos"))]
+    let (program, args) = (
+        "gsettings",
+        ["get", "org.gnome.desktop.a11y.interface", "high-contrast"],
+    );
+
+    let Ok(output) = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+    else {
+        return false;
+    };
+    matches!(String::from_utf8_lossy(&output.stdout).trim(), "1" | "true")
+}
+
+/// WCAG relative luminance of an sRGB color
+fn luminance((r, g, b): (u8, u8, u8)) -> f32 {
+    0.2126 * srgb_to_linear(r) + 0.7152 * srgb_to_linear(g) + 0.0722 * srgb_to_linear(b)
+}
+
+/// WCAG contrast ratio between two colors, from 1.0 (none) to 21.0 (black on white)
+pub fn contrast_ratio(a: (u8, u8, u8), b: (u8, u8, u8)) -> f32 {
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Move `fg` towards black or white, whichever contrasts more with `bg`, until the
+/// contrast ratio reaches `min_ratio` (or the extreme is hit)
+pub fn ensure_contrast(fg: (u8, u8, u8), bg: (u8, u8, u8), min_ratio: f32) -> (u8, u8, u8) {
+    if contrast_ratio(fg, bg) >= min_ratio {
+        return fg;
+    }
+    let target = if contrast_ratio((255, 255, 255), bg) >= contrast_ratio((0, 0, 0), bg) {
+        255.0
+    } else {
+        0.0
+    };
+    let mix = |c: u8, t: f32| (c as f32 + (target - c as f32) * t).round() as u8;
+
+    // Contrast grows monotonically towards the target, so bisect on the blend factor
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..12 {
+        let mid = (low + high) / 2.0;
+        let candidate = (mix(fg.0, mid), mix(fg.1, mid), mix(fg.2, mid));
+        if contrast_ratio(candidate, bg) >= min_ratio {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    (mix(fg.0, high), mix(fg.1, high), mix(fg.2, high))
+}
+
+/// Linear-RGB matrix (row-major) applied to every pixel for a color filter.
+///
+/// Daltonization adds the shifted simulation error back to the original,
+/// `c + E(c - Sc)`, which folds into the single matrix `I + E(I - S)`.
+pub fn color_filter_matrix(filter: ColorFilter) -> Matrix {
+    match filter {
+        ColorFilter::None => IDENTITY,
+        ColorFilter::Protanopia => PROTANOPIA,
+        ColorFilter::Deuteranopia => DEUTERANOPIA,
+        ColorFilter::Tritanopia => TRITANOPIA,
+        ColorFilter::DaltonizeProtanopia => daltonize(&PROTANOPIA),
+        ColorFilter::DaltonizeDeuteranopia => daltonize(&DEUTERANOPIA),
+        ColorFilter::DaltonizeTritanopia => daltonize(&TRITANOPIA),
+    }
+}
+
+fn daltonize(simulation: &Matrix) -> Matrix {
+    let mut error = IDENTITY;
+    for (row, sim) in error.iter_mut().zip(simulation) {
+        for (value, s) in row.iter_mut().zip(sim) {
+            *value -= s;
+        }
+    }
+    let mut result = IDENTITY;
+    for (i, row) in result.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value += (0..3).map(|k| ERROR_SHIFT[i][k] * error[k][j]).sum::<f32>();
+        }
+    }
+    result
+}
@@ -0,0 +1,58 @@
+use super::{color_filter_matrix, contrast_ratio, ensure_contrast};
+use crate::config::ColorFilter;
+
+fn apply(matrix: [[f32; 3]; 3], color: [f32; 3]) -> [f32; 3] {
+    matrix.map(|row| row.iter().zip(color).map(|(m, c)| m * c).sum())
+}
+
+#[test]
+fn contrast_ratio_should_span_one_to_twenty_one() {
+    assert!((contrast_ratio((0, 0, 0), (255, 255, 255)) - 21.0).abs() < 0.01);
+    assert!((contrast_ratio((90, 90, 90), (90, 90, 90)) - 1.0).abs() < 0.01);
+}
+
+#[test]
+fn ensure_contrast_should_keep_sufficient_colors() {
+    assert_eq!(
+        ensure_contrast((255, 255, 0), (0, 0, 0), 7.0),
+        (255, 255, 0)
+    );
+}
+
+#[test]
+fn ensure_contrast_should_lighten_on_dark_and_darken_on_light() {
+    let on_dark = ensure_contrast((0, 0, 160), (0, 0, 0), 7.0);
+    assert!(contrast_ratio(on_dark, (0, 0, 0)) >= 7.0);
+
+    let on_light = ensure_contrast((200, 200, 120), (255, 255, 255), 7.0);
+    assert!(contrast_ratio(on_light, (255, 255, 255)) >= 7.0);
+    assert!(on_light.0 < 200);
+}
+
+#[test]
+fn color_filters_should_leave_greys_unchanged() {
+    for filter in ColorFilter::ALL {
+        let grey = apply(color_filter_matrix(filter), [0.5, 0.5, 0.5]);
+        for channel in grey {
+            assert!((channel - 0.5).abs() < 0.001, "{:?}", filter);
+        }
+    }
+}
+
+#[test]
+fn protanopia_simulation_should_confuse_red_and_green() {
+    let matrix = color_filter_matrix(ColorFilter::Protanopia);
+    let red = apply(matrix, [1.0, 0.0, 0.0]);
+    let green = apply(matrix, [0.0, 1.0, 0.0]);
+    // Both land on the yellow-blue axis: red and green channels move together
+    assert!((red[0] / red[1] - green[0] / green[1]).abs() < 0.5);
+}
+
+#[test]
+fn daltonization_should_move_lost_red_into_visible_channels() {
+    let red = apply(
+        color_filter_matrix(ColorFilter::DaltonizeProtanopia),
+        [1.0, 0.0, 0.0],
+    );
+    assert!(red[2] > 0.5);
+}
@@ -0,0 +1,112 @@
+use winit::keyboard::{KeyCode, ModifiersState, PhysicalKey};
+
+use crate::config::OptionAsAlt;
+
+#[cfg(test)]
+mod tests;
+
+/// Something the user can trigger from a key binding or the menu bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    OpenCommandLine,
+    ToggleLogViewer,
+    ToggleInspector,
+    ShowLinkHints,
+    ToggleDebugOverlay,
+    TakeSnapshot,
+    ToggleRecording,
+    ClearScrollback,
+    Copy,
+    Paste,
+    NewWindow,
+    NewTab,
+    CloseWindow,
+    Quit,
+    IncreaseFontSize,
+    DecreaseFontSize,
+    ResetFontSize,
+}
+
+/// A key that triggers an action when pressed with exactly `modifiers`
+#[derive(Debug, Clone, Copy)]
+pub struct KeyBinding {
+    pub modifiers: ModifiersState,
+    pub key: KeyCode,
+    pub action: Action,
+}
+
+const fn bind(modifiers: ModifiersState, key: KeyCode, action: Action) -> KeyBinding {
+    KeyBinding {
+        modifiers,
+        key,
+        action,
+    }
+}
+
+const CTRL_SHIFT: ModifiersState = ModifiersState::CONTROL.union(ModifiersState::SHIFT);
+const CMD: ModifiersState = ModifiersState::SUPER;
+const CMD_SHIFT: ModifiersState = ModifiersState::SUPER.union(ModifiersState::SHIFT);
+
+/// Bindings on every platform
+pub const BINDINGS: &[KeyBinding] = &[
+    bind(CTRL_SHIFT, KeyCode::Semicolon, Action::OpenCommandLine),
+    bind(CTRL_SHIFT, KeyCode::KeyL, Action::ToggleLogViewer),
+    bind(CTRL_SHIFT, KeyCode::KeyE, Action::ToggleInspector),
+    bind(CTRL_SHIFT, KeyCode::KeyU, Action::ShowLinkHints),
+    bind(CTRL_SHIFT, KeyCode::KeyI, Action::ToggleDebugOverlay),
+    bind(CTRL_SHIFT, KeyCode::KeyS, Action::TakeSnapshot),
+    bind(CTRL_SHIFT, KeyCode::KeyR, Action::ToggleRecording),
+    bind(CTRL_SHIFT, KeyCode::KeyK, Action::ClearScrollback),
+    bind(CTRL_SHIFT, KeyCode::KeyC, Action::Copy),
+    bind(CTRL_SHIFT, KeyCode::KeyV, Action::Paste),
+];
+
+/// Standard Cmd shortcuts on macOS. The menu bar normally handles these before the
+/// window sees them; the table covers the case where it could not be installed.
+pub const MACOS_BINDINGS: &[KeyBinding] = &[
+    bind(CMD, KeyCode::KeyC, Action::Copy),
+    bind(CMD, KeyCode::KeyV, Action::Paste),
+    bind(CMD, KeyCode::KeyN, Action::NewWindow),
+    bind(CMD, KeyCode::KeyT, Action::NewTab),
+    bind(CMD, KeyCode::KeyW, Action::CloseWindow),
+    bind(CMD, KeyCode::KeyQ, Action::Quit),
+    bind(CMD, KeyCode::Equal, Action::IncreaseFontSize),
+    // Cmd++ is typed with Shift on most layouts
+    bind(CMD_SHIFT, KeyCode::Equal, Action::IncreaseFontSize),
+    bind(CMD, KeyCode::Minus, Action::DecreaseFontSize),
+    bind(CMD, KeyCode::Digit0, Action::ResetFontSize),
+];
+
+/// The action bound to `key` pressed with `modifiers`, if any
+pub fn lookup(
+    bindings: &[KeyBinding],
+    modifiers: ModifiersState,
+    key: PhysicalKey,
+) -> Option<Action> {
+    bindings
+        .iter()
+        .find(|binding| binding.modifiers == modifiers && key == PhysicalKey::Code(binding.key))
+        .map(|binding| binding.action)
+}
+
+/// The action bound to a key on this platform
+pub fn action_for_key(modifiers: ModifiersState, key: PhysicalKey) -> Option<Action> {
+    lookup(BINDINGS, modifiers, key).or_else(|| {
+        if cfg!(target_os = "macos") {
+            lookup(MACOS_BINDINGS, modifiers, key)
+        } else {
+            None
+        }
+    })
+}
+
+/// Whether a key typed with Option held should be sent as Meta (ESC-prefixed), given
+/// which Option keys are down
+pub fn option_is_meta(setting: OptionAsAlt, left: bool, right: bool) -> bool {
+    match setting {
+        OptionAsAlt::None => false,
+        OptionAsAlt::Left => left,
+        OptionAsAlt::Right => right,
+        OptionAsAlt::Both => left || right,
+    }
+}
@@ -0,0 +1,40 @@
+use super::*;
+
+#[test]
+fn lookup_should_match_exact_modifiers() {
+    let key = PhysicalKey::Code(KeyCode::KeyL);
+    assert_eq!(
+        lookup(BINDINGS, CTRL_SHIFT, key),
+        Some(Action::ToggleLogViewer)
+    );
+    assert_eq!(lookup(BINDINGS, ModifiersState::CONTROL, key), None);
+    assert_eq!(
+        lookup(BINDINGS, CTRL_SHIFT | ModifiersState::ALT, key),
+        None
+    );
+}
+
+#[test]
+fn macos_bindings_should_cover_standard_cmd_shortcuts() {
+    let cmd = |key| lookup(MACOS_BINDINGS, CMD, PhysicalKey::Code(key));
+    assert_eq!(cmd(KeyCode::KeyC), Some(Action::Copy));
+    assert_eq!(cmd(KeyCode::KeyV), Some(Action::Paste));
+    assert_eq!(cmd(KeyCode::KeyN), Some(Action::NewWindow));
+    assert_eq!(cmd(KeyCode::KeyT), Some(Action::NewTab));
+    assert_eq!(cmd(KeyCode::KeyW), Some(Action::CloseWindow));
+    assert_eq!(cmd(KeyCode::Minus), Some(Action::DecreaseFontSize));
+    assert_eq!(
+        lookup(MACOS_BINDINGS, CMD_SHIFT, PhysicalKey::Code(KeyCode::Equal)),
+        Some(Action::IncreaseFontSize)
+    );
+}
+
+#[test]
+fn option_is_meta_should_follow_configured_side() {
+    assert!(!option_is_meta(OptionAsAlt::None, true, true));
+    assert!(option_is_meta(OptionAsAlt::Left, true, false));
+    assert!(!option_is_meta(OptionAsAlt::Left, false, true));
+    assert!(option_is_meta(OptionAsAlt::Right, false, true));
+    assert!(option_is_meta(OptionAsAlt::Both, false, true));
+    assert!(!option_is_meta(OptionAsAlt::Both, false, false));
+}
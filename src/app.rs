@@ -1,5 +1,7 @@
 use crate::commands::{ClientCommand, ServerCommand};
 use crate::config::Config;
+use crate::inspector::Inspector;
+use crate::metrics::Metrics;
 use crate::term::Term;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
@@ -18,6 +20,10 @@ pub struct ServerChannel {
 pub struct App {
     pub config: Config,
     pub is_running: Arc<AtomicBool>,
+    /// Runtime counters shared with the PTY threads and the UI
+    pub metrics: Arc<Metrics>,
+    /// Records parsed sequences for the inspector panel
+    pub inspector: Arc<Inspector>,
     pub term: Term,
     pub client_channel: ClientChannel,
     pub server_channel: ServerChannel,
@@ -47,11 +53,22 @@ impl App {
 
         let term = Term::new(&config).expect("Failed to create terminal");
 
-        term.init(is_running.clone(), &client_channel, &server_channel);
+        let metrics = Arc::new(Metrics::default());
+        let inspector = Arc::new(Inspector::new());
+
+        term.init(
+            is_running.clone(),
+            metrics.clone(),
+            inspector.clone(),
+            &client_channel,
+            &server_channel,
+        );
 
         App {
             config,
             is_running,
+            metrics,
+            inspector,
             term,
             client_channel,
             server_channel,
@@ -0,0 +1,50 @@
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod tests;
+
+/// How long the cursor stays on, then off, while blinking
+const BLINK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// On/off phase of a blinking cursor.
+///
+/// With reduce-motion the phase never leaves "on", so blinking cursors are drawn steady.
+#[derive(Debug)]
+pub struct Blink {
+    enabled: bool,
+    visible: bool,
+    changed_at: Instant,
+}
+
+impl Blink {
+    pub fn new(enabled: bool, now: Instant) -> Self {
+        Self {
+            enabled,
+            visible: true,
+            changed_at: now,
+        }
+    }
+
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Advance the phase, returning true when it flipped
+    pub fn update(&mut self, now: Instant) -> bool {
+        if !self.enabled || now.duration_since(self.changed_at) < BLINK_INTERVAL {
+            return false;
+        }
+        self.visible = !self.visible;
+        self.changed_at = now;
+        true
+    }
+
+    /// Show the cursor and restart the interval (typing keeps it visible), returning
+    /// true when it was hidden
+    pub fn reset(&mut self, now: Instant) -> bool {
+        let was_hidden = !self.visible;
+        self.visible = true;
+        self.changed_at = now;
+        was_hidden
+    }
+}
@@ -0,0 +1,32 @@
+use std::time::{Duration, Instant};
+
+use super::Blink;
+
+#[test]
+fn blink_should_toggle_after_each_interval() {
+    let start = Instant::now();
+    let mut blink = Blink::new(true, start);
+    assert!(!blink.update(start + Duration::from_millis(100)));
+    assert!(blink.update(start + Duration::from_millis(500)));
+    assert!(!blink.visible());
+    assert!(blink.update(start + Duration::from_millis(1000)));
+    assert!(blink.visible());
+}
+
+#[test]
+fn blink_should_stay_visible_with_reduce_motion() {
+    let start = Instant::now();
+    let mut blink = Blink::new(false, start);
+    assert!(!blink.update(start + Duration::from_secs(5)));
+    assert!(blink.visible());
+}
+
+#[test]
+fn reset_should_show_hidden_cursor() {
+    let start = Instant::now();
+    let mut blink = Blink::new(true, start);
+    blink.update(start + Duration::from_millis(500));
+    assert!(blink.reset(start + Duration::from_millis(600)));
+    assert!(blink.visible());
+    assert!(!blink.update(start + Duration::from_millis(1000)));
+}
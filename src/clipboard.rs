@@ -0,0 +1,205 @@
+use std::{
+    io::Write,
+    ops::Range,
+    process::{Command, Stdio},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Start of a bracketed paste
+const PASTE_START: &str = "\x1b[200~";
+
+/// End of a bracketed paste; removed from pasted text so it cannot end the paste early
+const PASTE_END: &str = "\x1b[201~";
+
+/// Largest piece of a paste handed to the PTY writer at once
+pub const PASTE_CHUNK: usize = 64 * 1024;
+
+/// The next chunk of a paste is only sent while fewer input bytes than this are waiting
+/// for the PTY
+pub const PASTE_QUEUE_LIMIT: u64 = 4 * PASTE_CHUNK as u64;
+
+/// Read the system clipboard as text, using the platform's clipboard tool
+pub fn read() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("pbpaste");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-Command", "Get-Clipboard -Raw"]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let mut command = Command::new("wl-paste");
+        command.arg("--no-newline");
+        command
+    } else {
+        let mut command = Command::new("xclip");
+        command.args(["-selection", "clipboard", "-o"]);
+        command
+    };
+
+    let output = command
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| log::warn!("Failed to read the clipboard with {:?}: {}", command, e))
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Put `text` on the system clipboard, using the platform's clipboard tool
+pub fn write(text: &str) -> bool {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("pbcopy");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-Command", "$input | Set-Clipboard"]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        Command::new("wl-copy")
+    } else {
+        let mut command = Command::new("xclip");
+        command.args(["-selection", "clipboard", "-i"]);
+        command
+    };
+
+    let result = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .and_then(|mut child| {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(text.as_bytes())?;
+            }
+            child.wait()
+        });
+    match result {
+        Ok(status) => status.success(),
+        Err(e) => {
+            log::warn!("Failed to write the clipboard with {:?}: {}", command, e);
+            false
+        }
+    }
+}
+
+/// Bytes to send to the PTY for pasted `text`.
+///
+/// Line breaks become carriage returns, as if typed. In bracketed paste mode the text is
+/// wrapped in paste markers so the program can tell it apart from typing.
+pub fn encode_paste(text: &str, bracketed: bool) -> Vec<u8> {
+    let text = text.replace("\r\n", "\r").replace('\n', "\r");
+    if bracketed {
+        format!(
+            "{}{}{}",
+            PASTE_START,
+            text.replace(PASTE_END, ""),
+            PASTE_END
+        )
+        .into_bytes()
+    } else {
+        text.into_bytes()
+    }
+}
+
+/// Whether keyboard input is plain typed text, which waits behind a pending paste so it cannot
+/// land in the middle of it. Control keys (Ctrl+C, Ctrl+Z, escape sequences) go straight through
+/// so a runaway paste can be interrupted.
+pub fn queues_behind_paste(data: &[u8]) -> bool {
+    data.iter()
+        .all(|&byte| byte >= 0x20 || matches!(byte, b'\r' | b'\t'))
+}
+
+/// An encoded paste being sent to the PTY a chunk at a time as it drains.
+///
+/// The paste is encoded as a whole, so bracketed paste markers end up at the very start
+/// and end no matter where the chunks are cut.
+#[derive(Debug)]
+pub struct PendingPaste {
+    data: Vec<u8>,
+    sent: usize,
+    /// Parts of `data` that are bracketed pastes, markers included
+    bracketed: Vec<Range<usize>>,
+}
+
+impl PendingPaste {
+    pub fn new(data: Vec<u8>) -> Self {
+        let mut paste = Self {
+            data: Vec::new(),
+            sent: 0,
+            bracketed: Vec::new(),
+        };
+        paste.append_paste(&data);
+        paste
+    }
+
+    /// Typed input to send once the paste is done, so it cannot land in the middle of it
+    pub fn append(&mut self, data: &[u8]) {
+        self.data.extend_from_slice(data);
+    }
+
+    /// Another encoded paste to send after this one
+    pub fn append_paste(&mut self, data: &[u8]) {
+        let start = self.data.len();
+        self.data.extend_from_slice(data);
+        if data.starts_with(PASTE_START.as_bytes()) {
+            self.bracketed.push(start..self.data.len());
+        }
+    }
+
+    /// Drop everything not sent yet. Returns what must still be sent to close a bracketed
+    /// paste the program has seen start, so it does not wait for the end forever.
+    pub fn cancel(&mut self) -> Vec<u8> {
+        let sent = self.sent;
+        let closing = match self
+            .bracketed
+            .iter()
+            .find(|paste| paste.start < sent && sent < paste.end)
+        {
+            // Part of the end marker is already out; finish it
+            Some(paste) if sent > paste.end - PASTE_END.len() => {
+                self.data[sent..paste.end].to_vec()
+            }
+            Some(_) => PASTE_END.as_bytes().to_vec(),
+            None => Vec::new(),
+        };
+        self.data.truncate(sent);
+        self.bracketed.clear();
+        closing
+    }
+
+    /// The next chunk to send, given how many input bytes the PTY has not accepted yet.
+    /// `None` once everything is sent or while the PTY is still busy.
+    pub fn next_chunk(&mut self, queued: u64) -> Option<Vec<u8>> {
+        if self.is_done() || queued >= PASTE_QUEUE_LIMIT {
+            return None;
+        }
+        let end = (self.sent + PASTE_CHUNK).min(self.data.len());
+        let chunk = self.data[self.sent..end].to_vec();
+        self.sent = end;
+        Some(chunk)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.sent == self.data.len()
+    }
+
+    /// Whether the paste is large enough to take a while and show progress for
+    pub fn is_large(&self) -> bool {
+        self.data.len() > PASTE_CHUNK
+    }
+
+    /// Bytes sent so far and in total
+    pub fn progress(&self) -> (usize, usize) {
+        (self.sent, self.data.len())
+    }
+}
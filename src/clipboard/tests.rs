@@ -0,0 +1,81 @@
+use super::*;
+
+#[test]
+fn encode_paste_should_send_line_breaks_as_carriage_returns() {
+    assert_eq!(encode_paste("a\nb\r\nc", false), b"a\rb\rc");
+}
+
+#[test]
+fn encode_paste_should_wrap_bracketed_paste_and_drop_end_markers() {
+    assert_eq!(
+        encode_paste("ls\x1b[201~rm", true),
+        b"\x1b[200~lsrm\x1b[201~"
+    );
+}
+
+#[test]
+fn pending_paste_should_send_everything_in_bounded_chunks() {
+    let data = encode_paste(&"x".repeat(PASTE_CHUNK * 2 + 10), true);
+    let mut paste = PendingPaste::new(data.clone());
+    let mut sent = Vec::new();
+
+    while let Some(chunk) = paste.next_chunk(0) {
+        assert!(chunk.len() <= PASTE_CHUNK);
+        sent.extend(chunk);
+    }
+
+    assert!(paste.is_done());
+    assert_eq!(sent, data);
+    assert!(sent.starts_with(b"\x1b[200~"));
+    assert!(sent.ends_with(PASTE_END.as_bytes()));
+}
+
+#[test]
+fn pending_paste_should_wait_while_the_pty_is_busy() {
+    let mut paste = PendingPaste::new(vec![b'a'; PASTE_CHUNK * 2]);
+
+    assert_eq!(paste.next_chunk(PASTE_QUEUE_LIMIT), None);
+    assert_eq!(paste.progress(), (0, PASTE_CHUNK * 2));
+    assert_eq!(
+        paste.next_chunk(0).map(|chunk| chunk.len()),
+        Some(PASTE_CHUNK)
+    );
+    assert_eq!(paste.progress(), (PASTE_CHUNK, PASTE_CHUNK * 2));
+}
+
+#[test]
+fn input_appended_during_a_paste_should_follow_it() {
+    let mut paste = PendingPaste::new(encode_paste("ls", true));
+    paste.append(b"q");
+
+    let sent = paste.next_chunk(0).unwrap();
+
+    assert_eq!(sent, b"\x1b[200~ls\x1b[201~q");
+    assert_eq!(paste.next_chunk(0), None);
+}
+
+#[test]
+fn cancelling_a_bracketed_paste_should_close_it() {
+    let mut paste = PendingPaste::new(encode_paste(&"x".repeat(PASTE_CHUNK * 2), true));
+    paste.append(b"q");
+    paste.next_chunk(0).unwrap();
+
+    assert_eq!(paste.cancel(), PASTE_END.as_bytes());
+    assert!(paste.is_done());
+    assert_eq!(paste.next_chunk(0), None);
+}
+
+#[test]
+fn cancelling_a_paste_before_it_started_should_send_nothing() {
+    let mut paste = PendingPaste::new(encode_paste("ls", true));
+
+    assert_eq!(paste.cancel(), b"");
+    assert!(paste.is_done());
+}
+
+#[test]
+fn control_keys_should_not_wait_behind_a_paste() {
+    assert!(queues_behind_paste(b"ls -l\r"));
+    assert!(!queues_behind_paste(b"\x03"));
+    assert!(!queues_behind_paste(b"\x1b[A"));
+}
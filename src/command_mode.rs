@@ -0,0 +1,188 @@
+use std::time::{Duration, Instant};
+
+use crate::config::ColorFilter;
+
+#[cfg(test)]
+mod tests;
+
+/// How long the result of a command stays on screen after the command line closes
+const MESSAGE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A command entered on the internal `:` command line
+#[derive(Debug, Clone, PartialEq)]
+pub enum InternalCommand {
+    /// `set <key> <value>`
+    Set(Setting),
+    /// `theme <name>`
+    Theme(String),
+    /// `snapshot`
+    Snapshot,
+    /// `memory`
+    Memory,
+    /// `clear_scrollback`
+    ClearScrollback,
+    /// `monitor activity on|off` / `monitor silence <seconds>|off`
+    Monitor(MonitorSetting),
+    /// `record start` / `record stop`
+    Record(RecordAction),
+    /// `latency on|off`: measure keypress-to-screen latency with probe keypresses
+    Latency(bool),
+}
+
+/// A runtime setting changed with `set`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Setting {
+    FontSize(f32),
+    HighContrast(bool),
+    ColorFilter(ColorFilter),
+}
+
+/// What `monitor` watches the terminal's output for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonitorSetting {
+    Activity(bool),
+    /// Seconds without output, or `None` to stop
+    Silence(Option<u64>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordAction {
+    Start,
+    Stop,
+}
+
+/// Parse a command line such as `set font_size 18` (a leading `:` is accepted)
+pub fn parse(line: &str) -> Result<InternalCommand, String> {
+    let line = line.trim();
+    let line = line.strip_prefix(':').unwrap_or(line);
+    let mut words = line.split_whitespace();
+    let Some(name) = words.next() else {
+        return Err("empty command".to_string());
+    };
+    let args: Vec<&str> = words.collect();
+
+    match name {
+        "set" => match args.as_slice() {
+            [key, value] => parse_setting(key, value).map(InternalCommand::Set),
+            _ => Err("usage: set <key> <value>".to_string()),
+        },
+        "theme" => match args.as_slice() {
+            [theme] => Ok(InternalCommand::Theme(theme.to_string())),
+            _ => Err("usage: theme <name>".to_string()),
+        },
+        "snapshot" if args.is_empty() => Ok(InternalCommand::Snapshot),
+        "snapshot" => Err("usage: snapshot".to_string()),
+        "memory" if args.is_empty() => Ok(InternalCommand::Memory),
+        "memory" => Err("usage: memory".to_string()),
+        "clear_scrollback" if args.is_empty() => Ok(InternalCommand::ClearScrollback),
+        "clear_scrollback" => Err("usage: clear_scrollback".to_string()),
+        "monitor" => match args.as_slice() {
+            ["activity", "on"] => Ok(InternalCommand::Monitor(MonitorSetting::Activity(true))),
+            ["activity", "off"] => Ok(InternalCommand::Monitor(MonitorSetting::Activity(false))),
+            ["silence", "off"] => Ok(InternalCommand::Monitor(MonitorSetting::Silence(None))),
+            ["silence", seconds] => match seconds.parse::<u64>() {
+                Ok(seconds) if seconds > 0 => Ok(InternalCommand::Monitor(
+                    MonitorSetting::Silence(Some(seconds)),
+                )),
+                _ => Err(format!("invalid silence: {} (seconds or off)", seconds)),
+            },
+            _ => Err("usage: monitor activity on|off | monitor silence <seconds>|off".to_string()),
+        },
+        "record" => match args.as_slice() {
+            ["start"] => Ok(InternalCommand::Record(RecordAction::Start)),
+            ["stop"] => Ok(InternalCommand::Record(RecordAction::Stop)),
+            _ => Err("usage: record start|stop".to_string()),
+        },
+        "latency" => match args.as_slice() {
+            ["on"] => Ok(InternalCommand::Latency(true)),
+            ["off"] => Ok(InternalCommand::Latency(false)),
+            _ => Err("usage: latency on|off".to_string()),
+        },
+        _ => Err(format!("unknown command: {}", name)),
+    }
+}
+
+fn parse_setting(key: &str, value: &str) -> Result<Setting, String> {
+    match key {
+        "font_size" => match value.parse::<f32>() {
+            Ok(size) if size > 0.0 => Ok(Setting::FontSize(size)),
+            _ => Err(format!("invalid font_size: {}", value)),
+        },
+        "high_contrast" => match value {
+            "on" | "true" => Ok(Setting::HighContrast(true)),
+            "off" | "false" => Ok(Setting::HighContrast(false)),
+            _ => Err(format!("invalid high_contrast: {} (on or off)", value)),
+        },
+        "color_filter" => ColorFilter::ALL
+            .into_iter()
+            .find(|filter| filter.name() == value)
+            .map(Setting::ColorFilter)
+            .ok_or_else(|| {
+                let names: Vec<_> = ColorFilter::ALL.iter().map(|f| f.name()).collect();
+                format!("invalid color_filter: {} ({})", value, names.join(", "))
+            }),
+        _ => Err(format!("unknown setting: {}", key)),
+    }
+}
+
+/// Editing state for the internal command line (opened with Ctrl+Shift+;)
+#[derive(Default)]
+pub struct CommandLine {
+    active: bool,
+    input: String,
+    /// Result of the last command and whether it was an error
+    message: Option<(String, bool, Instant)>,
+}
+
+impl CommandLine {
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Whether anything should be drawn (the prompt or a recent result message)
+    pub fn is_visible(&self) -> bool {
+        self.active || self.message().is_some()
+    }
+
+    pub fn open(&mut self) {
+        self.active = true;
+        self.input.clear();
+        self.message = None;
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+        self.input.clear();
+    }
+
+    pub fn push_str(&mut self, text: &str) {
+        self.input.push_str(text);
+    }
+
+    pub fn backspace(&mut self) {
+        self.input.pop();
+    }
+
+    /// Close the command line and return what was typed
+    pub fn submit(&mut self) -> String {
+        self.active = false;
+        std::mem::take(&mut self.input)
+    }
+
+    pub fn set_message(&mut self, message: String, is_error: bool) {
+        self.message = Some((message, is_error, Instant::now()));
+    }
+
+    /// The last result message, if it hasn't timed out yet
+    pub fn message(&self) -> Option<(&str, bool)> {
+        self.message
+            .as_ref()
+            .filter(|(_, _, shown_at)| shown_at.elapsed() < MESSAGE_TIMEOUT)
+            .map(|(message, is_error, _)| (message.as_str(), *is_error))
+    }
+
+    /// Text for the prompt line while the command line is open
+    pub fn prompt(&self) -> String {
+        format!(":{}_", self.input)
+    }
+}
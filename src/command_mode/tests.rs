@@ -0,0 +1,131 @@
+use super::{parse, CommandLine, InternalCommand, MonitorSetting, RecordAction, Setting};
+use crate::config::ColorFilter;
+
+#[test]
+fn parse_should_read_set_font_size() {
+    assert_eq!(
+        parse("set font_size 18"),
+        Ok(InternalCommand::Set(Setting::FontSize(18.0)))
+    );
+}
+
+#[test]
+fn parse_should_accept_leading_colon_and_whitespace() {
+    assert_eq!(parse("  :snapshot  "), Ok(InternalCommand::Snapshot));
+}
+
+#[test]
+fn parse_should_read_theme_and_record() {
+    assert_eq!(
+        parse("theme gruvbox"),
+        Ok(InternalCommand::Theme("gruvbox".to_string()))
+    );
+    assert_eq!(
+        parse("record start"),
+        Ok(InternalCommand::Record(RecordAction::Start))
+    );
+    assert_eq!(
+        parse("record stop"),
+        Ok(InternalCommand::Record(RecordAction::Stop))
+    );
+}
+
+#[test]
+fn parse_should_reject_invalid_input() {
+    assert!(parse("").is_err());
+    assert!(parse("frobnicate").is_err());
+    assert!(parse("set font_size").is_err());
+    assert!(parse("set font_size big").is_err());
+    assert!(parse("set font_size -4").is_err());
+    assert!(parse("set colour red").is_err());
+    assert!(parse("record pause").is_err());
+    assert!(parse("snapshot now").is_err());
+}
+
+#[test]
+fn command_line_submit_should_return_input_and_close() {
+    let mut command_line = CommandLine::default();
+    command_line.open();
+    command_line.push_str("themx");
+    command_line.backspace();
+    command_line.push_str("e nord");
+
+    assert_eq!(command_line.prompt(), ":theme nord_");
+    assert_eq!(command_line.submit(), "theme nord");
+    assert!(!command_line.is_active());
+}
+
+#[test]
+fn command_line_should_show_message_after_closing() {
+    let mut command_line = CommandLine::default();
+    command_line.open();
+    command_line.submit();
+    command_line.set_message("snapshot saved".to_string(), false);
+
+    assert!(command_line.is_visible());
+    assert_eq!(command_line.message(), Some(("snapshot saved", false)));
+}
+
+#[test]
+fn parse_should_read_high_contrast_toggle() {
+    assert_eq!(
+        parse("set high_contrast on"),
+        Ok(InternalCommand::Set(Setting::HighContrast(true)))
+    );
+    assert_eq!(
+        parse("set high_contrast off"),
+        Ok(InternalCommand::Set(Setting::HighContrast(false)))
+    );
+    assert!(parse("set high_contrast maybe").is_err());
+}
+
+#[test]
+fn parse_should_read_color_filter_by_name() {
+    assert_eq!(
+        parse("set color_filter daltonize_deuteranopia"),
+        Ok(InternalCommand::Set(Setting::ColorFilter(
+            ColorFilter::DaltonizeDeuteranopia
+        )))
+    );
+    assert!(parse("set color_filter sepia").is_err());
+}
+
+#[test]
+fn parse_should_read_memory_without_arguments() {
+    assert_eq!(parse("memory"), Ok(InternalCommand::Memory));
+    assert!(parse("memory now").is_err());
+}
+
+#[test]
+fn parse_should_read_clear_scrollback_without_arguments() {
+    assert_eq!(
+        parse("clear_scrollback"),
+        Ok(InternalCommand::ClearScrollback)
+    );
+    assert!(parse("clear_scrollback all").is_err());
+}
+
+#[test]
+fn parse_should_read_monitor_settings() {
+    assert_eq!(
+        parse("monitor activity on"),
+        Ok(InternalCommand::Monitor(MonitorSetting::Activity(true)))
+    );
+    assert_eq!(
+        parse("monitor silence 30"),
+        Ok(InternalCommand::Monitor(MonitorSetting::Silence(Some(30))))
+    );
+    assert_eq!(
+        parse("monitor silence off"),
+        Ok(InternalCommand::Monitor(MonitorSetting::Silence(None)))
+    );
+    assert!(parse("monitor silence 0").is_err());
+    assert!(parse("monitor activity").is_err());
+}
+
+#[test]
+fn parse_should_read_latency_mode() {
+    assert_eq!(parse("latency on"), Ok(InternalCommand::Latency(true)));
+    assert_eq!(parse("latency off"), Ok(InternalCommand::Latency(false)));
+    assert!(parse("latency").is_err());
+}
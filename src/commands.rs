@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use vte::ansi::{Attr, Rgb};
+use vte::ansi::{Attr, Rgb, StandardCharset};
 
 use crate::styles::{Color, CursorShape, CursorState};
 
@@ -31,6 +31,17 @@ impl From<SerializableRgb> for Rgb {
     }
 }
 
+/// Screen area for DEC rectangular operations, 1-based and inclusive.
+///
+/// Bounds past the edge of the screen are clamped when the operation is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rectangle {
+    pub top: u16,
+    pub left: u16,
+    pub bottom: u16,
+    pub right: u16,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum IdentifyTerminalMode {
     Primary,
@@ -104,17 +115,60 @@ impl SgrAttribute {
     }
 }
 
+/// Progress of a long-running command, reported with OSC 9;4 (ConEmu / Windows Terminal).
+/// Values are percentages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Progress {
+    /// Hide the progress indicator
+    None,
+    Normal(u8),
+    Error(u8),
+    /// Busy without a known completion
+    Indeterminate,
+    Paused(u8),
+}
+
+/// Shell integration marks (OSC 133, FinalTerm), delimiting prompts, commands and their output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShellMark {
+    PromptStart,
+    CommandStart,
+    /// The command was entered and its output starts
+    CommandExecuted,
+    /// The command ended, with its exit status if the shell reported one
+    CommandFinished(Option<i32>),
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ServerCommand {
     Resize(u16, u16, u16, u16),
     RawData(Vec<u8>),
 }
 
+/// Graphic character set that can be designated as G0-G3
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Charset {
+    #[default]
+    Ascii,
+    /// DEC special graphics, used for line drawing
+    LineDrawing,
+}
+
+impl Charset {
+    pub fn map(self, c: char) -> char {
+        match self {
+            Charset::Ascii => c,
+            Charset::LineDrawing => StandardCharset::SpecialCharacterAndLineDrawing.map(c),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClientCommand {
     SetTitle(Option<String>),
     AlternateScreenBuffer(bool),
     Backspace,
+    Bell,
     BracketedPasteMode(bool),
     CarriageReturn,
     CursorKeysMode(bool),
@@ -125,9 +179,23 @@ pub enum ClientCommand {
     ClearLineAfterCursor,
     ClearLineBeforeCursor,
     ClearScreen,
+    /// Erase the scrollback history (ED 3)
+    ClearScrollback,
+    /// Designate a character set as G0-G3 (index 0-3)
+    ConfigureCharset(usize, Charset),
+    /// Copy a rectangle so its top-left corner lands at (top, left)
+    CopyRectangle {
+        source: Rectangle,
+        top: u16,
+        left: u16,
+    },
+    EraseRectangle(Rectangle),
     Exit,
+    FillRectangle(char, Rectangle),
     HideCursor,
     IdentifyTerminal(IdentifyTerminalMode),
+    /// Allow (true) or forbid (false) left and right margins (DECLRMM)
+    LeftRightMarginMode(bool),
     LineFeed,
     MoveCursor(i16, i16),
     MoveCursorAbsoluteHorizontal(i16),
@@ -135,15 +203,26 @@ pub enum ClientCommand {
     MoveCursorLineVertical(i16),
     MoveCursorVertical(i16),
     MoveCursorVerticalWithCarriageReturn(i16),
+    /// Make cursor addressing relative to the scrolling region and margins (DECOM)
+    OriginMode(bool),
     NewLine,
+    /// Output was stopped (true) or restarted (false) with XOFF/XON flow control
+    OutputPaused(bool),
     Print(char),
     PutTab,
     ReportCondition(bool),
     ReportCursorPosition,
+    /// DECRQCRA checksum query, answered with the request id
+    ReportRectangleChecksum {
+        id: u16,
+        rect: Rectangle,
+    },
     ResetColor(usize),
     RestoreCursor,
     SGR(SgrAttribute),
     SaveCursor,
+    /// Invoke G0-G3 (index 0-3) as the active character set
+    SetActiveCharset(usize),
     SetColor(usize, SerializableRgb),
     ShowCursor,
     /// Enter (true) or exit (false) alternate screen with cursor save/restore
@@ -153,11 +232,19 @@ pub enum ClientCommand {
     ScrollUp(i16),
     ScrollDown(i16),
     SetScrollingRegion(usize, Option<usize>),
+    /// Left and right margins (DECSLRM), 1-indexed like the scrolling region
+    SetLeftRightMargins(usize, Option<usize>),
     ReverseIndex,
     InsertBlanks(i16),
     DeleteChars(i16),
     SetCursorState(CursorState),
     SetCursorShape(CursorShape),
+    /// Back to the configured cursor shape and blinking (DECSCUSR 0)
+    ResetCursorState,
+    SetProgress(Progress),
+    ShellMark(ShellMark),
+    /// Start (with its URI) or end an OSC 8 hyperlink
+    SetHyperlink(Option<String>),
     SetDefaultForeground(SerializableRgb),
     SetDefaultBackground(SerializableRgb),
     ReportTextAreaSizeChars,
@@ -3,30 +3,338 @@ use std::env;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::{links, styles::CursorShape};
+
+mod check;
+
+pub use check::{check, check_file, ConfigError};
+
 /// TOML configuration file structure
 #[derive(Deserialize, Default)]
 struct ConfigFile {
     window: Option<WindowConfig>,
     font: Option<FontConfig>,
     shell: Option<ShellConfig>,
+    input: Option<InputConfig>,
+    selection: Option<SelectionConfig>,
+    hints: Option<HintsConfig>,
+    cursor: Option<CursorConfig>,
+    gpu: Option<GpuConfig>,
+    shader: Option<ShaderConfig>,
+    debug: Option<DebugConfig>,
+    status_bar: Option<StatusBarConfig>,
+    accessibility: Option<AccessibilityConfig>,
+    triggers: Option<Vec<TriggerConfig>>,
+    highlights: Option<Vec<HighlightConfig>>,
 }
 
 #[derive(Deserialize)]
 struct WindowConfig {
     width: Option<f32>,
     height: Option<f32>,
+    rulers: Option<Vec<u16>>,
+    minimap: Option<bool>,
+    scroll_on_output: Option<bool>,
 }
 
 #[derive(Deserialize)]
 struct FontConfig {
     size: Option<f32>,
     family: Option<String>,
+    antialiasing: Option<Antialiasing>,
+    hinting: Option<Hinting>,
+    gamma: Option<f32>,
 }
 
 #[derive(Deserialize)]
 struct ShellConfig {
     program: Option<String>,
     args: Option<Vec<String>>,
+    flow_control: Option<bool>,
+    command_annotations: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct CursorConfig {
+    animation: Option<CursorAnimationStyle>,
+    animation_duration: Option<u64>,
+    line_highlight: Option<bool>,
+    style: Option<CursorStyle>,
+    blinking: Option<bool>,
+    thickness: Option<f32>,
+}
+
+/// Cursor shape used until a program picks one with DECSCUSR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CursorStyle {
+    Block,
+    #[default]
+    Beam,
+    Underline,
+}
+
+impl CursorStyle {
+    pub fn shape(self) -> CursorShape {
+        match self {
+            CursorStyle::Block => CursorShape::Block,
+            CursorStyle::Beam => CursorShape::Beam,
+            CursorStyle::Underline => CursorShape::Underline,
+        }
+    }
+}
+
+/// How the cursor moves between cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CursorAnimationStyle {
+    /// Jump straight to the new cell
+    #[default]
+    None,
+    /// Slide to the new cell
+    Glide,
+    /// Stretch towards the new cell, the leading edge arriving first
+    Smear,
+}
+
+#[derive(Deserialize)]
+struct InputConfig {
+    predictive_echo: Option<bool>,
+    option_as_alt: Option<OptionAsAlt>,
+}
+
+#[derive(Deserialize)]
+struct SelectionConfig {
+    copy_on_select: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct HintsConfig {
+    alphabet: Option<String>,
+}
+
+/// Which Option keys act as Alt/Meta on macOS instead of typing special characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionAsAlt {
+    #[default]
+    None,
+    Left,
+    Right,
+    Both,
+}
+
+#[derive(Deserialize)]
+struct GpuConfig {
+    backend: Option<GpuBackend>,
+    power_preference: Option<PowerPreference>,
+    adapter: Option<String>,
+    present_mode: Option<PresentMode>,
+}
+
+#[derive(Deserialize)]
+struct ShaderConfig {
+    preset: Option<ShaderPreset>,
+    path: Option<PathBuf>,
+}
+
+/// Built-in post-processing shaders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShaderPreset {
+    /// Curved screen with scanlines and a vignette
+    Crt,
+    Scanlines,
+    /// Glow around bright text
+    Bloom,
+}
+
+#[derive(Deserialize)]
+struct DebugConfig {
+    socket: Option<PathBuf>,
+    trace: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct StatusBarConfig {
+    enabled: Option<bool>,
+    position: Option<StatusBarPosition>,
+    segments: Option<Vec<StatusSegment>>,
+    git_command: Option<String>,
+    session_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AccessibilityConfig {
+    high_contrast: Option<HighContrast>,
+    color_filter: Option<ColorFilter>,
+    reduce_motion: Option<bool>,
+}
+
+/// When to use the high-contrast palette and minimum text contrast
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HighContrast {
+    #[default]
+    Off,
+    On,
+    /// Follow the desktop's increase-contrast setting, read at startup
+    System,
+}
+
+/// Color-vision deficiency filter applied to the whole frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorFilter {
+    #[default]
+    None,
+    /// Simulate missing red cones
+    Protanopia,
+    /// Simulate missing green cones
+    Deuteranopia,
+    /// Simulate missing blue cones
+    Tritanopia,
+    /// Shift colors lost to protanopia into ones that remain distinguishable
+    DaltonizeProtanopia,
+    DaltonizeDeuteranopia,
+    DaltonizeTritanopia,
+}
+
+impl ColorFilter {
+    pub const ALL: [ColorFilter; 7] = [
+        ColorFilter::None,
+        ColorFilter::Protanopia,
+        ColorFilter::Deuteranopia,
+        ColorFilter::Tritanopia,
+        ColorFilter::DaltonizeProtanopia,
+        ColorFilter::DaltonizeDeuteranopia,
+        ColorFilter::DaltonizeTritanopia,
+    ];
+
+    /// Name used in the config file and by `set color_filter`
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorFilter::None => "none",
+            ColorFilter::Protanopia => "protanopia",
+            ColorFilter::Deuteranopia => "deuteranopia",
+            ColorFilter::Tritanopia => "tritanopia",
+            ColorFilter::DaltonizeProtanopia => "daltonize_protanopia",
+            ColorFilter::DaltonizeDeuteranopia => "daltonize_deuteranopia",
+            ColorFilter::DaltonizeTritanopia => "daltonize_tritanopia",
+        }
+    }
+}
+
+/// Window edge the status bar is drawn along
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusBarPosition {
+    Top,
+    #[default]
+    Bottom,
+}
+
+/// A piece of information shown in the status bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusSegment {
+    /// Working directory of the shell
+    Cwd,
+    /// Output of the git branch command, run in the shell's working directory
+    GitBranch,
+    /// Local time
+    Time,
+    /// Session name, or the window title when no name is configured
+    Session,
+    /// Shown briefly after the terminal bell rings
+    Bell,
+}
+
+/// A regex matched against each completed output line
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TriggerConfig {
+    pub pattern: String,
+    #[serde(flatten)]
+    pub action: TriggerAction,
+}
+
+/// What a trigger does when its pattern matches
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum TriggerAction {
+    /// Recolor the background of the matching line
+    Highlight { color: (u8, u8, u8) },
+    /// Show a desktop notification containing the line
+    Notify,
+    /// Play a sound file, or the system alert sound when unset
+    Sound { file: Option<PathBuf> },
+    /// Run a shell command with the line in `MTTY_TRIGGER_LINE`
+    Command { command: String },
+}
+
+/// A regex whose matches are drawn in `color` without changing the grid
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct HighlightConfig {
+    pub pattern: String,
+    pub color: (u8, u8, u8),
+}
+
+/// Graphics API used by wgpu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum GpuBackend {
+    /// Let wgpu pick (Vulkan/GL only on WSL2)
+    #[default]
+    Auto,
+    Vulkan,
+    Metal,
+    Dx12,
+    Gl,
+}
+
+/// Which GPU to prefer when several adapters are available
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerPreference {
+    /// Integrated / low power GPU
+    #[default]
+    Low,
+    /// Discrete / high performance GPU
+    High,
+    /// No preference
+    None,
+}
+
+/// Frame presentation strategy (trades tearing for input latency)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PresentMode {
+    /// Wait for vertical blank, never tears (FIFO)
+    #[default]
+    Vsync,
+    /// Replace queued frames without tearing, lower latency where supported
+    Mailbox,
+    /// Present as soon as possible, may tear
+    Immediate,
+}
+
+/// How glyph edges are smoothed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Antialiasing {
+    #[default]
+    Grayscale,
+    Subpixel,
+}
+
+/// How strongly glyph outlines are snapped to the pixel grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Hinting {
+    None,
+    Slight,
+    #[default]
+    Full,
 }
 
 /// Runtime configuration
@@ -34,12 +342,74 @@ struct ShellConfig {
 pub struct Config {
     pub width: f32,
     pub height: f32,
+    /// Columns after which a faint vertical guide is drawn behind the text
+    pub rulers: Vec<u16>,
+    /// Overview of the whole buffer along the right edge, dragged to scroll
+    pub minimap: bool,
+    /// Jump back to the bottom when output arrives while scrolled into the scrollback
+    pub scroll_on_output: bool,
     pub font_size: f32,
     pub font_family: Option<String>,
+    pub antialiasing: Antialiasing,
+    pub hinting: Hinting,
+    /// Gamma applied to glyph colors (1.0 = unchanged)
+    pub font_gamma: f32,
     pub rows: u16,
     pub cols: u16,
     pub shell: String,
     pub shell_args: Vec<String>,
+    /// Let Ctrl+S and Ctrl+Q pause and resume output (XON/XOFF) instead of passing them through
+    pub flow_control: bool,
+    /// Show each command's duration and exit status at the end of its prompt line, using
+    /// the shell's OSC 133 marks
+    pub command_annotations: bool,
+    /// Echo typed characters locally before the shell does, for high-latency sessions
+    pub predictive_echo: bool,
+    /// Option keys that send Escape-prefixed keys on macOS
+    pub option_as_alt: OptionAsAlt,
+    /// Copy text to the clipboard as soon as a mouse selection is finished
+    pub copy_on_select: bool,
+    /// Keys used for the labels in link hint mode
+    pub hint_alphabet: String,
+    pub cursor_animation: CursorAnimationStyle,
+    /// Length of a cursor animation in milliseconds
+    pub cursor_animation_duration: u64,
+    /// Shade the background of the cursor's row on the main screen
+    pub cursor_line_highlight: bool,
+    /// Default cursor shape and blinking, until a program changes them
+    pub cursor_style: CursorStyle,
+    pub cursor_blinking: bool,
+    /// Width of the beam and height of the underline cursor in pixels (None = automatic)
+    pub cursor_thickness: Option<f32>,
+    pub gpu_backend: GpuBackend,
+    pub power_preference: PowerPreference,
+    /// Case-insensitive substring of the adapter name to use
+    pub adapter_name: Option<String>,
+    pub present_mode: PresentMode,
+    pub shader_preset: Option<ShaderPreset>,
+    /// WGSL post-processing shader applied to every frame, reloaded when the file changes
+    pub shader_path: Option<PathBuf>,
+    /// Path of the Unix debug socket (disabled when unset)
+    pub debug_socket: Option<PathBuf>,
+    /// Write a chrome://tracing compatible trace to this file (disabled when unset)
+    pub trace_file: Option<PathBuf>,
+    /// Output triggers, checked in order for every completed line
+    pub triggers: Vec<TriggerConfig>,
+    /// Render-time recoloring rules, earlier rules win on overlap
+    pub highlights: Vec<HighlightConfig>,
+    /// Reserve one row outside the grid for the status bar
+    pub status_bar: bool,
+    pub status_bar_position: StatusBarPosition,
+    pub status_bar_segments: Vec<StatusSegment>,
+    /// Shell command printing the current git branch
+    pub git_branch_command: String,
+    pub session_name: Option<String>,
+    /// High-contrast palette and minimum text contrast
+    pub high_contrast: HighContrast,
+    /// Color-blindness simulation or daltonization post-process
+    pub color_filter: ColorFilter,
+    /// Draw blinking cursors steady and skip animations
+    pub reduce_motion: bool,
 }
 
 impl Default for Config {
@@ -67,12 +437,54 @@ impl Default for Config {
         Self {
             width: WIDTH,
             height: HEIGHT,
+            rulers: Vec::new(),
+            minimap: false,
+            scroll_on_output: true,
             font_size: FONT_SIZE,
             font_family: None, // Use system monospace font by default
+            antialiasing: Antialiasing::default(),
+            hinting: Hinting::default(),
+            font_gamma: 1.0,
             rows,
             cols,
             shell,
             shell_args: vec!["-l".to_string()], // Login shell by default
+            flow_control: false,
+            command_annotations: true,
+            predictive_echo: false,
+            option_as_alt: OptionAsAlt::default(),
+            copy_on_select: false,
+            hint_alphabet: links::DEFAULT_HINT_ALPHABET.to_string(),
+            cursor_animation: CursorAnimationStyle::default(),
+            cursor_animation_duration: 80,
+            cursor_line_highlight: false,
+            cursor_style: CursorStyle::default(),
+            cursor_blinking: false,
+            cursor_thickness: None,
+            gpu_backend: GpuBackend::default(),
+            power_preference: PowerPreference::default(),
+            adapter_name: None,
+            present_mode: PresentMode::default(),
+            shader_preset: None,
+            shader_path: None,
+            debug_socket: None,
+            trace_file: None,
+            triggers: Vec::new(),
+            highlights: Vec::new(),
+            status_bar: false,
+            status_bar_position: StatusBarPosition::default(),
+            status_bar_segments: vec![
+                StatusSegment::Session,
+                StatusSegment::Cwd,
+                StatusSegment::GitBranch,
+                StatusSegment::Bell,
+                StatusSegment::Time,
+            ],
+            git_branch_command: "git rev-parse --abbrev-ref HEAD".to_string(),
+            session_name: None,
+            high_contrast: HighContrast::default(),
+            color_filter: ColorFilter::default(),
+            reduce_motion: false,
         }
     }
 }
@@ -106,22 +518,9 @@ impl Config {
         config
     }
 
-    /// Get the config file path (~/.config/mtty/config.toml)
-    fn config_path() -> Option<PathBuf> {
-        // first try to get from XDG_CONFIG_HOME
-        if let Ok(xdg_config_home) = env::var("XDG_CONFIG_HOME") {
-            let mut path = PathBuf::from(xdg_config_home);
-            path.push("mtty");
-            path.push("config.toml");
-            return Some(path);
-        }
-
-        // fallback to ~/.config
-        dirs::config_dir().map(|mut path| {
-            path.push("mtty");
-            path.push("config.toml");
-            path
-        })
+    /// Get the config file path (see `paths::config_dir`)
+    pub fn config_path() -> Option<PathBuf> {
+        crate::paths::config_file()
     }
 
     /// Apply settings from the config file
@@ -134,6 +533,15 @@ impl Config {
             if let Some(height) = window.height {
                 self.height = height;
             }
+            if let Some(rulers) = window.rulers {
+                self.rulers = rulers;
+            }
+            if let Some(minimap) = window.minimap {
+                self.minimap = minimap;
+            }
+            if let Some(scroll_on_output) = window.scroll_on_output {
+                self.scroll_on_output = scroll_on_output;
+            }
         }
 
         // Font settings
@@ -144,6 +552,19 @@ impl Config {
             if let Some(family) = font.family {
                 self.font_family = Some(family);
             }
+            if let Some(antialiasing) = font.antialiasing {
+                self.antialiasing = antialiasing;
+            }
+            if let Some(hinting) = font.hinting {
+                self.hinting = hinting;
+            }
+            if let Some(gamma) = font.gamma {
+                if gamma > 0.0 {
+                    self.font_gamma = gamma;
+                } else {
+                    log::warn!("Ignoring invalid font gamma {}, must be positive", gamma);
+                }
+            }
         }
 
         // Shell settings
@@ -154,6 +575,133 @@ impl Config {
             if let Some(args) = shell.args {
                 self.shell_args = args;
             }
+            if let Some(flow_control) = shell.flow_control {
+                self.flow_control = flow_control;
+            }
+            if let Some(command_annotations) = shell.command_annotations {
+                self.command_annotations = command_annotations;
+            }
+        }
+
+        // Input settings
+        if let Some(input) = file_config.input {
+            if let Some(predictive_echo) = input.predictive_echo {
+                self.predictive_echo = predictive_echo;
+            }
+            if let Some(option_as_alt) = input.option_as_alt {
+                self.option_as_alt = option_as_alt;
+            }
+        }
+
+        // Selection settings
+        if let Some(selection) = file_config.selection {
+            if let Some(copy_on_select) = selection.copy_on_select {
+                self.copy_on_select = copy_on_select;
+            }
+        }
+
+        // Link hint settings
+        if let Some(hints) = file_config.hints {
+            if let Some(alphabet) = hints.alphabet {
+                self.hint_alphabet = alphabet;
+            }
+        }
+
+        // Post-processing shader settings
+        if let Some(shader) = file_config.shader {
+            if let Some(preset) = shader.preset {
+                self.shader_preset = Some(preset);
+            }
+            if let Some(path) = shader.path {
+                self.shader_path = Some(path);
+            }
+        }
+
+        // Cursor settings
+        if let Some(cursor) = file_config.cursor {
+            if let Some(animation) = cursor.animation {
+                self.cursor_animation = animation;
+            }
+            if let Some(duration) = cursor.animation_duration {
+                self.cursor_animation_duration = duration;
+            }
+            if let Some(line_highlight) = cursor.line_highlight {
+                self.cursor_line_highlight = line_highlight;
+            }
+            if let Some(style) = cursor.style {
+                self.cursor_style = style;
+            }
+            if let Some(blinking) = cursor.blinking {
+                self.cursor_blinking = blinking;
+            }
+            if let Some(thickness) = cursor.thickness {
+                self.cursor_thickness = Some(thickness.max(1.0));
+            }
+        }
+
+        // GPU settings
+        if let Some(gpu) = file_config.gpu {
+            if let Some(backend) = gpu.backend {
+                self.gpu_backend = backend;
+            }
+            if let Some(power_preference) = gpu.power_preference {
+                self.power_preference = power_preference;
+            }
+            if let Some(adapter) = gpu.adapter {
+                self.adapter_name = Some(adapter);
+            }
+            if let Some(present_mode) = gpu.present_mode {
+                self.present_mode = present_mode;
+            }
+        }
+
+        // Debug settings
+        if let Some(debug) = file_config.debug {
+            if let Some(socket) = debug.socket {
+                self.debug_socket = Some(socket);
+            }
+            if let Some(trace) = debug.trace {
+                self.trace_file = Some(trace);
+            }
+        }
+
+        // Accessibility settings
+        if let Some(accessibility) = file_config.accessibility {
+            if let Some(high_contrast) = accessibility.high_contrast {
+                self.high_contrast = high_contrast;
+            }
+            if let Some(color_filter) = accessibility.color_filter {
+                self.color_filter = color_filter;
+            }
+            if let Some(reduce_motion) = accessibility.reduce_motion {
+                self.reduce_motion = reduce_motion;
+            }
+        }
+
+        // Status bar settings
+        if let Some(status_bar) = file_config.status_bar {
+            if let Some(enabled) = status_bar.enabled {
+                self.status_bar = enabled;
+            }
+            if let Some(position) = status_bar.position {
+                self.status_bar_position = position;
+            }
+            if let Some(segments) = status_bar.segments {
+                self.status_bar_segments = segments;
+            }
+            if let Some(git_command) = status_bar.git_command {
+                self.git_branch_command = git_command;
+            }
+            if let Some(session_name) = status_bar.session_name {
+                self.session_name = Some(session_name);
+            }
+        }
+
+        if let Some(triggers) = file_config.triggers {
+            self.triggers = triggers;
+        }
+        if let Some(highlights) = file_config.highlights {
+            self.highlights = highlights;
         }
 
         // Recalculate rows/cols based on updated dimensions
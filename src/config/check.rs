@@ -0,0 +1,155 @@
+use std::{fmt, fs, ops::Range, path::Path};
+
+use regex::Regex;
+use serde::Deserialize;
+use toml::Spanned;
+
+use super::ConfigFile;
+
+#[cfg(test)]
+mod tests;
+
+/// A problem found in the config file, located by line and column (both 1-based)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// The values that need checks beyond deserialization, with their positions in the file
+#[derive(Deserialize)]
+struct Located {
+    font: Option<LocatedFont>,
+    shader: Option<LocatedShader>,
+    #[serde(default)]
+    triggers: Vec<LocatedPattern>,
+    #[serde(default)]
+    highlights: Vec<LocatedPattern>,
+}
+
+#[derive(Deserialize)]
+struct LocatedFont {
+    family: Option<Spanned<String>>,
+    size: Option<Spanned<f32>>,
+    gamma: Option<Spanned<f32>>,
+}
+
+#[derive(Deserialize)]
+struct LocatedShader {
+    path: Option<Spanned<String>>,
+}
+
+#[derive(Deserialize)]
+struct LocatedPattern {
+    pattern: Spanned<String>,
+    file: Option<Spanned<String>>,
+}
+
+/// Check the config file at `path`, using the system font database for font names
+pub fn check_file(path: &Path) -> Result<(), Vec<ConfigError>> {
+    let contents = fs::read_to_string(path).map_err(|e| {
+        vec![ConfigError {
+            line: 0,
+            column: 0,
+            message: format!("failed to read {}: {}", path.display(), e),
+        }]
+    })?;
+    let font_system = crate::fonts::font_system();
+    check(&contents, |family| {
+        crate::fonts::has_family(&font_system, family)
+    })
+}
+
+/// Check config file contents: syntax and types, then font names, numbers, patterns and
+/// referenced files. `font_exists` tells whether a font family is installed.
+pub fn check(contents: &str, font_exists: impl Fn(&str) -> bool) -> Result<(), Vec<ConfigError>> {
+    let at = |span: Range<usize>, message: String| {
+        let (line, column) = position(contents, span.start);
+        ConfigError {
+            line,
+            column,
+            message,
+        }
+    };
+
+    if let Err(e) = toml::from_str::<ConfigFile>(contents) {
+        let message = e.message().to_string();
+        return Err(vec![at(e.span().unwrap_or(0..0), message)]);
+    }
+    let located: Located = toml::from_str(contents)
+        .map_err(|e| vec![at(e.span().unwrap_or(0..0), e.message().to_string())])?;
+
+    let mut errors = Vec::new();
+    if let Some(font) = &located.font {
+        if let Some(family) = &font.family {
+            if !font_exists(family.get_ref()) {
+                errors.push(at(
+                    family.span(),
+                    format!("font family {:?} is not installed", family.get_ref()),
+                ));
+            }
+        }
+        for (name, value) in [("size", &font.size), ("gamma", &font.gamma)] {
+            if let Some(value) = value.as_ref().filter(|value| *value.get_ref() <= 0.0) {
+                errors.push(at(
+                    value.span(),
+                    format!("font {} must be positive, got {}", name, value.get_ref()),
+                ));
+            }
+        }
+    }
+
+    let patterns = located.triggers.iter().chain(&located.highlights);
+    for rule in patterns {
+        if let Err(e) = Regex::new(rule.pattern.get_ref()) {
+            // Regex errors draw a diagram above the actual message
+            let reason = e.to_string();
+            let reason = reason.lines().last().unwrap_or_default().trim();
+            let reason = reason.strip_prefix("error: ").unwrap_or(reason);
+            errors.push(at(
+                rule.pattern.span(),
+                format!("invalid pattern {:?}: {}", rule.pattern.get_ref(), reason),
+            ));
+        }
+    }
+
+    let files = located
+        .triggers
+        .iter()
+        .filter_map(|trigger| trigger.file.as_ref())
+        .chain(
+            located
+                .shader
+                .as_ref()
+                .and_then(|shader| shader.path.as_ref()),
+        );
+    for file in files {
+        if !Path::new(file.get_ref()).is_file() {
+            errors.push(at(
+                file.span(),
+                format!("file {:?} does not exist", file.get_ref()),
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// 1-based line and column of a byte offset
+fn position(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset.min(contents.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+    (line, column)
+}
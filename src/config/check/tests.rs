@@ -0,0 +1,48 @@
+use super::*;
+
+fn check_with_fonts(contents: &str) -> Result<(), Vec<ConfigError>> {
+    check(contents, |family| family == "Fira Code")
+}
+
+#[test]
+fn check_should_accept_valid_config() {
+    let contents = "[font]\nfamily = \"Fira Code\"\nsize = 14.0\n\n[[highlights]]\npattern = \"\\\\d+\"\ncolor = [1, 2, 3]\n";
+    assert_eq!(check_with_fonts(contents), Ok(()));
+}
+
+#[test]
+fn check_should_report_syntax_error_line() {
+    let errors = check_with_fonts("[font]\nsize = 14.0\nfamily = \n").unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 3);
+}
+
+#[test]
+fn check_should_report_invalid_color() {
+    let contents = "[[highlights]]\npattern = \"x\"\ncolor = [255, 255]\n";
+    let errors = check_with_fonts(contents).unwrap_err();
+    assert_eq!(errors[0].line, 3);
+}
+
+#[test]
+fn check_should_report_missing_font() {
+    let errors = check_with_fonts("\n[font]\nfamily = \"Nope Sans\"\n").unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!((errors[0].line, errors[0].column), (3, 10));
+    assert!(errors[0].message.contains("Nope Sans"));
+}
+
+#[test]
+fn check_should_report_every_invalid_pattern() {
+    let contents = "[[triggers]]\npattern = \"(abc\"\naction = \"notify\"\n\n[[highlights]]\npattern = \"[z-a]\"\ncolor = [1, 2, 3]\n";
+    let errors = check_with_fonts(contents).unwrap_err();
+    let lines: Vec<_> = errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines, vec![2, 6]);
+    assert!(!errors[0].message.contains('\n'));
+}
+
+#[test]
+fn check_should_report_non_positive_font_size() {
+    let errors = check_with_fonts("[font]\nsize = 0.0\n").unwrap_err();
+    assert_eq!(errors[0].line, 2);
+}
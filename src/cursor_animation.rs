@@ -0,0 +1,103 @@
+use std::time::{Duration, Instant};
+
+use crate::config::CursorAnimationStyle;
+
+#[cfg(test)]
+mod tests;
+
+/// Share of the animation the leading edge of a smear takes to arrive
+const SMEAR_LEAD: f32 = 0.5;
+
+/// A point in cell units: (column, row)
+pub type Point = (f32, f32);
+
+/// Animates the cursor quad between cells.
+///
+/// Positions are in cell units relative to the top-left of the grid. Corners are given as
+/// offsets within the cell (so beam and underline cursors animate their own shape) and
+/// returned top-left, top-right, bottom-right, bottom-left.
+#[derive(Debug)]
+pub struct CursorAnimation {
+    style: CursorAnimationStyle,
+    duration: Duration,
+    from: Point,
+    to: Point,
+    started: Instant,
+}
+
+impl CursorAnimation {
+    pub fn new(style: CursorAnimationStyle, duration: Duration, now: Instant) -> Self {
+        Self {
+            style,
+            duration,
+            from: (0.0, 0.0),
+            to: (0.0, 0.0),
+            started: now,
+        }
+    }
+
+    /// Head for `target`, starting from wherever the cursor is drawn now
+    pub fn move_to(&mut self, target: Point, now: Instant) {
+        if target == self.to {
+            return;
+        }
+        self.from = lerp(self.from, self.to, ease_out(self.progress(now)));
+        self.to = target;
+        self.started = now;
+    }
+
+    /// Place the cursor at `target` without animating (e.g. after scrolling or a resize)
+    pub fn jump_to(&mut self, target: Point) {
+        self.from = target;
+        self.to = target;
+    }
+
+    pub fn is_animating(&self, now: Instant) -> bool {
+        self.from != self.to && self.progress(now) < 1.0
+    }
+
+    /// Corners of the cursor quad at `now`
+    pub fn corners(&self, offsets: [Point; 4], now: Instant) -> [Point; 4] {
+        let t = self.progress(now);
+        let motion = (self.to.0 - self.from.0, self.to.1 - self.from.1);
+        let length = (motion.0 * motion.0 + motion.1 * motion.1).sqrt();
+        let center = (
+            offsets.iter().map(|o| o.0).sum::<f32>() / 4.0,
+            offsets.iter().map(|o| o.1).sum::<f32>() / 4.0,
+        );
+
+        offsets.map(|offset| {
+            let progress = match self.style {
+                CursorAnimationStyle::None => 1.0,
+                CursorAnimationStyle::Glide => ease_out(t),
+                CursorAnimationStyle::Smear if length > 0.0 => {
+                    // Corners facing the direction of travel lead, those behind trail
+                    let (dx, dy) = (offset.0 - center.0, offset.1 - center.1);
+                    let facing = (dx * motion.0 + dy * motion.1) / length;
+                    let share = if facing > 0.0 { SMEAR_LEAD } else { 1.0 };
+                    ease_out((t / share).min(1.0))
+                }
+                CursorAnimationStyle::Smear => 1.0,
+            };
+            let (x, y) = lerp(self.from, self.to, progress);
+            (x + offset.0, y + offset.1)
+        })
+    }
+
+    /// Fraction of the current animation that has elapsed, from 0.0 to 1.0
+    fn progress(&self, now: Instant) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        (now.duration_since(self.started).as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+}
+
+fn lerp(from: Point, to: Point, t: f32) -> Point {
+    (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t)
+}
+
+/// Cubic ease-out: fast start, gentle arrival
+fn ease_out(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(3)
+}
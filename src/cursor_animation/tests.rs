@@ -0,0 +1,51 @@
+use std::time::{Duration, Instant};
+
+use super::CursorAnimation;
+use crate::config::CursorAnimationStyle;
+
+const CELL: [(f32, f32); 4] = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+
+fn animation(style: CursorAnimationStyle, start: Instant) -> CursorAnimation {
+    let mut animation = CursorAnimation::new(style, Duration::from_millis(100), start);
+    animation.jump_to((0.0, 0.0));
+    animation.move_to((10.0, 0.0), start);
+    animation
+}
+
+#[test]
+fn glide_should_arrive_after_duration() {
+    let start = Instant::now();
+    let glide = animation(CursorAnimationStyle::Glide, start);
+
+    let halfway = glide.corners(CELL, start + Duration::from_millis(50));
+    assert!(halfway[0].0 > 0.0 && halfway[0].0 < 10.0);
+    assert!(glide.is_animating(start + Duration::from_millis(50)));
+
+    let end = start + Duration::from_millis(100);
+    assert_eq!(glide.corners(CELL, end)[0], (10.0, 0.0));
+    assert!(!glide.is_animating(end));
+}
+
+#[test]
+fn smear_should_stretch_leading_edge_ahead() {
+    let start = Instant::now();
+    let smear = animation(CursorAnimationStyle::Smear, start);
+
+    let [top_left, top_right, ..] = smear.corners(CELL, start + Duration::from_millis(30));
+    // Moving right: the right edge leads, so the quad is wider than a cell
+    assert!(top_right.0 - top_left.0 > 1.0);
+
+    let [top_left, top_right, ..] = smear.corners(CELL, start + Duration::from_millis(100));
+    assert_eq!((top_left.0, top_right.0), (10.0, 11.0));
+}
+
+#[test]
+fn move_should_continue_from_drawn_position() {
+    let start = Instant::now();
+    let mut glide = animation(CursorAnimationStyle::Glide, start);
+
+    let retarget = start + Duration::from_millis(50);
+    let drawn = glide.corners(CELL, retarget)[0];
+    glide.move_to((0.0, 5.0), retarget);
+    assert_eq!(glide.corners(CELL, retarget)[0], drawn);
+}
@@ -0,0 +1,194 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use serde::Serialize;
+
+use crate::{grid::Grid, metrics::Metrics, snapshot::TerminalSnapshot};
+
+#[cfg(test)]
+mod tests;
+
+/// How long a connection waits for the UI thread to answer before giving up
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A request line sent to the debug socket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugRequest {
+    /// Full grid snapshot, in the same JSON format as snapshot files
+    Snapshot,
+    /// Dirty-row tracking state
+    Dirty,
+    /// Terminal and UI mode flags
+    Modes,
+    /// Runtime counters
+    Metrics,
+}
+
+impl DebugRequest {
+    pub fn parse(line: &str) -> Option<Self> {
+        match line.trim() {
+            "snapshot" => Some(Self::Snapshot),
+            "dirty" => Some(Self::Dirty),
+            "modes" => Some(Self::Modes),
+            "metrics" => Some(Self::Metrics),
+            _ => None,
+        }
+    }
+}
+
+/// A request waiting to be answered by the UI thread, which owns the grid
+pub struct DebugQuery {
+    pub request: DebugRequest,
+    reply: Sender<String>,
+}
+
+impl DebugQuery {
+    pub fn respond(self, response: String) {
+        // The connection may have timed out and gone away; nothing to do then
+        let _ = self.reply.send(response);
+    }
+}
+
+/// Mode flags reported by the `modes` request
+#[derive(Debug, Clone, Serialize)]
+pub struct ModeFlags {
+    pub cursor_keys_application: bool,
+    pub bracketed_paste: bool,
+    pub alternate_screen: bool,
+    pub cursor_hidden: bool,
+    pub recording: bool,
+    pub replay: bool,
+}
+
+#[derive(Serialize)]
+struct DirtyState {
+    dirty: bool,
+    dirty_row_count: usize,
+    dirty_rows: Vec<usize>,
+}
+
+/// Build the JSON response for `request` from the current UI state
+pub fn response(
+    request: DebugRequest,
+    grid: &Grid,
+    modes: &ModeFlags,
+    metrics: &Metrics,
+) -> String {
+    let result = match request {
+        DebugRequest::Snapshot => serde_json::to_string(&TerminalSnapshot::from_grid(grid)),
+        DebugRequest::Dirty => serde_json::to_string(&DirtyState {
+            dirty: grid.is_dirty(),
+            dirty_row_count: grid.dirty_row_count(),
+            dirty_rows: grid
+                .dirty_rows()
+                .iter()
+                .enumerate()
+                .filter(|(_, dirty)| **dirty)
+                .map(|(row, _)| row)
+                .collect(),
+        }),
+        DebugRequest::Modes => serde_json::to_string(modes),
+        DebugRequest::Metrics => serde_json::to_string(&metrics.snapshot()),
+    };
+    result.unwrap_or_else(|e| error_response(&e.to_string()))
+}
+
+fn error_response(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+/// Unix-socket server answering one JSON line per request line
+/// (`snapshot`, `dirty`, `modes`, `metrics`).
+///
+/// Requests are forwarded to the UI thread, which drains them with `try_recv`.
+/// The socket file is removed when the server is dropped.
+pub struct DebugServer {
+    path: PathBuf,
+    queries: Receiver<DebugQuery>,
+}
+
+impl DebugServer {
+    pub fn bind(path: &Path) -> io::Result<Self> {
+        // Remove a stale socket left behind by a previous instance
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        let (tx, queries) = mpsc::channel();
+
+        thread::Builder::new()
+            .name("debug-socket".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    match stream {
+                        Ok(stream) => {
+                            let tx = tx.clone();
+                            thread::spawn(move || handle_connection(stream, tx));
+                        }
+                        Err(e) => log::warn!("Debug socket accept failed: {}", e),
+                    }
+                }
+            })?;
+
+        log::info!("Debug socket listening on {:?}", path);
+        Ok(Self {
+            path: path.to_path_buf(),
+            queries,
+        })
+    }
+
+    /// Next pending request, if any
+    pub fn try_recv(&self) -> Option<DebugQuery> {
+        self.queries.try_recv().ok()
+    }
+}
+
+impl Drop for DebugServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn handle_connection(stream: UnixStream, queries: Sender<DebugQuery>) {
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(e) => {
+            log::warn!("Debug socket connection failed: {}", e);
+            return;
+        }
+    };
+
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match DebugRequest::parse(&line) {
+            Some(request) => {
+                let (reply, response) = mpsc::channel();
+                if queries.send(DebugQuery { request, reply }).is_err() {
+                    // UI has shut down
+                    break;
+                }
+                response
+                    .recv_timeout(REPLY_TIMEOUT)
+                    .unwrap_or_else(|_| error_response("timed out waiting for the UI thread"))
+            }
+            None => error_response(&format!("unknown request: {}", line.trim())),
+        };
+
+        if writeln!(writer, "{}", response).is_err() {
+            break;
+        }
+    }
+}
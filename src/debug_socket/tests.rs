@@ -0,0 +1,120 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    os::unix::net::UnixStream,
+};
+
+use crate::{config::Config, grid::Grid, metrics::Metrics};
+
+use super::{response, DebugRequest, DebugServer, ModeFlags};
+
+fn modes() -> ModeFlags {
+    ModeFlags {
+        cursor_keys_application: true,
+        bracketed_paste: false,
+        alternate_screen: false,
+        cursor_hidden: false,
+        recording: false,
+        replay: false,
+    }
+}
+
+#[test]
+fn parse_should_accept_known_requests() {
+    assert_eq!(
+        DebugRequest::parse("snapshot\n"),
+        Some(DebugRequest::Snapshot)
+    );
+    assert_eq!(DebugRequest::parse(" dirty"), Some(DebugRequest::Dirty));
+    assert_eq!(DebugRequest::parse("modes"), Some(DebugRequest::Modes));
+    assert_eq!(DebugRequest::parse("metrics"), Some(DebugRequest::Metrics));
+    assert_eq!(DebugRequest::parse("reboot"), None);
+}
+
+#[test]
+fn dirty_response_should_list_dirty_rows() {
+    let mut grid = Grid::new(&Config::default());
+    grid.clear_dirty();
+    grid.set_pos(2, 0);
+    grid.place_character_in_grid(grid.width, 'x');
+
+    let json: serde_json::Value = serde_json::from_str(&response(
+        DebugRequest::Dirty,
+        &grid,
+        &modes(),
+        &Metrics::default(),
+    ))
+    .unwrap();
+
+    assert_eq!(json["dirty"], true);
+    assert!(json["dirty_rows"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!(2)));
+}
+
+#[test]
+fn snapshot_response_should_match_grid() {
+    let grid = Grid::new(&Config::default());
+
+    let json: serde_json::Value = serde_json::from_str(&response(
+        DebugRequest::Snapshot,
+        &grid,
+        &modes(),
+        &Metrics::default(),
+    ))
+    .unwrap();
+
+    assert_eq!(json["width"], grid.width);
+    assert_eq!(json["height"], grid.height);
+}
+
+#[test]
+fn server_should_forward_requests_and_return_replies() {
+    let path = std::env::temp_dir().join(format!("mtty-debug-test-{}.sock", std::process::id()));
+    let server = DebugServer::bind(&path).unwrap();
+
+    let client = std::thread::spawn({
+        let path = path.clone();
+        move || {
+            let mut stream = UnixStream::connect(path).unwrap();
+            stream.write_all(b"modes\nbogus\n").unwrap();
+            let mut lines = BufReader::new(stream).lines();
+            let modes = lines.next().unwrap().unwrap();
+            let bogus = lines.next().unwrap().unwrap();
+            (modes, bogus)
+        }
+    });
+
+    let query = loop {
+        if let Some(query) = server.try_recv() {
+            break query;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    };
+    let request = query.request;
+    assert_eq!(request, DebugRequest::Modes);
+    let grid = Grid::new(&Config::default());
+    query.respond(response(request, &grid, &modes(), &Metrics::default()));
+
+    let (modes, bogus) = client.join().unwrap();
+    assert!(modes.contains("\"cursor_keys_application\":true"));
+    assert!(bogus.contains("unknown request"));
+
+    drop(server);
+    assert!(!path.exists());
+}
+
+#[test]
+fn metrics_response_should_report_counters() {
+    let grid = Grid::new(&Config::default());
+    let metrics = Metrics::default();
+    metrics.add_bytes_read(42);
+    metrics.add_dropped_messages(3);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&response(DebugRequest::Metrics, &grid, &modes(), &metrics)).unwrap();
+
+    assert_eq!(json["bytes_read"], 42);
+    assert_eq!(json["dropped_messages"], 3);
+    assert_eq!(json["frames_rendered"], 0);
+}
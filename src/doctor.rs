@@ -0,0 +1,318 @@
+use std::{
+    env,
+    fmt::{self, Write},
+    path::PathBuf,
+};
+
+use wgpu::RequestAdapterOptions;
+
+use crate::{
+    config::{self, Config},
+    fonts,
+    gpu::{self, is_wsl2},
+    paths,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Terminal type the shell is started with (see `term.rs`)
+const TERM: &str = "xterm-256color";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Status::Ok => "ok",
+            Status::Warning => "warn",
+            Status::Error => "error",
+        })
+    }
+}
+
+/// One line of the `mtty doctor` report, with a hint on how to fix it when it is not ok
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: String) -> Self {
+        Self {
+            name,
+            status: Status::Ok,
+            detail,
+            hint: None,
+        }
+    }
+
+    fn problem(name: &'static str, status: Status, detail: String, hint: &str) -> Self {
+        Self {
+            name,
+            status,
+            detail,
+            hint: Some(hint.to_string()),
+        }
+    }
+}
+
+/// Run every diagnostic against the environment and `config`
+pub fn diagnose(config: &Config) -> Vec<Check> {
+    let mut checks = vec![check_config_file(), check_data_dirs(), check_wsl()];
+    checks.push(check_display_server(
+        env::var("WAYLAND_DISPLAY").ok().as_deref(),
+        env::var("DISPLAY").ok().as_deref(),
+    ));
+    checks.push(Check::ok(
+        "backend",
+        format!(
+            "{:?} ({:?}), power preference {:?}",
+            config.gpu_backend,
+            gpu::backends_for(config.gpu_backend),
+            config.power_preference
+        ),
+    ));
+    checks.extend(check_adapters(config));
+    checks.push(check_fonts(config));
+    checks.push(check_terminfo(TERM, &terminfo_dirs()));
+    checks
+}
+
+/// Format the report, one check per line with hints indented below
+pub fn format_report(checks: &[Check]) -> String {
+    let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    let mut report = String::new();
+    for check in checks {
+        let _ = writeln!(
+            report,
+            "[{:>5}] {:<width$}  {}",
+            check.status, check.name, check.detail
+        );
+        if let Some(hint) = &check.hint {
+            let _ = writeln!(report, "        {:<width$}  -> {}", "", hint);
+        }
+    }
+    report
+}
+
+fn check_config_file() -> Check {
+    let Some(path) = Config::config_path() else {
+        return Check::problem(
+            "config",
+            Status::Warning,
+            "could not determine the config directory".to_string(),
+            "set XDG_CONFIG_HOME or HOME",
+        );
+    };
+    if !path.exists() {
+        return Check::ok(
+            "config",
+            format!("{} (not found, using defaults)", path.display()),
+        );
+    }
+    match config::check_file(&path) {
+        Ok(()) => Check::ok("config", path.display().to_string()),
+        Err(errors) => Check::problem(
+            "config",
+            Status::Error,
+            format!("{} has {} error(s)", path.display(), errors.len()),
+            "run `mtty --check-config` to list them",
+        ),
+    }
+}
+
+fn check_data_dirs() -> Check {
+    let show = |dir: Option<PathBuf>| {
+        dir.map_or_else(|| "unknown".to_string(), |dir| dir.display().to_string())
+    };
+    Check::ok(
+        "data",
+        format!(
+            "{}, state {}",
+            show(paths::data_dir()),
+            show(paths::state_dir())
+        ),
+    )
+}
+
+fn check_wsl() -> Check {
+    let detail = if is_wsl2() {
+        "WSL2 detected"
+    } else {
+        "not WSL2"
+    };
+    Check::ok("wsl", detail.to_string())
+}
+
+/// Which display server a window would open on, given `WAYLAND_DISPLAY` and `DISPLAY`
+pub fn check_display_server(wayland: Option<&str>, x11: Option<&str>) -> Check {
+    if cfg!(any(target_os = "macos", target_os = "windows")) {
+        return Check::ok("display", "native".to_string());
+    }
+    match (
+        wayland.filter(|v| !v.is_empty()),
+        x11.filter(|v| !v.is_empty()),
+    ) {
+        (Some(wayland), _) => Check::ok("display", format!("Wayland ({})", wayland)),
+        (None, Some(x11)) => Check::ok("display", format!("X11 ({})", x11)),
+        (None, None) => Check::problem(
+            "display",
+            Status::Error,
+            "no display server (WAYLAND_DISPLAY and DISPLAY are unset)".to_string(),
+            if is_wsl2() {
+                "enable WSLg: run `wsl --update` from Windows, then `wsl --shutdown`; \
+                 or set DISPLAY for an X server such as VcXsrv"
+            } else {
+                "run mtty from a graphical session"
+            },
+        ),
+    }
+}
+
+/// List every adapter for the configured backends, then the one the renderer would pick
+fn check_adapters(config: &Config) -> Vec<Check> {
+    let backends = gpu::backends_for(config.gpu_backend);
+    let instance = gpu::create_instance(config);
+    let adapters = instance.enumerate_adapters(backends);
+    if adapters.is_empty() {
+        let hint = if is_wsl2() {
+            "update the Windows GPU driver and run `wsl --update`, or try `--backend gl`"
+        } else {
+            "install the graphics driver for your GPU, or try another `--backend`"
+        };
+        return vec![Check::problem(
+            "adapter",
+            Status::Error,
+            format!("no adapters found for {:?}", backends),
+            hint,
+        )];
+    }
+
+    let mut checks: Vec<_> = adapters
+        .iter()
+        .map(|adapter| Check::ok("adapter", gpu::describe_adapter(&adapter.get_info())))
+        .collect();
+
+    let by_name = config.adapter_name.as_deref().and_then(|name| {
+        let found = gpu::find_adapter_by_name(&instance, backends, None, name);
+        if found.is_none() {
+            checks.push(Check::problem(
+                "adapter",
+                Status::Warning,
+                format!("no adapter matches {:?}", name),
+                "check `adapter` in the [gpu] config section against the names above",
+            ));
+        }
+        found
+    });
+    let chosen = by_name.or_else(|| {
+        pollster::block_on(instance.request_adapter(&RequestAdapterOptions {
+            power_preference: gpu::power_preference_for(config.power_preference),
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        }))
+    });
+    checks.push(match chosen {
+        Some(adapter) => Check::ok("selected", gpu::describe_adapter(&adapter.get_info())),
+        None => Check::problem(
+            "selected",
+            Status::Error,
+            "no adapter matches the power preference".to_string(),
+            "try another `--power-preference` or `--backend`",
+        ),
+    });
+    checks
+}
+
+fn check_fonts(config: &Config) -> Check {
+    let font_system = fonts::font_system();
+    let db = font_system.db();
+    let faces = db.faces().count();
+    let monospace = db.faces().filter(|face| face.monospaced).count();
+    let detail = format!("{} faces ({} monospace)", faces, monospace);
+
+    if monospace == 0 {
+        return Check::problem(
+            "fonts",
+            Status::Error,
+            detail,
+            "install a monospace font, e.g. DejaVu Sans Mono",
+        );
+    }
+    match &config.font_family {
+        Some(family) if !fonts::has_family(&font_system, family) => Check::problem(
+            "fonts",
+            Status::Warning,
+            format!("{}, {:?} not installed", detail, family),
+            "install it or change `family` in the [font] config section",
+        ),
+        Some(family) => Check::ok("fonts", format!("{}, using {:?}", detail, family)),
+        None => Check::ok("fonts", detail),
+    }
+}
+
+/// Directories searched for terminfo entries, in ncurses order
+fn terminfo_dirs() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(dir) = env::var_os("TERMINFO") {
+        paths.push(PathBuf::from(dir));
+    }
+    if let Some(home) = dirs::home_dir() {
+        paths.push(home.join(".terminfo"));
+    }
+    if let Ok(list) = env::var("TERMINFO_DIRS") {
+        paths.extend(list.split(':').filter(|d| !d.is_empty()).map(PathBuf::from));
+    }
+    paths.extend(
+        [
+            "/etc/terminfo",
+            "/lib/terminfo",
+            "/usr/share/terminfo",
+            "/usr/lib/terminfo",
+        ]
+        .map(PathBuf::from),
+    );
+    paths
+}
+
+/// Find the compiled entry for `term`, stored under its first letter (or its hex code on macOS)
+pub fn find_terminfo(term: &str, dirs: &[PathBuf]) -> Option<PathBuf> {
+    let first = term.chars().next()?;
+    let subdirs = [first.to_string(), format!("{:x}", first as u32)];
+    dirs.iter()
+        .flat_map(|dir| subdirs.iter().map(move |sub| dir.join(sub).join(term)))
+        .find(|path| path.is_file())
+}
+
+pub fn check_terminfo(term: &str, dirs: &[PathBuf]) -> Check {
+    match find_terminfo(term, dirs) {
+        Some(path) => Check::ok("terminfo", format!("{} ({})", term, path.display())),
+        None => Check::problem(
+            "terminfo",
+            Status::Warning,
+            format!("no {} entry in {}", term, join_paths(dirs)),
+            "install ncurses-term (or your distribution's terminfo package)",
+        ),
+    }
+}
+
+fn join_paths(dirs: &[PathBuf]) -> String {
+    dirs.iter()
+        .map(|dir| dir.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Whether the report found anything that stops mtty from starting
+pub fn has_errors(checks: &[Check]) -> bool {
+    checks.iter().any(|check| check.status == Status::Error)
+}
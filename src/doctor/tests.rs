@@ -0,0 +1,63 @@
+use std::fs;
+
+use super::*;
+
+#[test]
+fn find_terminfo_should_search_letter_and_hex_directories() {
+    let root = env::temp_dir().join(format!("mtty-terminfo-{}", std::process::id()));
+    let linux = root.join("linux");
+    let macos = root.join("macos");
+    fs::create_dir_all(linux.join("x")).unwrap();
+    fs::create_dir_all(macos.join("78")).unwrap();
+    fs::write(linux.join("x/xterm-256color"), b"").unwrap();
+    fs::write(macos.join("78/xterm-kitty"), b"").unwrap();
+
+    let dirs = [macos.clone(), linux.clone()];
+    assert_eq!(
+        find_terminfo("xterm-256color", &dirs),
+        Some(linux.join("x/xterm-256color"))
+    );
+    assert_eq!(
+        find_terminfo("xterm-kitty", &dirs),
+        Some(macos.join("78/xterm-kitty"))
+    );
+    assert_eq!(find_terminfo("vt52", &dirs), None);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn check_terminfo_should_warn_with_hint_when_missing() {
+    let check = check_terminfo("xterm-256color", &[PathBuf::from("/nonexistent")]);
+    assert_eq!(check.status, Status::Warning);
+    assert!(check.detail.contains("/nonexistent"));
+    assert!(check.hint.is_some());
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+#[test]
+fn check_display_server_should_prefer_wayland_and_ignore_empty_values() {
+    assert_eq!(
+        check_display_server(Some("wayland-0"), Some(":0")).detail,
+        "Wayland (wayland-0)"
+    );
+    assert_eq!(
+        check_display_server(Some(""), Some(":0")).detail,
+        "X11 (:0)"
+    );
+    assert_eq!(check_display_server(None, Some("")).status, Status::Error);
+}
+
+#[test]
+fn format_report_should_put_hints_under_their_check() {
+    let checks = [
+        Check::ok("wsl", "not WSL2".to_string()),
+        Check::problem("display", Status::Error, "none".to_string(), "fix it"),
+    ];
+    let report = format_report(&checks);
+    let lines: Vec<_> = report.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with("[error] display"));
+    assert!(lines[2].ends_with("-> fix it"));
+    assert!(has_errors(&checks));
+}
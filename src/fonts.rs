@@ -1,3 +1,43 @@
+use std::{
+    sync::Mutex,
+    thread::{self, JoinHandle},
+};
+
+use glyphon::{Color as GlyphonColor, FontSystem, SwashCache};
+
+use crate::config::{Antialiasing, Config, Hinting};
+use crate::profiling::SPAN_TARGET;
+
+/// System font database being loaded in the background by `preload_font_system`
+static PRELOADED_FONT_SYSTEM: Mutex<Option<JoinHandle<FontSystem>>> = Mutex::new(None);
+
+/// Start scanning the system fonts on another thread, overlapping it with spawning the
+/// shell and opening the window
+pub fn preload_font_system() {
+    let handle = thread::spawn(|| {
+        let _span = tracing::info_span!(target: SPAN_TARGET, "load_fonts").entered();
+        FontSystem::new()
+    });
+    *PRELOADED_FONT_SYSTEM.lock().unwrap() = Some(handle);
+}
+
+/// The preloaded font database, waiting for it if needed, or a freshly loaded one
+pub fn font_system() -> FontSystem {
+    let preloaded = PRELOADED_FONT_SYSTEM.lock().unwrap().take();
+    preloaded
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_else(FontSystem::new)
+}
+
+/// Whether a font family is installed, ignoring case
+pub fn has_family(font_system: &FontSystem, family: &str) -> bool {
+    font_system.db().faces().any(|face| {
+        face.families
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case(family))
+    })
+}
+
 /// Calculate cell dimensions based on font size
 /// Returns (cell_width, cell_height)
 pub fn get_cell_size(font_size: f32) -> (f32, f32) {
@@ -7,3 +47,60 @@ pub fn get_cell_size(font_size: f32) -> (f32, f32) {
     let cell_height = font_size * 1.2;
     (cell_width, cell_height)
 }
+
+/// Glyph rasterization settings taken from the `[font]` config section
+#[derive(Debug, Clone, Copy)]
+pub struct FontRendering {
+    pub antialiasing: Antialiasing,
+    pub hinting: Hinting,
+    pub gamma: f32,
+}
+
+impl FontRendering {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            antialiasing: config.antialiasing,
+            hinting: config.hinting,
+            gamma: config.font_gamma,
+        }
+    }
+
+    /// Create the swash glyph cache used by the text renderer.
+    ///
+    /// The swash rasterizer bundled with glyphon always produces hinted grayscale masks, so
+    /// settings it cannot honor are reported and fall back to that mode.
+    pub fn create_swash_cache(&self) -> SwashCache {
+        log::info!(
+            "Font rendering: antialiasing={:?}, hinting={:?}, gamma={}",
+            self.antialiasing,
+            self.hinting,
+            self.gamma
+        );
+        if self.antialiasing == Antialiasing::Subpixel {
+            log::warn!(
+                "Subpixel antialiasing is not supported by the glyph rasterizer, using grayscale"
+            );
+        }
+        if self.hinting != Hinting::Full {
+            log::warn!(
+                "Hinting level {:?} is not supported by the glyph rasterizer, using full hinting",
+                self.hinting
+            );
+        }
+        SwashCache::new()
+    }
+
+    /// Apply the configured gamma to a glyph color
+    pub fn apply_gamma(&self, color: GlyphonColor) -> GlyphonColor {
+        if (self.gamma - 1.0).abs() < f32::EPSILON {
+            return color;
+        }
+        let correct = |c: u8| ((c as f32 / 255.0).powf(1.0 / self.gamma) * 255.0).round() as u8;
+        GlyphonColor::rgba(
+            correct(color.r()),
+            correct(color.g()),
+            correct(color.b()),
+            color.a(),
+        )
+    }
+}
@@ -0,0 +1,111 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use tokio::sync::broadcast::{self, error::TryRecvError, Receiver, Sender};
+
+use crate::{
+    app::App,
+    commands::{ClientCommand, ServerCommand},
+    config::Config,
+    inspector::Inspector,
+    metrics::Metrics,
+    terminal::Terminal,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// How long the headless frontend sleeps when no commands are waiting
+const HEADLESS_POLL: Duration = Duration::from_millis(5);
+
+/// The connection between the terminal core and a frontend: parsed commands come in on
+/// `rx`, input and responses go out on `tx`
+pub struct Session {
+    pub config: Config,
+    /// Set once the shell has exited
+    pub exit_flag: Arc<AtomicBool>,
+    pub metrics: Arc<Metrics>,
+    pub inspector: Arc<Inspector>,
+    pub tx: Sender<ServerCommand>,
+    pub rx: Receiver<ClientCommand>,
+    /// Shell process, used by the status bar to find the working directory
+    pub shell_pid: Option<u32>,
+}
+
+impl Session {
+    /// Connect to the shell started by `app`
+    pub fn new(app: &App) -> Self {
+        Self {
+            config: app.config.clone(),
+            exit_flag: app.is_running.clone(),
+            metrics: app.metrics.clone(),
+            inspector: app.inspector.clone(),
+            tx: app.server_channel.input_transmitter.clone(),
+            rx: app.client_channel.output_receiver.resubscribe(),
+            shell_pid: Some(app.term.child.id()),
+        }
+    }
+
+    /// A session with no shell behind it, e.g. for replaying a recording
+    pub fn detached(config: Config) -> Self {
+        let (tx, _) = broadcast::channel(1);
+        let (_, rx) = broadcast::channel(1);
+        Self {
+            config,
+            exit_flag: Arc::new(AtomicBool::new(false)),
+            metrics: Arc::new(Metrics::default()),
+            inspector: Arc::new(Inspector::new()),
+            tx,
+            rx,
+            shell_pid: None,
+        }
+    }
+}
+
+/// A user interface that drives a session: it applies the command stream to a grid,
+/// shows it, and sends input back. Returns when the user quits or the shell exits.
+pub trait Frontend {
+    fn run(&mut self, session: Session);
+}
+
+/// Applies commands to a `Terminal` without showing anything, answering queries like a
+/// real frontend would. Useful for tests and scripted sessions.
+pub struct HeadlessFrontend {
+    pub terminal: Terminal,
+}
+
+impl HeadlessFrontend {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            terminal: Terminal::new(config),
+        }
+    }
+}
+
+impl Frontend for HeadlessFrontend {
+    fn run(&mut self, mut session: Session) {
+        while !session.exit_flag.load(Ordering::Relaxed) {
+            match session.rx.try_recv() {
+                Ok(command) => self.terminal.handle_command(command),
+                Err(TryRecvError::Empty) => thread::sleep(HEADLESS_POLL),
+                Err(TryRecvError::Lagged(n)) => {
+                    log::warn!("Headless frontend lagged, {} commands dropped", n)
+                }
+                Err(TryRecvError::Closed) => break,
+            }
+
+            let responses = self.terminal.take_responses();
+            if !responses.is_empty() {
+                if let Err(e) = session.tx.send(ServerCommand::RawData(responses)) {
+                    log::warn!("Failed to send responses: {}", e);
+                }
+            }
+        }
+    }
+}
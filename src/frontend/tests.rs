@@ -0,0 +1,39 @@
+use tokio::sync::broadcast;
+
+use crate::{
+    commands::{ClientCommand, ServerCommand},
+    config::Config,
+};
+
+use super::{Frontend, HeadlessFrontend, Session};
+
+#[test]
+fn headless_frontend_should_apply_commands_until_the_stream_closes() {
+    let config = Config::default();
+    let mut session = Session::detached(config.clone());
+    let (client_tx, rx) = broadcast::channel(16);
+    let (tx, mut server_rx) = broadcast::channel(16);
+    session.rx = rx;
+    session.tx = tx;
+
+    client_tx.send(ClientCommand::Print('h')).unwrap();
+    client_tx.send(ClientCommand::Print('i')).unwrap();
+    client_tx
+        .send(ClientCommand::ReportTextAreaSizeChars)
+        .unwrap();
+    drop(client_tx);
+
+    let mut frontend = HeadlessFrontend::new(&config);
+    frontend.run(session);
+
+    let grid = &frontend.terminal.grid;
+    let text: String = grid.active_grid_ref()[..2]
+        .iter()
+        .map(|cell| cell.char)
+        .collect();
+    assert_eq!(text, "hi");
+    assert!(matches!(
+        server_rx.try_recv(),
+        Ok(ServerCommand::RawData(data)) if data.starts_with(b"\x1b[8;")
+    ));
+}
@@ -0,0 +1,228 @@
+use std::{
+    sync::Mutex,
+    thread::{self, JoinHandle},
+};
+
+use wgpu::{
+    Adapter, Backends, Device, DeviceDescriptor, Features, Instance, InstanceDescriptor, Limits,
+    Queue, RequestAdapterOptions, RequestDeviceError, Surface,
+};
+
+use crate::config::{Config, GpuBackend, PowerPreference, PresentMode};
+use crate::profiling::SPAN_TARGET;
+
+/// An adapter and device opened before the window existed
+pub struct GpuContext {
+    pub instance: Instance,
+    pub adapter: Adapter,
+    pub device: Device,
+    pub queue: Queue,
+}
+
+/// Device being opened in the background by `preload`
+static PRELOADED: Mutex<Option<JoinHandle<Option<GpuContext>>>> = Mutex::new(None);
+
+/// Start opening the GPU on another thread, overlapping it with spawning the shell.
+///
+/// No surface exists yet, so the renderer checks the adapter can present to the window
+/// and falls back to the usual selection if not.
+pub fn preload(config: &Config) {
+    let config = config.clone();
+    let handle = thread::spawn(move || {
+        let _span = tracing::info_span!(target: SPAN_TARGET, "open_gpu").entered();
+        pollster::block_on(open_without_surface(&config))
+    });
+    *PRELOADED.lock().unwrap() = Some(handle);
+}
+
+/// Take the preloaded GPU, waiting for it if needed
+pub fn take_preloaded() -> Option<GpuContext> {
+    let preloaded = PRELOADED.lock().unwrap().take();
+    preloaded.and_then(|handle| handle.join().ok().flatten())
+}
+
+pub fn create_instance(config: &Config) -> Instance {
+    Instance::new(&InstanceDescriptor {
+        backends: backends_for(config.gpu_backend),
+        ..Default::default()
+    })
+}
+
+async fn open_without_surface(config: &Config) -> Option<GpuContext> {
+    let instance = create_instance(config);
+    let by_name = config.adapter_name.as_deref().and_then(|name| {
+        find_adapter_by_name(&instance, backends_for(config.gpu_backend), None, name)
+    });
+    let adapter = match by_name {
+        Some(adapter) => adapter,
+        None => {
+            instance
+                .request_adapter(&RequestAdapterOptions {
+                    power_preference: power_preference_for(config.power_preference),
+                    compatible_surface: None,
+                    force_fallback_adapter: false,
+                })
+                .await?
+        }
+    };
+    let (device, queue) = request_device(&adapter).await.ok()?;
+    Some(GpuContext {
+        instance,
+        adapter,
+        device,
+        queue,
+    })
+}
+
+/// Open the device the renderer draws with
+pub async fn request_device(adapter: &Adapter) -> Result<(Device, Queue), RequestDeviceError> {
+    adapter
+        .request_device(
+            &DeviceDescriptor {
+                label: Some("MTTY Device"),
+                required_features: Features::empty(),
+                required_limits: Limits::downlevel_webgl2_defaults()
+                    .using_resolution(adapter.limits()),
+                memory_hints: Default::default(),
+            },
+            None,
+        )
+        .await
+}
+
+/// Detect if running under WSL2 by checking for WSL-specific indicators
+pub fn is_wsl2() -> bool {
+    // Check for WSL-specific environment variable
+    if std::env::var("WSL_DISTRO_NAME").is_ok() {
+        return true;
+    }
+
+    // Check /proc/version for Microsoft/WSL indicators
+    if let Ok(version) = std::fs::read_to_string("/proc/version") {
+        let version_lower = version.to_lowercase();
+        if version_lower.contains("microsoft") || version_lower.contains("wsl") {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Resolve the configured backend to the set of wgpu backends to try
+pub fn backends_for(backend: GpuBackend) -> Backends {
+    match backend {
+        GpuBackend::Vulkan => Backends::VULKAN,
+        GpuBackend::Metal => Backends::METAL,
+        GpuBackend::Dx12 => Backends::DX12,
+        GpuBackend::Gl => Backends::GL,
+        GpuBackend::Auto => {
+            // On WSL2, Vulkan has native WSLg support and GL is the fallback
+            if is_wsl2() {
+                Backends::VULKAN | Backends::GL
+            } else {
+                Backends::all()
+            }
+        }
+    }
+}
+
+pub fn power_preference_for(preference: PowerPreference) -> wgpu::PowerPreference {
+    match preference {
+        PowerPreference::Low => wgpu::PowerPreference::LowPower,
+        PowerPreference::High => wgpu::PowerPreference::HighPerformance,
+        PowerPreference::None => wgpu::PowerPreference::None,
+    }
+}
+
+/// Resolve the configured present mode against what the surface supports.
+///
+/// Falls back to `AutoVsync`, which every surface supports, when the requested mode is missing.
+pub fn present_mode_for(
+    present_mode: PresentMode,
+    supported: &[wgpu::PresentMode],
+) -> wgpu::PresentMode {
+    let requested = match present_mode {
+        PresentMode::Vsync => return wgpu::PresentMode::AutoVsync,
+        PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+        PresentMode::Immediate => wgpu::PresentMode::Immediate,
+    };
+
+    if supported.contains(&requested) {
+        requested
+    } else {
+        log::warn!(
+            "Present mode {:?} not supported by this surface (supported: {:?}), using vsync",
+            requested,
+            supported
+        );
+        wgpu::PresentMode::AutoVsync
+    }
+}
+
+/// Find an adapter whose name contains `name` (case-insensitive) and can present to `surface`
+pub fn find_adapter_by_name(
+    instance: &Instance,
+    backends: Backends,
+    surface: Option<&Surface>,
+    name: &str,
+) -> Option<Adapter> {
+    let needle = name.to_lowercase();
+    instance
+        .enumerate_adapters(backends)
+        .into_iter()
+        .filter(|adapter| surface.is_none_or(|surface| adapter.is_surface_supported(surface)))
+        .find(|adapter| adapter.get_info().name.to_lowercase().contains(&needle))
+}
+
+/// Pick an adapter according to the GPU settings in `config`.
+///
+/// An adapter name match takes priority; otherwise wgpu chooses based on the power preference,
+/// retrying with the fallback (software) adapter if no hardware adapter is available.
+pub async fn select_adapter(
+    instance: &Instance,
+    surface: &Surface<'_>,
+    config: &Config,
+) -> Option<Adapter> {
+    let backends = backends_for(config.gpu_backend);
+
+    if let Some(name) = &config.adapter_name {
+        match find_adapter_by_name(instance, backends, Some(surface), name) {
+            Some(adapter) => return Some(adapter),
+            None => log::warn!(
+                "No adapter matching {:?} found for backends {:?}, using default selection",
+                name,
+                backends
+            ),
+        }
+    }
+
+    let power_preference = power_preference_for(config.power_preference);
+
+    if let Some(adapter) = instance
+        .request_adapter(&RequestAdapterOptions {
+            power_preference,
+            compatible_surface: Some(surface),
+            force_fallback_adapter: false,
+        })
+        .await
+    {
+        return Some(adapter);
+    }
+
+    log::warn!("Primary adapter not available, trying fallback adapter");
+    instance
+        .request_adapter(&RequestAdapterOptions {
+            power_preference,
+            compatible_surface: Some(surface),
+            force_fallback_adapter: true,
+        })
+        .await
+}
+
+/// One-line human readable description of an adapter
+pub fn describe_adapter(info: &wgpu::AdapterInfo) -> String {
+    format!(
+        "{} ({:?}, {:?}, driver: {} {})",
+        info.name, info.backend, info.device_type, info.driver, info.driver_info
+    )
+}
@@ -1,10 +1,13 @@
 use crate::{
-    commands::SgrAttribute,
+    commands::{Charset, Rectangle, SgrAttribute},
     config::Config,
-    styles::{Color, Styles},
+    styles::{Color, CursorState, Styles},
+    viewport::Viewport,
 };
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
 use unicode_width::UnicodeWidthChar;
 
 #[cfg(test)]
@@ -16,6 +19,9 @@ pub struct Cell {
     pub fg: Color,
     pub bg: Color,
     pub attrs: Vec<SgrAttribute>,
+    /// Target of the OSC 8 hyperlink the character was printed with
+    #[serde(skip)]
+    pub link: Option<Arc<str>>,
 }
 
 impl Default for Cell {
@@ -25,6 +31,7 @@ impl Default for Cell {
             fg: Color::Foreground,
             bg: Color::Background,
             attrs: vec![SgrAttribute::default()],
+            link: None,
         }
     }
 }
@@ -42,6 +49,41 @@ impl Cell {
             fg,
             bg,
             attrs: vec![],
+            link: None,
+        }
+    }
+}
+
+/// State saved by DECSC and restored by DECRC, kept separately for each screen.
+///
+/// A pending autowrap is part of the position, as the column just past the right edge.
+#[derive(Debug, Clone)]
+pub struct SavedCursor {
+    pub pos: (usize, usize),
+    pub text_color: Color,
+    pub background_color: Color,
+    pub font_size: u32,
+    pub italic: bool,
+    pub underline: bool,
+    pub reverse: bool,
+    pub charsets: [Charset; 4],
+    pub active_charset: usize,
+    pub origin_mode: bool,
+}
+
+impl Default for SavedCursor {
+    fn default() -> Self {
+        Self {
+            pos: (0, 0),
+            text_color: Color::Foreground,
+            background_color: Color::Background,
+            font_size: 16,
+            italic: false,
+            underline: false,
+            reverse: false,
+            charsets: [Charset::Ascii; 4],
+            active_charset: 0,
+            origin_mode: false,
         }
     }
 }
@@ -53,8 +95,12 @@ pub struct Grid {
     pub width: u16,
     pub height: u16,
     pub cursor_pos: (usize, usize),
-    pub saved_cursor_pos: (usize, usize),
-    pub scroll_pos: usize,
+    /// DECSC state of the active screen
+    pub saved_cursor: SavedCursor,
+    /// DECSC state of the inactive screen, swapped in with it
+    inactive_saved_cursor: SavedCursor,
+    /// Rows of the active buffer on screen
+    viewport: Viewport,
     pub styles: Styles,
     /// Row-level dirty tracking - each element indicates if that row needs re-rendering
     dirty_rows: Vec<bool>,
@@ -64,6 +110,18 @@ pub struct Grid {
     prev_cursor_pos: (usize, usize),
     /// Scrolling region (top row, bottom row) - 0-indexed, inclusive
     scroll_region: (usize, usize),
+    /// Whether left and right margins can be set (DECLRMM)
+    left_right_margin_mode: bool,
+    /// Left and right margins (first column, last column) - 0-indexed, inclusive
+    horizontal_margins: (usize, usize),
+    /// Cursor addressing relative to the scrolling region and margins (DECOM)
+    origin_mode: bool,
+    /// Character sets designated as G0-G3
+    charsets: [Charset; 4],
+    /// Which of G0-G3 printed characters are mapped through
+    active_charset: usize,
+    /// OSC 8 hyperlink attached to printed characters until it is closed
+    hyperlink: Option<Arc<str>>,
 }
 
 impl Grid {
@@ -81,14 +139,24 @@ impl Grid {
             cells,
             alternate_screen,
             cursor_pos: (0, 0),
-            saved_cursor_pos: (0, 0),
-            scroll_pos: height as usize - 1,
-            styles: Styles::default(),
+            saved_cursor: SavedCursor::default(),
+            inactive_saved_cursor: SavedCursor::default(),
+            viewport: Viewport::new(height as usize, config.scroll_on_output),
+            styles: Styles::with_cursor(CursorState::new(
+                config.cursor_style.shape(),
+                config.cursor_blinking,
+            )),
             alternate: false,
             dirty_rows,
             dirty_count: height as usize, // All rows start dirty
             prev_cursor_pos: (0, 0),
             scroll_region: (0, height as usize - 1),
+            left_right_margin_mode: false,
+            horizontal_margins: (0, width as usize - 1),
+            origin_mode: false,
+            charsets: [Charset::Ascii; 4],
+            active_charset: 0,
+            hyperlink: None,
         }
     }
 
@@ -102,6 +170,99 @@ impl Grid {
         &self.dirty_rows
     }
 
+    /// Number of rows currently marked dirty
+    pub fn dirty_row_count(&self) -> usize {
+        self.dirty_count
+    }
+
+    /// Number of rows kept above the visible screen in the active buffer
+    pub fn scrollback_rows(&self) -> usize {
+        self.total_rows().saturating_sub(self.height as usize)
+    }
+
+    /// Number of rows in the active buffer
+    pub fn total_rows(&self) -> usize {
+        self.active_grid_ref().len() / self.width.max(1) as usize
+    }
+
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    /// First buffer row on screen
+    pub fn top_row(&self) -> usize {
+        self.viewport.top(self.total_rows())
+    }
+
+    /// Last buffer row on screen
+    pub fn bottom_row(&self) -> usize {
+        self.viewport.bottom(self.total_rows())
+    }
+
+    /// Approximate heap memory used by both screen buffers, in bytes
+    pub fn memory_usage(&self) -> usize {
+        let buffer_usage = |cells: &Vec<Cell>| {
+            cells.capacity() * std::mem::size_of::<Cell>()
+                + cells
+                    .iter()
+                    .map(|cell| cell.attrs.capacity() * std::mem::size_of::<SgrAttribute>())
+                    .sum::<usize>()
+        };
+        buffer_usage(&self.cells) + buffer_usage(&self.alternate_screen)
+    }
+
+    /// Check structural invariants, describing the first one that is violated.
+    ///
+    /// Used by tests and fuzzing to catch states that would make rendering index out of bounds.
+    pub fn check_invariants(&self) -> Result<(), String> {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        let len = self.active_grid_ref().len();
+        if width == 0 || height == 0 {
+            return Err(format!("empty grid {}x{}", width, height));
+        }
+        if !len.is_multiple_of(width) || len < width * height {
+            return Err(format!(
+                "buffer length {} for {}x{} grid",
+                len, width, height
+            ));
+        }
+        let rows = len / width;
+        let (row, col) = self.cursor_pos;
+        // The column may sit one past the end while a wrap is pending
+        if row >= rows || col > width {
+            return Err(format!(
+                "cursor {:?} outside {}x{} buffer",
+                self.cursor_pos, width, rows
+            ));
+        }
+        if self.viewport.height() != height || self.viewport.display_offset() + height > rows {
+            return Err(format!("viewport {:?} with {} rows", self.viewport, rows));
+        }
+        let (top, bottom) = self.scroll_region;
+        if top > bottom || bottom >= height {
+            return Err(format!(
+                "scroll region {:?} with height {}",
+                self.scroll_region, height
+            ));
+        }
+        let (left, right) = self.horizontal_margins;
+        if left > right || right >= width {
+            return Err(format!(
+                "horizontal margins {:?} with width {}",
+                self.horizontal_margins, width
+            ));
+        }
+        if self.dirty_rows.len() != height {
+            return Err(format!(
+                "{} dirty flags for height {}",
+                self.dirty_rows.len(),
+                height
+            ));
+        }
+        Ok(())
+    }
+
     /// Clears all dirty flags (call after rendering)
     pub fn clear_dirty(&mut self) {
         for dirty in &mut self.dirty_rows {
@@ -113,10 +274,8 @@ impl Grid {
 
     /// Marks a specific row as dirty
     fn mark_row_dirty(&mut self, row: usize) {
-        // Convert absolute row to dirty_rows index based on current scroll position
-        let start_row = self.scroll_pos.saturating_sub(self.height as usize - 1);
-        if row >= start_row {
-            let display_row = row - start_row;
+        // Convert absolute row to dirty_rows index based on the viewport
+        if let Some(display_row) = self.viewport.to_screen(row, self.total_rows()) {
             if display_row < self.dirty_rows.len() && !self.dirty_rows[display_row] {
                 self.dirty_rows[display_row] = true;
                 self.dirty_count += 1;
@@ -151,6 +310,29 @@ impl Grid {
         }
     }
 
+    /// Text of an absolute buffer row without trailing blanks
+    pub fn row_text(&self, row: usize) -> String {
+        let width = self.width as usize;
+        self.active_grid_ref()
+            .chunks(width)
+            .nth(row)
+            .map(|cells| cells.iter().map(|cell| cell.char).collect::<String>())
+            .unwrap_or_default()
+            .trim_end()
+            .to_string()
+    }
+
+    /// Recolor the background of every cell in an absolute buffer row
+    pub fn set_row_background(&mut self, row: usize, color: Color) {
+        let width = self.width as usize;
+        if let Some(cells) = self.active_grid().chunks_mut(width).nth(row) {
+            for cell in cells {
+                cell.bg = color;
+            }
+            self.mark_row_dirty(row);
+        }
+    }
+
     /// Check if currently using alternate screen
     pub fn is_alternate(&self) -> bool {
         self.alternate
@@ -163,8 +345,9 @@ impl Grid {
 
     pub fn swap_active_grid(&mut self) {
         self.alternate = !self.alternate;
-        // Reset scroll position when switching screens
-        self.scroll_pos = self.height as usize - 1;
+        std::mem::swap(&mut self.saved_cursor, &mut self.inactive_saved_cursor);
+        // Each screen is shown from its bottom
+        self.viewport.scroll_to_bottom();
         self.mark_all_dirty();
     }
 
@@ -183,16 +366,17 @@ impl Grid {
         self.dirty_count = new_rows as usize;
 
         // Reset positions and scroll region
-        self.scroll_pos = new_rows as usize - 1;
+        self.viewport.set_height(new_rows as usize);
         self.cursor_pos = (0, 0);
         self.scroll_region = (0, new_rows as usize - 1);
+        self.horizontal_margins = (0, new_cols as usize - 1);
     }
 
     pub fn pretty_print(&mut self) {
         log::info!("Grid: {}x{}", self.width, self.height);
         log::info!("Cursor Position: {:?}", self.cursor_pos);
-        log::info!("Saved Cursor Position: {:?}", self.saved_cursor_pos);
-        log::info!("Scroll Position: {:?}", self.scroll_pos);
+        log::info!("Saved Cursor Position: {:?}", self.saved_cursor.pos);
+        log::info!("Viewport: {:?}", self.viewport);
         log::info!(
             "Active Grid: {:?}",
             if self.alternate { "Alternate" } else { "Main" }
@@ -229,8 +413,7 @@ impl Grid {
         self.cursor_pos = (row, col);
 
         // Auto-scroll: if cursor is below visible area, scroll to follow
-        if row > self.scroll_pos {
-            self.scroll_pos = row;
+        if self.viewport.output(row, self.total_rows()) {
             self.mark_all_dirty(); // Need to redraw all rows when scrolling
         }
 
@@ -258,6 +441,7 @@ impl Grid {
                 self.active_grid().push(Cell::new(' ', fg, bg));
             }
         }
+        self.viewport.rows_added(rows);
         // Adding rows typically means scrolling, mark all visible rows dirty
         self.mark_all_dirty();
     }
@@ -268,9 +452,14 @@ impl Grid {
         // Get character display width (0 for control chars, 1 for normal, 2 for wide/emoji)
         let char_width = c.width().unwrap_or(0);
 
+        // Wrap at the right margin when the cursor is inside the margins, else at the edge
+        let (left, right) = match self.horizontal_margins {
+            (left, right) if (left..=right + 1).contains(&col) => (left, right),
+            _ => (0, cols as usize - 1),
+        };
         // Check if we need to wrap (accounting for wide characters)
-        if col >= cols as usize || (char_width == 2 && col + 1 >= cols as usize) {
-            self.set_pos(row + 1, 0);
+        if col > right || (char_width == 2 && col + 1 > right) {
+            self.set_pos(row + 1, left);
         }
 
         (row, col) = self.cursor_pos;
@@ -289,10 +478,12 @@ impl Grid {
 
         match c {
             '\n' => {
-                self.set_pos(row + 1, 0);
+                let start = self.line_start();
+                self.set_pos(row + 1, start);
             }
             '\r' => {
-                self.set_pos(row, 0);
+                let start = self.line_start();
+                self.set_pos(row, start);
             }
             _ => {
                 // Skip zero-width characters (combining marks, etc.)
@@ -306,14 +497,21 @@ impl Grid {
                 if index >= active_grid_len {
                     self.add_rows(row - (active_grid_len / (self.width as usize)) + 1);
                 }
-                self.active_grid()[index] = Cell::new(c, fg, bg);
+                let link = self.hyperlink.clone();
+                self.active_grid()[index] = Cell {
+                    link: link.clone(),
+                    ..Cell::new(c, fg, bg)
+                };
 
                 // For wide characters (width 2), place a placeholder in the next cell
                 if char_width == 2 && col + 1 < self.width as usize {
                     let next_index = index + 1;
                     if next_index < self.active_grid().len() {
                         // Use a space as placeholder for the second half of wide char
-                        self.active_grid()[next_index] = Cell::new(' ', fg, bg);
+                        self.active_grid()[next_index] = Cell {
+                            link,
+                            ..Cell::new(' ', fg, bg)
+                        };
                     }
                 }
 
@@ -349,11 +547,130 @@ impl Grid {
             self.active_grid()[i] = Cell::new(' ', fg, bg);
         }
 
-        self.scroll_pos = 0;
+        self.viewport.scroll_to_bottom();
         self.cursor_pos = (0, 0);
         self.mark_all_dirty();
     }
 
+    /// Move the view by `rows` (negative is up, into the scrollback), staying inside the buffer
+    pub fn scroll_view(&mut self, rows: isize) {
+        if self.viewport.scroll(rows, self.total_rows()) {
+            self.mark_all_dirty();
+        }
+    }
+
+    /// Move the view so its last row is `bottom`, staying inside the buffer
+    pub fn scroll_view_to(&mut self, bottom: usize) {
+        if self.viewport.scroll_to(bottom, self.total_rows()) {
+            self.mark_all_dirty();
+        }
+    }
+
+    /// Drop the rows kept above the visible screen (ED 3), leaving the screen as it is
+    pub fn clear_scrollback(&mut self) {
+        let removed = self.scrollback_rows();
+        if removed == 0 {
+            return;
+        }
+        let width = self.width as usize;
+        self.active_grid().drain(..removed * width);
+        self.viewport.rows_removed(self.total_rows());
+        self.cursor_pos.0 = self.cursor_pos.0.saturating_sub(removed);
+        self.prev_cursor_pos.0 = self.prev_cursor_pos.0.saturating_sub(removed);
+        self.saved_cursor.pos.0 = self.saved_cursor.pos.0.saturating_sub(removed);
+        self.mark_all_dirty();
+    }
+
+    /// Zero-based screen rows and columns covered by a DEC rectangle, clipped to the screen
+    fn rectangle_ranges(&self, rect: Rectangle) -> Option<(Range<usize>, Range<usize>)> {
+        let top = rect.top.max(1) as usize - 1;
+        let left = rect.left.max(1) as usize - 1;
+        let bottom = (rect.bottom as usize).min(self.height as usize);
+        let right = (rect.right as usize).min(self.width as usize);
+        (top < bottom && left < right).then_some((top..bottom, left..right))
+    }
+
+    /// Copy a rectangle so its top-left corner lands at (top, left), clipping at the screen edge (DECCRA)
+    pub fn copy_rectangle(&mut self, source: Rectangle, top: u16, left: u16) {
+        let Some((rows, cols)) = self.rectangle_ranges(source) else {
+            return;
+        };
+        let width = self.width as usize;
+        // Copy out first so overlapping source and destination behave
+        let copied: Vec<Vec<Cell>> = rows
+            .map(|row| {
+                self.active_grid_ref()[row * width + cols.start..row * width + cols.end].to_vec()
+            })
+            .collect();
+
+        let dest_top = top.max(1) as usize - 1;
+        let dest_left = left.max(1) as usize - 1;
+        for (row, cells) in (dest_top..self.height as usize).zip(copied) {
+            for (col, cell) in (dest_left..width).zip(cells) {
+                self.active_grid()[row * width + col] = cell;
+            }
+            self.mark_row_dirty(row);
+        }
+    }
+
+    /// Fill a rectangle with `c` in the current colors (DECFRA)
+    pub fn fill_rectangle(&mut self, rect: Rectangle, c: char) {
+        let Some((rows, cols)) = self.rectangle_ranges(rect) else {
+            return;
+        };
+        let (fg, bg) = if self.styles.reverse {
+            (
+                self.styles.active_background_color,
+                self.styles.active_text_color,
+            )
+        } else {
+            (
+                self.styles.active_text_color,
+                self.styles.active_background_color,
+            )
+        };
+
+        let width = self.width as usize;
+        for row in rows {
+            for col in cols.clone() {
+                self.active_grid()[row * width + col] = Cell::new(c, fg, bg);
+            }
+            self.mark_row_dirty(row);
+        }
+    }
+
+    /// DECRQCRA checksum of a rectangle, computed like the VT520 and xterm's default:
+    /// the sum of every character code plus its attribute bits, negated to 16 bits
+    pub fn rectangle_checksum(&self, rect: Rectangle) -> u16 {
+        let Some((rows, cols)) = self.rectangle_ranges(rect) else {
+            return 0;
+        };
+        let width = self.width as usize;
+        let cells = self.active_grid_ref();
+
+        let mut total: u32 = 0;
+        for row in rows {
+            for cell in &cells[row * width + cols.start..row * width + cols.end] {
+                total = total.wrapping_add(cell.char as u32);
+                for attr in &cell.attrs {
+                    total = total.wrapping_add(match attr {
+                        SgrAttribute::Bold => 0x80,
+                        SgrAttribute::BlinkSlow | SgrAttribute::BlinkFast => 0x40,
+                        SgrAttribute::Reverse => 0x20,
+                        SgrAttribute::Underline => 0x10,
+                        _ => 0,
+                    });
+                }
+            }
+        }
+        (total.wrapping_neg() & 0xffff) as u16
+    }
+
+    /// Blank a rectangle (DECERA)
+    pub fn erase_rectangle(&mut self, rect: Rectangle) {
+        self.fill_rectangle(rect, ' ');
+    }
+
     pub fn delete_character(&mut self) {
         let (row, col) = self.cursor_pos;
         let cols = self.width as usize;
@@ -385,6 +702,11 @@ impl Grid {
         }
     }
 
+    /// Redraw the cursor's row, e.g. when its blink phase changes
+    pub fn mark_cursor_row_dirty(&mut self) {
+        self.mark_row_dirty(self.cursor_pos.0);
+    }
+
     pub fn show_cursor(&mut self) {
         self.styles.cursor_state.hidden = false;
         self.mark_row_dirty(self.cursor_pos.0);
@@ -395,31 +717,152 @@ impl Grid {
         self.mark_row_dirty(self.cursor_pos.0);
     }
 
+    /// Save the cursor position, text attributes, charsets and origin mode (DECSC)
     pub fn save_cursor(&mut self) {
-        self.saved_cursor_pos = self.cursor_pos;
+        self.saved_cursor = SavedCursor {
+            pos: self.cursor_pos,
+            text_color: self.styles.active_text_color,
+            background_color: self.styles.active_background_color,
+            font_size: self.styles.font_size,
+            italic: self.styles.italic,
+            underline: self.styles.underline,
+            reverse: self.styles.reverse,
+            charsets: self.charsets,
+            active_charset: self.active_charset,
+            origin_mode: self.origin_mode,
+        };
     }
 
+    /// Restore the state saved by `save_cursor`, or the defaults if nothing was saved (DECRC)
     pub fn restore_cursor(&mut self) {
-        self.cursor_pos = self.saved_cursor_pos;
+        let saved = self.saved_cursor.clone();
+        self.styles.active_text_color = saved.text_color;
+        self.styles.active_background_color = saved.background_color;
+        self.styles.font_size = saved.font_size;
+        self.styles.italic = saved.italic;
+        self.styles.underline = saved.underline;
+        self.styles.reverse = saved.reverse;
+        self.charsets = saved.charsets;
+        self.active_charset = saved.active_charset;
+        self.origin_mode = saved.origin_mode;
+        let (row, col) = saved.pos;
+        self.set_pos(row, col.min(self.width as usize));
+    }
+
+    /// Designate `charset` as G0-G3
+    pub fn configure_charset(&mut self, index: usize, charset: Charset) {
+        if let Some(slot) = self.charsets.get_mut(index) {
+            *slot = charset;
+        }
+    }
+
+    pub fn set_active_charset(&mut self, index: usize) {
+        if index < self.charsets.len() {
+            self.active_charset = index;
+        }
+    }
+
+    /// Map a printed character through the active character set
+    pub fn map_charset(&self, c: char) -> char {
+        self.charsets[self.active_charset].map(c)
+    }
+
+    /// Attach characters printed from now on to the OSC 8 hyperlink `uri`, or stop with `None`
+    pub fn set_hyperlink(&mut self, uri: Option<&str>) {
+        self.hyperlink = uri.filter(|uri| !uri.is_empty()).map(Arc::from);
+    }
+
+    pub fn set_origin_mode(&mut self, enabled: bool) {
+        self.origin_mode = enabled;
+        self.goto(0, 0);
+    }
+
+    /// Absolute cursor move (CUP), kept on screen, or inside the scrolling region and margins
+    /// in origin mode
+    pub fn goto(&mut self, row: usize, col: usize) {
+        let ((top, bottom), (left, right)) = if self.origin_mode {
+            (self.scroll_region, self.horizontal_margins)
+        } else {
+            ((0, self.height as usize - 1), (0, self.width as usize - 1))
+        };
+        self.set_pos(
+            top.saturating_add(row).min(bottom),
+            left.saturating_add(col).min(right),
+        );
     }
 
     /// Set the scrolling region (1-indexed from terminal, converted to 0-indexed)
     pub fn set_scroll_region(&mut self, top: usize, bottom: Option<usize>) {
         // Terminal uses 1-indexed, convert to 0-indexed
+        let last_row = self.height as usize - 1;
         let top = top.saturating_sub(1);
         let bottom = bottom
             .map(|b| b.saturating_sub(1))
-            .unwrap_or(self.height as usize - 1);
-        self.scroll_region = (top, bottom.min(self.height as usize - 1));
+            .unwrap_or(last_row)
+            .min(last_row);
+        // Like DECSTBM, ignore regions that are less than two lines or start below the screen
+        if top >= bottom {
+            return;
+        }
+        self.scroll_region = (top, bottom);
         // Move cursor to home position when scroll region is set
-        self.set_pos(0, 0);
+        self.goto(0, 0);
+    }
+
+    pub fn get_horizontal_margins(&self) -> (usize, usize) {
+        self.horizontal_margins
+    }
+
+    /// Enable or disable left and right margins (DECLRMM), resetting them to the full width
+    pub fn set_left_right_margin_mode(&mut self, enabled: bool) {
+        self.left_right_margin_mode = enabled;
+        self.horizontal_margins = (0, self.width as usize - 1);
+    }
+
+    /// Set the left and right margins (1-indexed from terminal, converted to 0-indexed).
+    ///
+    /// Ignored unless DECLRMM is enabled or when the margins would be less than two columns apart.
+    pub fn set_left_right_margins(&mut self, left: usize, right: Option<usize>) {
+        if !self.left_right_margin_mode {
+            return;
+        }
+        let last_col = self.width as usize - 1;
+        let left = left.saturating_sub(1);
+        let right = right.map(|r| r.saturating_sub(1)).unwrap_or(last_col);
+        if left >= right || right > last_col {
+            return;
+        }
+        self.horizontal_margins = (left, right);
+        // Move cursor to home position when margins are set
+        self.goto(0, 0);
+    }
+
+    /// Columns the cursor can move between: the margins when it is inside them, else the full width
+    pub fn cursor_margins(&self) -> (usize, usize) {
+        let (left, right) = self.horizontal_margins;
+        if (left..=right).contains(&self.cursor_pos.1) {
+            (left, right)
+        } else {
+            (0, self.width as usize - 1)
+        }
+    }
+
+    /// Column a carriage return moves to: the left margin, unless the cursor is left of it
+    pub fn line_start(&self) -> usize {
+        let left = self.horizontal_margins.0;
+        if self.cursor_pos.1 >= left {
+            left
+        } else {
+            0
+        }
     }
 
     /// Scroll content up within the scroll region (content moves up, blank lines appear at bottom)
     pub fn scroll_up(&mut self, count: usize) {
         let (top, bottom) = self.scroll_region;
+        let (left, right) = self.horizontal_margins;
         let width = self.width as usize;
-        let region_height = bottom - top + 1;
+        let region_height = (bottom + 1).saturating_sub(top);
 
         if count >= region_height {
             // Clear the entire scroll region
@@ -442,10 +885,10 @@ impl Grid {
         let grid = self.active_grid();
 
         // Move rows up within the region
-        for row in top..(bottom - count + 1) {
+        for row in top..(bottom + 1 - count) {
             let dest_idx = row * width;
             let src_idx = (row + count) * width;
-            for col in 0..width {
+            for col in left..=right {
                 if src_idx + col < grid.len() && dest_idx + col < grid.len() {
                     grid[dest_idx + col] = grid[src_idx + col].clone();
                 }
@@ -453,9 +896,9 @@ impl Grid {
         }
 
         // Clear the bottom rows
-        for row in (bottom - count + 1)..=bottom {
+        for row in (bottom + 1 - count)..=bottom {
             let start_idx = row * width;
-            for col in 0..width {
+            for col in left..=right {
                 if start_idx + col < grid.len() {
                     grid[start_idx + col] = Cell::new(' ', fg, bg);
                 }
@@ -468,8 +911,9 @@ impl Grid {
     /// Scroll content down within the scroll region (content moves down, blank lines appear at top)
     pub fn scroll_down(&mut self, count: usize) {
         let (top, bottom) = self.scroll_region;
+        let (left, right) = self.horizontal_margins;
         let width = self.width as usize;
-        let region_height = bottom - top + 1;
+        let region_height = (bottom + 1).saturating_sub(top);
 
         if count >= region_height {
             // Clear the entire scroll region
@@ -495,7 +939,7 @@ impl Grid {
         for row in ((top + count)..=bottom).rev() {
             let dest_idx = row * width;
             let src_idx = (row - count) * width;
-            for col in 0..width {
+            for col in left..=right {
                 if src_idx + col < grid.len() && dest_idx + col < grid.len() {
                     grid[dest_idx + col] = grid[src_idx + col].clone();
                 }
@@ -505,7 +949,7 @@ impl Grid {
         // Clear the top rows
         for row in top..(top + count) {
             let start_idx = row * width;
-            for col in 0..width {
+            for col in left..=right {
                 if start_idx + col < grid.len() {
                     grid[start_idx + col] = Cell::new(' ', fg, bg);
                 }
@@ -518,6 +962,7 @@ impl Grid {
     /// Clear the entire scroll region
     fn clear_scroll_region(&mut self) {
         let (top, bottom) = self.scroll_region;
+        let (left, right) = self.horizontal_margins;
         let width = self.width as usize;
 
         let (fg, bg) = if self.styles.reverse {
@@ -535,7 +980,7 @@ impl Grid {
         let grid = self.active_grid();
         for row in top..=bottom {
             let start_idx = row * width;
-            for col in 0..width {
+            for col in left..=right {
                 if start_idx + col < grid.len() {
                     grid[start_idx + col] = Cell::new(' ', fg, bg);
                 }
@@ -547,12 +992,13 @@ impl Grid {
 
     /// Insert blank lines at cursor position within scroll region
     pub fn insert_blank_lines(&mut self, count: usize) {
-        let (row, _) = self.cursor_pos;
+        let (row, col) = self.cursor_pos;
         let (top, bottom) = self.scroll_region;
+        let (left, right) = self.horizontal_margins;
         let width = self.width as usize;
 
-        // Only operate if cursor is within scroll region
-        if row < top || row > bottom {
+        // Only operate if cursor is within scroll region and margins
+        if row < top || row > bottom || col < left || col > right {
             return;
         }
 
@@ -578,7 +1024,7 @@ impl Grid {
             let src_row = dest_row - count;
             let dest_idx = dest_row * width;
             let src_idx = src_row * width;
-            for col in 0..width {
+            for col in left..=right {
                 if src_idx + col < grid.len() && dest_idx + col < grid.len() {
                     grid[dest_idx + col] = grid[src_idx + col].clone();
                 }
@@ -588,7 +1034,7 @@ impl Grid {
         // Clear the rows at cursor position
         for r in row..(row + count).min(bottom + 1) {
             let start_idx = r * width;
-            for col in 0..width {
+            for col in left..=right {
                 if start_idx + col < grid.len() {
                     grid[start_idx + col] = Cell::new(' ', fg, bg);
                 }
@@ -600,12 +1046,13 @@ impl Grid {
 
     /// Delete lines at cursor position within scroll region (content moves up)
     pub fn delete_lines(&mut self, count: usize) {
-        let (row, _) = self.cursor_pos;
+        let (row, col) = self.cursor_pos;
         let (top, bottom) = self.scroll_region;
+        let (left, right) = self.horizontal_margins;
         let width = self.width as usize;
 
-        // Only operate if cursor is within scroll region
-        if row < top || row > bottom {
+        // Only operate if cursor is within scroll region and margins
+        if row < top || row > bottom || col < left || col > right {
             return;
         }
 
@@ -631,7 +1078,7 @@ impl Grid {
             let dest_row = src_row - count;
             let dest_idx = dest_row * width;
             let src_idx = src_row * width;
-            for col in 0..width {
+            for col in left..=right {
                 if src_idx + col < grid.len() && dest_idx + col < grid.len() {
                     grid[dest_idx + col] = grid[src_idx + col].clone();
                 }
@@ -639,9 +1086,9 @@ impl Grid {
         }
 
         // Clear the bottom rows
-        for r in (bottom - count + 1)..=bottom {
+        for r in (bottom + 1 - count)..=bottom {
             let start_idx = r * width;
-            for col in 0..width {
+            for col in left..=right {
                 if start_idx + col < grid.len() {
                     grid[start_idx + col] = Cell::new(' ', fg, bg);
                 }
@@ -668,7 +1115,6 @@ impl Grid {
     /// Insert blank characters at cursor, shifting existing chars to the right
     pub fn insert_blanks(&mut self, count: usize) {
         let (row, col) = self.cursor_pos;
-        let width = self.width as usize;
 
         let (fg, bg) = if self.styles.reverse {
             (
@@ -682,8 +1128,16 @@ impl Grid {
             )
         };
 
+        // Characters only shift up to the right margin
+        let (left, right) = self.horizontal_margins;
+        if col < left || col > right {
+            return;
+        }
+        let width = right + 1;
+        let count = count.min(width - col);
+
+        let row_start = row * (self.width as usize);
         let grid = self.active_grid();
-        let row_start = row * width;
         let row_end = row_start + width;
 
         // Shift characters to the right
@@ -713,7 +1167,6 @@ impl Grid {
     /// Delete characters at cursor, shifting remaining chars left
     pub fn delete_chars(&mut self, count: usize) {
         let (row, col) = self.cursor_pos;
-        let width = self.width as usize;
 
         let (fg, bg) = if self.styles.reverse {
             (
@@ -727,8 +1180,16 @@ impl Grid {
             )
         };
 
+        // Characters only shift in from the right margin
+        let (left, right) = self.horizontal_margins;
+        if col < left || col > right {
+            return;
+        }
+        let width = right + 1;
+        let count = count.min(width - col);
+
+        let row_start = row * (self.width as usize);
         let grid = self.active_grid();
-        let row_start = row * width;
 
         // Shift characters to the left
         for i in col..(width - count) {
@@ -755,9 +1216,9 @@ impl Grid {
         self.width = snapshot.width;
         self.height = snapshot.height;
         self.cursor_pos = snapshot.cursor_pos;
-        self.saved_cursor_pos = snapshot.saved_cursor_pos;
-        self.scroll_pos = snapshot.scroll_pos;
+        self.saved_cursor.pos = snapshot.saved_cursor_pos;
         self.scroll_region = snapshot.scroll_region;
+        self.horizontal_margins = (0, self.width as usize - 1);
         self.alternate = snapshot.alternate_active;
         self.styles.cursor_state = snapshot.cursor_state;
         self.styles.active_text_color = snapshot.active_fg;
@@ -770,6 +1231,10 @@ impl Grid {
             self.cells = snapshot.cells.clone();
         }
 
+        self.viewport.set_height(self.height as usize);
+        self.viewport
+            .scroll_to(snapshot.scroll_pos, self.total_rows());
+
         // Resize dirty tracking to match
         self.dirty_rows = vec![true; self.height as usize];
         self.dirty_count = self.height as usize;
@@ -4,6 +4,11 @@ use crate::{
     styles::Color,
 };
 
+/// Index of the cell at `row`, `col` in the flat buffer
+fn index(grid: &Grid, row: usize, col: usize) -> usize {
+    row * grid.width as usize + col
+}
+
 #[test]
 fn set_pos_should_set_cursor_position() {
     let mut grid = Grid::new(&Config::default());
@@ -24,8 +29,8 @@ fn set_pos_should_add_rows_and_set_scroll_pos_if_needed() {
     grid.set_pos(15, 5);
 
     assert_eq!(grid.cursor_pos, (15, 5));
-    assert_eq!(grid.active_grid().len(), 16);
-    assert_eq!(grid.scroll_pos, 15);
+    assert_eq!(grid.active_grid().len(), 16 * 10);
+    assert_eq!(grid.bottom_row(), 15);
 }
 
 #[test]
@@ -33,7 +38,8 @@ fn delete_character_should_remove_character_in_row() {
     let mut grid = Grid::new(&Config::default());
 
     grid.set_pos(5, 10);
-    grid.cells[5][10] = Cell {
+    let cell = index(&grid, 5, 10);
+    grid.cells[cell] = Cell {
         char: 'a',
         ..Cell::default()
     };
@@ -41,7 +47,7 @@ fn delete_character_should_remove_character_in_row() {
     grid.delete_character();
 
     assert_eq!(grid.cursor_pos, (5, 9));
-    assert_eq!(grid.cells[5][10].char, ' ');
+    assert_eq!(grid.cells[index(&grid, 5, 10)].char, ' ');
 }
 
 #[test]
@@ -54,7 +60,8 @@ fn delete_character_should_wrap_to_previous_line_if_pos_at_beginning() {
     let mut grid = Grid::new(&config);
 
     grid.set_pos(9, 0);
-    grid.cells[9][0] = Cell {
+    let cell = index(&grid, 9, 0);
+    grid.cells[cell] = Cell {
         char: 'a',
         ..Cell::default()
     };
@@ -62,7 +69,7 @@ fn delete_character_should_wrap_to_previous_line_if_pos_at_beginning() {
     grid.delete_character();
 
     assert_eq!(grid.cursor_pos, (8, 9));
-    assert_eq!(grid.cells[9][0].char, ' ');
+    assert_eq!(grid.cells[index(&grid, 9, 0)].char, ' ');
 }
 
 #[test]
@@ -73,18 +80,24 @@ fn place_character_in_grid_should_place_character_in_grid() {
     grid.place_character_in_grid(10, 'a');
 
     assert_eq!(grid.cursor_pos, (5, 10));
-    assert_eq!(grid.cells[5][9].char, 'a');
+    assert_eq!(grid.cells[index(&grid, 5, 9)].char, 'a');
 }
 
 #[test]
 fn place_character_in_grid_should_wrap_to_next_row_if_column_specified_larger_than_grid() {
-    let mut grid = Grid::new(&Config::default());
+    // Wrapping follows the grid's width and margins, so size the grid to the columns passed
+    let config = Config {
+        rows: 10,
+        cols: 10,
+        ..Config::default()
+    };
+    let mut grid = Grid::new(&config);
 
     grid.set_pos(5, 10);
     grid.place_character_in_grid(10, 'a');
 
     assert_eq!(grid.cursor_pos, (6, 1));
-    assert_eq!(grid.cells[6][0].char, 'a');
+    assert_eq!(grid.cells[index(&grid, 6, 0)].char, 'a');
 }
 
 #[test]
@@ -111,16 +124,12 @@ fn place_character_in_grid_with_carriage_return_should_move_cursor_to_start_of_l
 fn clear_screen_should_clear_grid() {
     let mut grid = Grid::new(&Config::default());
 
-    grid.cells.iter_mut().for_each(|row| {
-        row.iter_mut()
-            .for_each(|cell| *cell = Cell::new('a', Color::White, Color::Black));
-    });
+    grid.cells
+        .iter_mut()
+        .for_each(|cell| *cell = Cell::new('a', Color::White, Color::Black));
 
     grid.clear_screen();
-    assert!(grid
-        .cells
-        .iter()
-        .all(|row| row.iter().all(|cell| cell.char == ' ')));
+    assert!(grid.cells.iter().all(|cell| cell.char == ' '));
 }
 
 #[test]
@@ -135,7 +144,8 @@ fn swap_active_grid_should_swap_grids() {
     // Fill the active grid with some data
     for row in 0..5 {
         for col in 0..5 {
-            grid.cells[row][col] = Cell::new('x', Color::White, Color::Black);
+            let cell = index(&grid, row, col);
+            grid.cells[cell] = Cell::new('x', Color::White, Color::Black);
         }
     }
 
@@ -145,10 +155,8 @@ fn swap_active_grid_should_swap_grids() {
     let active_grid = grid.active_grid();
 
     // grid should be same size as before
-    assert_eq!(active_grid.len(), config.rows as usize);
+    assert_eq!(active_grid.len(), (config.rows * config.cols) as usize);
 
     // each cell in the active grid should be empty
-    assert!(active_grid
-        .iter()
-        .all(|row| row.iter().all(|cell| cell.char == ' ')));
+    assert!(active_grid.iter().all(|cell| cell.char == ' '));
 }
@@ -0,0 +1,70 @@
+use regex::Regex;
+
+use crate::{config::HighlightConfig, grid::Cell};
+
+#[cfg(test)]
+mod tests;
+
+struct HighlightRule {
+    regex: Regex,
+    color: (u8, u8, u8),
+}
+
+/// Compiled highlight rules, applied per row at render time
+#[derive(Default)]
+pub struct HighlightRules {
+    rules: Vec<HighlightRule>,
+}
+
+impl HighlightRules {
+    /// Compile the configured rules, skipping (and logging) invalid patterns
+    pub fn new(configs: &[HighlightConfig]) -> Self {
+        let rules = configs
+            .iter()
+            .filter_map(|config| match Regex::new(&config.pattern) {
+                Ok(regex) => Some(HighlightRule {
+                    regex,
+                    color: config.color,
+                }),
+                Err(e) => {
+                    log::warn!(
+                        "Ignoring highlight with invalid pattern {:?}: {}",
+                        config.pattern,
+                        e
+                    );
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Override color for each cell of `row`, `None` where no rule matched.
+    ///
+    /// Earlier rules take precedence where matches overlap. Returns an empty
+    /// list without allocating when there are no rules.
+    pub fn row_colors(&self, row: &[Cell]) -> Vec<Option<(u8, u8, u8)>> {
+        if self.rules.is_empty() {
+            return Vec::new();
+        }
+        let mut colors = vec![None; row.len()];
+
+        let text: String = row.iter().map(|cell| cell.char).collect();
+        // Matches are reported in bytes; map them back to cell columns
+        let columns: Vec<usize> = text.char_indices().map(|(byte, _)| byte).collect();
+        let column_of = |byte: usize| columns.partition_point(|&start| start < byte);
+
+        for rule in &self.rules {
+            for found in rule.regex.find_iter(&text) {
+                for color in &mut colors[column_of(found.start())..column_of(found.end())] {
+                    color.get_or_insert(rule.color);
+                }
+            }
+        }
+        colors
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{config::HighlightConfig, grid::Cell, styles::Color};
+
+use super::HighlightRules;
+
+const RED: (u8, u8, u8) = (255, 0, 0);
+const CYAN: (u8, u8, u8) = (0, 255, 255);
+
+fn rule(pattern: &str, color: (u8, u8, u8)) -> HighlightConfig {
+    HighlightConfig {
+        pattern: pattern.to_string(),
+        color,
+    }
+}
+
+fn row(text: &str) -> Vec<Cell> {
+    text.chars()
+        .map(|c| Cell::new(c, Color::Foreground, Color::Background))
+        .collect()
+}
+
+#[test]
+fn row_colors_should_cover_matched_cells() {
+    let rules = HighlightRules::new(&[rule("error", RED)]);
+
+    let colors = rules.row_colors(&row("an error here"));
+
+    let expected: Vec<_> = (0..13)
+        .map(|col| (3..8).contains(&col).then_some(RED))
+        .collect();
+    assert_eq!(colors, expected);
+}
+
+#[test]
+fn earlier_rules_should_win_on_overlap() {
+    let rules = HighlightRules::new(&[rule("10\\.0", RED), rule("[\\d.]+", CYAN)]);
+
+    let colors = rules.row_colors(&row("10.0.0.1"));
+
+    assert_eq!(colors[..4], [Some(RED); 4]);
+    assert_eq!(colors[4..], [Some(CYAN); 4]);
+}
+
+#[test]
+fn row_colors_should_map_multibyte_text_to_columns() {
+    let rules = HighlightRules::new(&[rule("ok", RED)]);
+
+    let colors = rules.row_colors(&row("✓─ok"));
+
+    assert_eq!(colors, vec![None, None, Some(RED), Some(RED)]);
+}
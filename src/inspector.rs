@@ -0,0 +1,243 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use vte::Params;
+
+use crate::commands::ClientCommand;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of entries kept for the inspector panel
+const INSPECTOR_CAPACITY: usize = 2000;
+
+/// Longest OSC/DCS payload shown before it is cut off
+const MAX_PAYLOAD: usize = 80;
+
+/// Something seen on the way from PTY output to the grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InspectorEntry {
+    /// An escape sequence or control character, as parsed
+    Sequence(String),
+    /// A command the state machine sent for it
+    Command(String),
+    /// Printed characters, merged into one entry until the next sequence
+    Text(String),
+}
+
+impl InspectorEntry {
+    /// Single line shown in the inspector panel
+    pub fn format(&self) -> String {
+        match self {
+            Self::Sequence(sequence) => format!("<- {}", sequence),
+            Self::Command(command) => format!("   {}", command),
+            Self::Text(text) => format!("   Print {:?}", text),
+        }
+    }
+}
+
+/// Records the parsed sequences and resulting commands while the inspector is shown.
+///
+/// Shared between the PTY read thread, which records, and the UI, which reads. Recording
+/// is off by default so parsing pays nothing for it beyond an atomic load.
+#[derive(Debug, Default)]
+pub struct Inspector {
+    enabled: AtomicBool,
+    entries: Mutex<VecDeque<InspectorEntry>>,
+}
+
+impl Inspector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Start or stop recording; starting drops whatever was recorded before
+    pub fn set_enabled(&self, enabled: bool) {
+        if enabled && !self.is_enabled() {
+            self.lock().clear();
+        }
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn record_sequence(&self, description: String) {
+        self.push(InspectorEntry::Sequence(description));
+    }
+
+    pub fn record_command(&self, command: &ClientCommand) {
+        match command {
+            ClientCommand::Print(c) => {
+                let mut entries = self.lock();
+                if let Some(InspectorEntry::Text(text)) = entries.back_mut() {
+                    text.push(*c);
+                } else {
+                    Self::push_locked(&mut entries, InspectorEntry::Text(c.to_string()));
+                }
+            }
+            command => self.push(InspectorEntry::Command(format!("{:?}", command))),
+        }
+    }
+
+    /// Entries whose text contains `query` (case-insensitive), oldest first
+    pub fn filtered(&self, query: &str) -> Vec<InspectorEntry> {
+        let query = query.to_lowercase();
+        self.lock()
+            .iter()
+            .filter(|entry| query.is_empty() || entry.format().to_lowercase().contains(&query))
+            .cloned()
+            .collect()
+    }
+
+    fn push(&self, entry: InspectorEntry) {
+        Self::push_locked(&mut self.lock(), entry);
+    }
+
+    fn push_locked(entries: &mut VecDeque<InspectorEntry>, entry: InspectorEntry) {
+        if entries.len() == INSPECTOR_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<InspectorEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// State of the inspector panel (toggled with Ctrl+Shift+E)
+#[derive(Debug, Default)]
+pub struct InspectorView {
+    pub show: bool,
+    /// Case-insensitive text filter
+    pub query: String,
+    /// Lines scrolled back from the newest entry
+    pub scroll: usize,
+}
+
+impl InspectorView {
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_add(lines);
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    /// Overlay text for `entries`, fitting at most `rows` lines including the header
+    pub fn text(&mut self, entries: &[InspectorEntry], rows: usize) -> String {
+        let body_rows = rows.saturating_sub(1).max(1);
+        let max_scroll = entries.len().saturating_sub(body_rows);
+        self.scroll = self.scroll.min(max_scroll);
+        let end = entries.len() - self.scroll;
+        let start = end.saturating_sub(body_rows);
+
+        let mut text = format!(
+            "Inspector filter: {}_  ({} of {})  Up/Down: scroll  Esc: close",
+            self.query,
+            end,
+            entries.len()
+        );
+        for entry in &entries[start..end] {
+            text.push('\n');
+            text.push_str(&entry.format());
+        }
+        text
+    }
+}
+
+/// A C0 control or C1 control character, e.g. `C0 LF`
+pub fn describe_control(byte: u8) -> String {
+    let set = if byte < 0x80 { "C0" } else { "C1" };
+    let name = match byte {
+        0x00 => "NUL",
+        0x05 => "ENQ",
+        0x07 => "BEL",
+        0x08 => "BS",
+        0x09 => "HT",
+        0x0a => "LF",
+        0x0b => "VT",
+        0x0c => "FF",
+        0x0d => "CR",
+        0x0e => "SO",
+        0x0f => "SI",
+        0x11 => "XON",
+        0x13 => "XOFF",
+        0x84 => "IND",
+        0x85 => "NEL",
+        0x88 => "HTS",
+        _ => return format!("{} 0x{:02x}", set, byte),
+    };
+    format!("{} {}", set, name)
+}
+
+/// A CSI sequence written the way it appears on the wire, e.g. `CSI ?1049h`
+pub fn describe_csi(params: &Params, intermediates: &[u8], ignore: bool, action: char) -> String {
+    // Private markers (`?`, `>`, `<`, `=`) come before the parameters, the rest after
+    let (markers, trailing): (Vec<u8>, Vec<u8>) = intermediates
+        .iter()
+        .partition(|&&byte| (b'<'..=b'?').contains(&byte));
+    let params = params
+        .iter()
+        .map(|param| {
+            param
+                .iter()
+                .map(u16::to_string)
+                .collect::<Vec<_>>()
+                .join(":")
+        })
+        .collect::<Vec<_>>()
+        .join(";");
+    format!(
+        "CSI {}{}{}{}{}",
+        String::from_utf8_lossy(&markers),
+        params,
+        String::from_utf8_lossy(&trailing),
+        action,
+        if ignore { " (ignored)" } else { "" }
+    )
+}
+
+/// An escape sequence, e.g. `ESC 7` or `ESC ( B`
+pub fn describe_esc(intermediates: &[u8], byte: u8) -> String {
+    let mut text = "ESC ".to_string();
+    for &intermediate in intermediates {
+        text.push(intermediate as char);
+        text.push(' ');
+    }
+    text.push(byte as char);
+    text
+}
+
+/// An operating system command with its parameters, e.g. `OSC 0;title`
+pub fn describe_osc(params: &[&[u8]]) -> String {
+    let params = params
+        .iter()
+        .map(|param| String::from_utf8_lossy(param))
+        .collect::<Vec<_>>()
+        .join(";");
+    format!("OSC {}", truncate(&params))
+}
+
+/// The start of a device control string, e.g. `DCS $q`
+pub fn describe_dcs(params: &Params, intermediates: &[u8], ignore: bool, action: char) -> String {
+    let csi = describe_csi(params, intermediates, ignore, action);
+    format!("DCS {}", &csi["CSI ".len()..])
+}
+
+/// Control characters escaped, cut off after `MAX_PAYLOAD` characters
+fn truncate(text: &str) -> String {
+    let mut escaped: String = text.chars().flat_map(char::escape_debug).collect();
+    if let Some((index, _)) = escaped.char_indices().nth(MAX_PAYLOAD) {
+        escaped.truncate(index);
+        escaped.push('…');
+    }
+    escaped
+}
@@ -0,0 +1,86 @@
+use crate::commands::ClientCommand;
+
+use super::{describe_csi, describe_esc, describe_osc, Inspector, InspectorEntry, InspectorView};
+
+#[test]
+fn printed_characters_should_merge_until_the_next_sequence() {
+    let inspector = Inspector::new();
+    inspector.set_enabled(true);
+    inspector.record_command(&ClientCommand::Print('h'));
+    inspector.record_command(&ClientCommand::Print('i'));
+    inspector.record_sequence("C0 LF".to_string());
+    inspector.record_command(&ClientCommand::Print('!'));
+
+    assert_eq!(
+        inspector.filtered(""),
+        [
+            InspectorEntry::Text("hi".to_string()),
+            InspectorEntry::Sequence("C0 LF".to_string()),
+            InspectorEntry::Text("!".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn filter_should_match_case_insensitively() {
+    let inspector = Inspector::new();
+    inspector.set_enabled(true);
+    inspector.record_sequence("CSI 2J".to_string());
+    inspector.record_command(&ClientCommand::ClearScreen);
+    inspector.record_command(&ClientCommand::Print('x'));
+
+    assert_eq!(
+        inspector.filtered("clear"),
+        [InspectorEntry::Command("ClearScreen".to_string())]
+    );
+}
+
+#[test]
+fn enabling_should_drop_old_entries() {
+    let inspector = Inspector::new();
+    inspector.set_enabled(true);
+    inspector.record_sequence("ESC 7".to_string());
+    inspector.set_enabled(false);
+    inspector.set_enabled(true);
+
+    assert!(inspector.filtered("").is_empty());
+}
+
+#[test]
+fn csi_should_be_described_as_on_the_wire() {
+    let mut parser = vte::Parser::new();
+    let mut performer = Csi(Vec::new());
+    parser.advance(&mut performer, b"\x1b[?1049h\x1b[38:2::1:2:3m\x1b[2 q");
+
+    assert_eq!(performer.0, ["CSI ?1049h", "CSI 38:2:0:1:2:3m", "CSI 2 q"]);
+    assert_eq!(describe_esc(b"(", b'B'), "ESC ( B");
+    assert_eq!(describe_osc(&[b"2", b"a\x01b"]), "OSC 2;a\\u{1}b");
+}
+
+#[test]
+fn view_should_show_newest_entries_that_fit() {
+    let entries: Vec<_> = (0..10)
+        .map(|i| InspectorEntry::Sequence(format!("ESC {}", i)))
+        .collect();
+    let mut view = InspectorView::default();
+    view.scroll_up(100);
+
+    let text = view.text(&entries, 4);
+    let lines: Vec<_> = text.lines().skip(1).collect();
+    assert_eq!(lines, ["<- ESC 0", "<- ESC 1", "<- ESC 2"]);
+}
+
+struct Csi(Vec<String>);
+
+impl vte::Perform for Csi {
+    fn csi_dispatch(
+        &mut self,
+        params: &vte::Params,
+        intermediates: &[u8],
+        ignore: bool,
+        action: char,
+    ) {
+        self.0
+            .push(describe_csi(params, intermediates, ignore, action));
+    }
+}
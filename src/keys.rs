@@ -0,0 +1,148 @@
+use winit::keyboard::{KeyCode, ModifiersState};
+
+#[cfg(test)]
+mod tests;
+
+/// How a special key is sent, following xterm
+enum Sequence {
+    /// `CSI final`, or `SS3 final` in application cursor key mode
+    Cursor(u8),
+    /// `SS3 final`, e.g. F1-F4
+    Ss3(u8),
+    /// `CSI number ~`
+    Tilde(u8),
+}
+
+fn sequence(key: KeyCode) -> Option<Sequence> {
+    let sequence = match key {
+        KeyCode::ArrowUp => Sequence::Cursor(b'A'),
+        KeyCode::ArrowDown => Sequence::Cursor(b'B'),
+        KeyCode::ArrowRight => Sequence::Cursor(b'C'),
+        KeyCode::ArrowLeft => Sequence::Cursor(b'D'),
+        KeyCode::Home => Sequence::Cursor(b'H'),
+        KeyCode::End => Sequence::Cursor(b'F'),
+        KeyCode::Insert => Sequence::Tilde(2),
+        KeyCode::Delete => Sequence::Tilde(3),
+        KeyCode::PageUp => Sequence::Tilde(5),
+        KeyCode::PageDown => Sequence::Tilde(6),
+        KeyCode::F1 => Sequence::Ss3(b'P'),
+        KeyCode::F2 => Sequence::Ss3(b'Q'),
+        KeyCode::F3 => Sequence::Ss3(b'R'),
+        KeyCode::F4 => Sequence::Ss3(b'S'),
+        KeyCode::F5 => Sequence::Tilde(15),
+        KeyCode::F6 => Sequence::Tilde(17),
+        KeyCode::F7 => Sequence::Tilde(18),
+        KeyCode::F8 => Sequence::Tilde(19),
+        KeyCode::F9 => Sequence::Tilde(20),
+        KeyCode::F10 => Sequence::Tilde(21),
+        KeyCode::F11 => Sequence::Tilde(23),
+        KeyCode::F12 => Sequence::Tilde(24),
+        _ => return None,
+    };
+    Some(sequence)
+}
+
+/// xterm's modifier parameter: 1 plus Shift = 1, Alt = 2, Ctrl = 4. `None` without modifiers.
+fn modifier_param(modifiers: ModifiersState) -> Option<u8> {
+    let param = 1
+        + u8::from(modifiers.shift_key())
+        + 2 * u8::from(modifiers.alt_key())
+        + 4 * u8::from(modifiers.control_key());
+    (param > 1).then_some(param)
+}
+
+/// Control character for Ctrl with `key`: Ctrl+A = 1 ... Ctrl+Z = 26, plus the ASCII
+/// punctuation controls
+fn control_code(key: KeyCode) -> Option<u8> {
+    let letters = [
+        KeyCode::KeyA,
+        KeyCode::KeyB,
+        KeyCode::KeyC,
+        KeyCode::KeyD,
+        KeyCode::KeyE,
+        KeyCode::KeyF,
+        KeyCode::KeyG,
+        KeyCode::KeyH,
+        KeyCode::KeyI,
+        KeyCode::KeyJ,
+        KeyCode::KeyK,
+        KeyCode::KeyL,
+        KeyCode::KeyM,
+        KeyCode::KeyN,
+        KeyCode::KeyO,
+        KeyCode::KeyP,
+        KeyCode::KeyQ,
+        KeyCode::KeyR,
+        KeyCode::KeyS,
+        KeyCode::KeyT,
+        KeyCode::KeyU,
+        KeyCode::KeyV,
+        KeyCode::KeyW,
+        KeyCode::KeyX,
+        KeyCode::KeyY,
+        KeyCode::KeyZ,
+    ];
+    if let Some(index) = letters.iter().position(|&letter| letter == key) {
+        return Some(index as u8 + 1);
+    }
+    match key {
+        KeyCode::Space | KeyCode::Digit2 => Some(0),
+        KeyCode::BracketLeft => Some(27),
+        KeyCode::Backslash => Some(28),
+        KeyCode::BracketRight => Some(29),
+        _ => None,
+    }
+}
+
+/// Bytes to send to the PTY for `key`, or `None` when the key's text should be sent instead.
+///
+/// `modifiers` should only include Alt when it acts as Meta; Alt then prefixes the
+/// character with Escape, and special keys carry it in their modifier parameter.
+pub fn encode(key: KeyCode, modifiers: ModifiersState, cursor_keys_mode: bool) -> Option<Vec<u8>> {
+    let meta = |bytes: &[u8]| {
+        let mut data = Vec::with_capacity(bytes.len() + 1);
+        if modifiers.alt_key() {
+            data.push(0x1b);
+        }
+        data.extend_from_slice(bytes);
+        Some(data)
+    };
+
+    if let Some(sequence) = sequence(key) {
+        let param = modifier_param(modifiers);
+        let data = match (sequence, param) {
+            (Sequence::Cursor(c), None) if cursor_keys_mode => format!("\x1bO{}", c as char),
+            (Sequence::Cursor(c), None) => format!("\x1b[{}", c as char),
+            (Sequence::Ss3(c), None) => format!("\x1bO{}", c as char),
+            (Sequence::Cursor(c) | Sequence::Ss3(c), Some(param)) => {
+                format!("\x1b[1;{}{}", param, c as char)
+            }
+            (Sequence::Tilde(n), None) => format!("\x1b[{}~", n),
+            (Sequence::Tilde(n), Some(param)) => format!("\x1b[{};{}~", n, param),
+        };
+        return Some(data.into_bytes());
+    }
+
+    match key {
+        // DEL for xterm-256color compatibility; Ctrl+Backspace sends ^H
+        KeyCode::Backspace if modifiers.control_key() => meta(&[0x08]),
+        KeyCode::Backspace => meta(&[0x7f]),
+        KeyCode::Escape => meta(&[0x1b]),
+        KeyCode::Enter | KeyCode::NumpadEnter => meta(b"\r"),
+        KeyCode::Tab if modifiers.shift_key() => Some(b"\x1b[Z".to_vec()),
+        KeyCode::Tab => meta(b"\t"),
+        KeyCode::Space if !modifiers.control_key() => meta(b" "),
+        _ if modifiers.control_key() => control_code(key).and_then(|code| meta(&[code])),
+        _ => None,
+    }
+}
+
+/// UTF-8 bytes to send for typed `text`, prefixed with Escape when Alt acts as Meta
+pub fn encode_text(text: &str, meta: bool) -> Vec<u8> {
+    let mut data = Vec::with_capacity(text.len() + 1);
+    if meta {
+        data.push(0x1b);
+    }
+    data.extend_from_slice(text.as_bytes());
+    data
+}
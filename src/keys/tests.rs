@@ -0,0 +1,80 @@
+use winit::keyboard::{KeyCode, ModifiersState};
+
+use super::{encode, encode_text};
+
+const NONE: ModifiersState = ModifiersState::empty();
+
+#[test]
+fn arrows_should_follow_cursor_key_mode() {
+    assert_eq!(
+        encode(KeyCode::ArrowUp, NONE, false),
+        Some(b"\x1b[A".to_vec())
+    );
+    assert_eq!(
+        encode(KeyCode::ArrowUp, NONE, true),
+        Some(b"\x1bOA".to_vec())
+    );
+    assert_eq!(encode(KeyCode::Home, NONE, false), Some(b"\x1b[H".to_vec()));
+}
+
+#[test]
+fn modified_special_keys_should_carry_xterm_modifier_parameter() {
+    let ctrl = ModifiersState::CONTROL;
+    let shift_alt = ModifiersState::SHIFT | ModifiersState::ALT;
+
+    assert_eq!(
+        encode(KeyCode::ArrowLeft, ctrl, true),
+        Some(b"\x1b[1;5D".to_vec())
+    );
+    assert_eq!(
+        encode(KeyCode::Delete, shift_alt, false),
+        Some(b"\x1b[3;4~".to_vec())
+    );
+    assert_eq!(encode(KeyCode::F1, NONE, false), Some(b"\x1bOP".to_vec()));
+    assert_eq!(
+        encode(KeyCode::F1, ctrl, false),
+        Some(b"\x1b[1;5P".to_vec())
+    );
+    assert_eq!(encode(KeyCode::F5, NONE, false), Some(b"\x1b[15~".to_vec()));
+}
+
+#[test]
+fn control_and_meta_should_encode_characters() {
+    let ctrl = ModifiersState::CONTROL;
+
+    assert_eq!(encode(KeyCode::KeyC, ctrl, false), Some(vec![3]));
+    assert_eq!(encode(KeyCode::BracketLeft, ctrl, false), Some(vec![27]));
+    assert_eq!(encode(KeyCode::Space, ctrl, false), Some(vec![0]));
+    assert_eq!(
+        encode(KeyCode::KeyX, ctrl | ModifiersState::ALT, false),
+        Some(vec![0x1b, 24])
+    );
+    assert_eq!(
+        encode(KeyCode::Backspace, ModifiersState::ALT, false),
+        Some(vec![0x1b, 0x7f])
+    );
+    assert_eq!(
+        encode(KeyCode::Tab, ModifiersState::SHIFT, false),
+        Some(b"\x1b[Z".to_vec())
+    );
+}
+
+#[test]
+fn plain_characters_should_be_left_to_text_input() {
+    assert_eq!(encode(KeyCode::KeyA, NONE, false), None);
+    assert_eq!(encode(KeyCode::KeyA, ModifiersState::SHIFT, false), None);
+    assert_eq!(
+        encode(KeyCode::Digit1, ModifiersState::CONTROL, false),
+        None
+    );
+}
+
+#[test]
+fn typed_text_should_be_sent_as_utf8() {
+    assert_eq!(encode_text("a", false), b"a".to_vec());
+    assert_eq!(
+        encode_text("é€中", false),
+        vec![0xc3, 0xa9, 0xe2, 0x82, 0xac, 0xe4, 0xb8, 0xad]
+    );
+    assert_eq!(encode_text("ü", true), vec![0x1b, 0xc3, 0xbc]);
+}
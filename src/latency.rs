@@ -0,0 +1,183 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::{commands::ShellMark, grid::Grid};
+
+#[cfg(test)]
+mod tests;
+
+/// Characters typed as the probe, picking one the target cell does not already show;
+/// erased with a Backspace once it has been seen
+const PROBE_CHARS: [char; 2] = ['x', 'z'];
+/// Erases the probe, both at the shell prompt and in the tty's line editing
+const ERASE: u8 = 0x7f;
+/// Time between probes
+const PROBE_INTERVAL: Duration = Duration::from_millis(500);
+/// A probe that has not been echoed by then is given up on and erased
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Longest wait between probes after repeated misses
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Samples kept for the statistics
+const MAX_SAMPLES: usize = 100;
+
+/// A probe keypress waiting for its echo to reach the screen
+#[derive(Debug, Clone, Copy)]
+struct Probe {
+    sent: Instant,
+    /// Cell the echo is expected in
+    pos: (usize, usize),
+    /// Character typed, different from what the cell showed when it was sent
+    c: char,
+    echoed: bool,
+}
+
+/// Keypress-to-photon latency statistics over the recent samples
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LatencyStats {
+    pub samples: usize,
+    /// Probes that were never echoed
+    pub missed: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+/// Measures how long a typed character takes to come back from the shell and be shown.
+///
+/// A probe character is typed at the cursor; once the shell echoes it into the grid, the
+/// next presented frame (after the GPU has finished drawing it) ends the measurement and
+/// the probe is erased again.
+///
+/// No probes are typed while the shell reports (with OSC 133) that a command is running, and
+/// misses back off, so probes do not pile up in the input of a busy foreground program.
+#[derive(Debug)]
+pub struct LatencyProbe {
+    pending: Option<Probe>,
+    next_probe: Instant,
+    samples: VecDeque<Duration>,
+    missed: usize,
+    /// Misses since the last echoed probe
+    consecutive_misses: u32,
+    command_running: bool,
+}
+
+impl LatencyProbe {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            pending: None,
+            next_probe: now,
+            samples: VecDeque::new(),
+            missed: 0,
+            consecutive_misses: 0,
+            command_running: false,
+        }
+    }
+
+    /// Follow the shell's marks: no probes while a command is running
+    pub fn shell_mark(&mut self, mark: ShellMark) {
+        match mark {
+            ShellMark::CommandExecuted => self.command_running = true,
+            ShellMark::PromptStart | ShellMark::CommandFinished(_) => self.command_running = false,
+            ShellMark::CommandStart => {}
+        }
+    }
+
+    /// Give up on a probe that has not been echoed in time. Returns the keypress that erases
+    /// it, so it does not stay behind in the input of whatever is running.
+    pub fn expire(&mut self, now: Instant) -> Option<Vec<u8>> {
+        let probe = self
+            .pending
+            .take_if(|probe| now.duration_since(probe.sent) >= PROBE_TIMEOUT)?;
+        log::debug!("Latency probe at {:?} was not echoed", probe.pos);
+        self.missed += 1;
+        self.consecutive_misses += 1;
+        let backoff = PROBE_INTERVAL * 2u32.saturating_pow(self.consecutive_misses);
+        self.next_probe = now + backoff.min(MAX_BACKOFF);
+        Some(vec![ERASE])
+    }
+
+    /// Drop a pending probe because other input is about to follow it. Returns the keypress
+    /// that erases it, which has to be sent before that input.
+    pub fn cancel(&mut self, now: Instant) -> Option<Vec<u8>> {
+        self.pending.take()?;
+        self.next_probe = now + PROBE_INTERVAL;
+        Some(vec![ERASE])
+    }
+
+    /// The probe keypress to send when one is due, typed at the grid's cursor
+    pub fn start(&mut self, grid: &Grid, now: Instant) -> Option<Vec<u8>> {
+        if self.pending.is_some() || now < self.next_probe || self.command_running {
+            return None;
+        }
+        let pos = grid.cursor_pos;
+        let shown = cell_char(grid, pos);
+        let c = PROBE_CHARS
+            .into_iter()
+            .find(|&c| Some(c) != shown)
+            .unwrap_or(PROBE_CHARS[0]);
+        self.pending = Some(Probe {
+            sent: now,
+            pos,
+            c,
+            echoed: false,
+        });
+        Some(c.to_string().into_bytes())
+    }
+
+    /// Look for the probe's echo in the grid. Returns whether it has been echoed and is
+    /// waiting to be presented.
+    pub fn check_echo(&mut self, grid: &Grid) -> bool {
+        let Some(probe) = &mut self.pending else {
+            return false;
+        };
+        if !probe.echoed {
+            probe.echoed = cell_char(grid, probe.pos) == Some(probe.c);
+        }
+        probe.echoed
+    }
+
+    /// A frame showing the echo finished drawing at `now`. Records the sample and returns
+    /// the keypress that erases the probe.
+    pub fn presented(&mut self, now: Instant) -> Option<Vec<u8>> {
+        let probe = self.pending.take_if(|probe| probe.echoed)?;
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(now.duration_since(probe.sent));
+        self.next_probe = now + PROBE_INTERVAL;
+        self.consecutive_misses = 0;
+        Some(vec![ERASE])
+    }
+
+    pub fn stats(&self) -> LatencyStats {
+        let mut sorted: Vec<Duration> = self.samples.iter().copied().collect();
+        sorted.sort();
+        let percentile = |p: usize| {
+            sorted
+                .get((sorted.len() * p / 100).min(sorted.len().saturating_sub(1)))
+                .copied()
+                .unwrap_or_default()
+        };
+        LatencyStats {
+            samples: sorted.len(),
+            missed: self.missed,
+            min: sorted.first().copied().unwrap_or_default(),
+            median: percentile(50),
+            p95: percentile(95),
+            max: sorted.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+/// Character shown in the cell at absolute `pos`, if it is inside the grid
+fn cell_char(grid: &Grid, (row, col): (usize, usize)) -> Option<char> {
+    if col >= grid.width as usize {
+        return None;
+    }
+    grid.active_grid_ref()
+        .get(row * grid.width as usize + col)
+        .map(|cell| cell.char)
+}
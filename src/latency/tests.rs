@@ -0,0 +1,121 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    commands::{ClientCommand, ShellMark},
+    config::Config,
+    terminal::Terminal,
+};
+
+use super::{LatencyProbe, PROBE_INTERVAL, PROBE_TIMEOUT};
+
+fn terminal() -> Terminal {
+    Terminal::new(&Config {
+        cols: 10,
+        rows: 3,
+        ..Config::default()
+    })
+}
+
+#[test]
+fn probe_should_be_measured_from_keypress_to_presented_echo() {
+    let start = Instant::now();
+    let mut terminal = terminal();
+    terminal.handle_command(ClientCommand::MoveCursor(0, 2));
+    let mut probe = LatencyProbe::new(start);
+
+    assert_eq!(probe.start(&terminal.grid, start), Some(b"x".to_vec()));
+    assert_eq!(probe.start(&terminal.grid, start), None);
+    assert!(!probe.check_echo(&terminal.grid));
+    assert_eq!(probe.presented(start + Duration::from_millis(5)), None);
+
+    terminal.handle_command(ClientCommand::Print('x'));
+    assert!(probe.check_echo(&terminal.grid));
+    assert_eq!(
+        probe.presented(start + Duration::from_millis(12)),
+        Some(vec![0x7f])
+    );
+
+    let stats = probe.stats();
+    assert_eq!(stats.samples, 1);
+    assert_eq!(stats.median, Duration::from_millis(12));
+}
+
+#[test]
+fn next_probe_should_wait_for_the_interval() {
+    let start = Instant::now();
+    let mut terminal = terminal();
+    let mut probe = LatencyProbe::new(start);
+    probe.start(&terminal.grid, start);
+    terminal.handle_command(ClientCommand::Print('x'));
+    probe.check_echo(&terminal.grid);
+    let presented = start + Duration::from_millis(10);
+    probe.presented(presented);
+    terminal.handle_command(ClientCommand::MoveCursor(0, 0));
+
+    assert_eq!(probe.start(&terminal.grid, presented), None);
+    assert!(probe
+        .start(&terminal.grid, presented + PROBE_INTERVAL)
+        .is_some());
+}
+
+#[test]
+fn unanswered_probe_should_be_erased_and_count_as_missed() {
+    let start = Instant::now();
+    let terminal = terminal();
+    let mut probe = LatencyProbe::new(start);
+    probe.start(&terminal.grid, start);
+
+    assert_eq!(probe.expire(start + Duration::from_secs(1)), None);
+    let timed_out = start + PROBE_TIMEOUT;
+    assert_eq!(probe.expire(timed_out), Some(vec![0x7f]));
+    assert_eq!(probe.stats().missed, 1);
+    assert_eq!(probe.stats().samples, 0);
+    // Backs off instead of typing the next probe straight away
+    assert_eq!(
+        probe.start(&terminal.grid, timed_out + PROBE_INTERVAL),
+        None
+    );
+}
+
+#[test]
+fn probe_should_not_be_typed_while_a_command_runs() {
+    let start = Instant::now();
+    let terminal = terminal();
+    let mut probe = LatencyProbe::new(start);
+
+    probe.shell_mark(ShellMark::CommandExecuted);
+    assert_eq!(probe.start(&terminal.grid, start), None);
+    probe.shell_mark(ShellMark::CommandFinished(Some(0)));
+    assert!(probe.start(&terminal.grid, start).is_some());
+}
+
+#[test]
+fn probe_should_not_count_a_character_already_in_the_cell() {
+    let start = Instant::now();
+    let mut terminal = terminal();
+    terminal.handle_command(ClientCommand::Print('x'));
+    terminal.handle_command(ClientCommand::MoveCursor(0, 0));
+    let mut probe = LatencyProbe::new(start);
+
+    assert_eq!(probe.start(&terminal.grid, start), Some(b"z".to_vec()));
+    assert!(!probe.check_echo(&terminal.grid));
+    terminal.handle_command(ClientCommand::Print('z'));
+    assert!(probe.check_echo(&terminal.grid));
+}
+
+#[test]
+fn cancelled_probe_should_be_erased_without_counting() {
+    let start = Instant::now();
+    let terminal = terminal();
+    let mut probe = LatencyProbe::new(start);
+    probe.start(&terminal.grid, start);
+
+    assert_eq!(probe.cancel(start), Some(vec![0x7f]));
+    assert_eq!(probe.cancel(start), None);
+    assert_eq!(probe.stats().missed, 0);
+    assert_eq!(probe.expire(start + PROBE_TIMEOUT), None);
+    assert_eq!(probe.start(&terminal.grid, start), None);
+    assert!(probe
+        .start(&terminal.grid, start + PROBE_INTERVAL)
+        .is_some());
+}
@@ -0,0 +1,45 @@
+pub mod accessibility;
+pub mod actions;
+pub mod app;
+pub mod blink;
+pub mod clipboard;
+pub mod command_mode;
+pub mod commands;
+pub mod config;
+pub mod cursor_animation;
+pub mod debug_socket;
+pub mod doctor;
+pub mod fonts;
+pub mod frontend;
+pub mod gpu;
+pub mod grid;
+pub mod highlights;
+pub mod inspector;
+pub mod keys;
+pub mod latency;
+pub mod links;
+pub mod logging;
+#[cfg(target_os = "macos")]
+pub mod menu;
+pub mod metrics;
+pub mod minimap;
+pub mod monitor;
+pub mod parser;
+pub mod paths;
+pub mod prediction;
+pub mod profiling;
+pub mod recording;
+pub mod renderer;
+pub mod selection;
+pub mod shell_integration;
+pub mod signals;
+pub mod snapshot;
+pub mod statemachine;
+pub mod status_bar;
+pub mod styles;
+pub mod term;
+pub mod terminal;
+pub mod themes;
+pub mod triggers;
+pub mod ui;
+pub mod viewport;
@@ -0,0 +1,298 @@
+use std::{process::Command, sync::OnceLock};
+
+use regex::Regex;
+
+use crate::{
+    grid::{Cell, Grid},
+    triggers,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Keys hint labels are made from unless configured otherwise
+pub const DEFAULT_HINT_ALPHABET: &str = "asdfghjkl";
+
+/// Punctuation that usually ends the sentence around a URL rather than the URL itself
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', '\'', '"'];
+
+/// A link on screen: an OSC 8 hyperlink or a URL found in the text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    /// Absolute row and column of its first cell
+    pub row: usize,
+    pub col: usize,
+    pub target: String,
+}
+
+fn url_regex() -> &'static Regex {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    URL.get_or_init(|| {
+        Regex::new(r#"(?:https?|ftp|file)://[^\s<>"'`{}|\\^]+"#).expect("valid URL pattern")
+    })
+}
+
+/// Links in the visible rows of `grid`, top to bottom and left to right
+pub fn visible_links(grid: &Grid) -> Vec<Link> {
+    let width = grid.width as usize;
+    let cells = grid.active_grid_ref();
+    let mut links: Vec<Link> = Vec::new();
+    for row in grid.top_row()..=grid.bottom_row() {
+        let start = (row * width).min(cells.len());
+        let end = (start + width).min(cells.len());
+        for link in row_links(row, &cells[start..end]) {
+            // A hyperlink wrapped onto this row continues the one before it
+            let continues = link.col == 0
+                && links
+                    .last()
+                    .is_some_and(|last| last.row + 1 == row && last.target == link.target);
+            if !continues {
+                links.push(link);
+            }
+        }
+    }
+    links
+}
+
+/// OSC 8 hyperlinks and detected URLs in one row of cells. Hyperlinks win where the two
+/// overlap.
+pub fn row_links(row: usize, cells: &[Cell]) -> Vec<Link> {
+    let mut links = Vec::new();
+
+    let mut col = 0;
+    while col < cells.len() {
+        let Some(target) = &cells[col].link else {
+            col += 1;
+            continue;
+        };
+        links.push(Link {
+            row,
+            col,
+            target: target.to_string(),
+        });
+        while cells
+            .get(col)
+            .is_some_and(|cell| cell.link.as_ref() == Some(target))
+        {
+            col += 1;
+        }
+    }
+
+    let text: String = cells.iter().map(|cell| cell.char).collect();
+    // Matches are reported in bytes; map them back to cell columns
+    let columns: Vec<usize> = text.char_indices().map(|(byte, _)| byte).collect();
+    let column_of = |byte: usize| columns.partition_point(|&start| start < byte);
+    for found in url_regex().find_iter(&text) {
+        let url = trim_url(found.as_str());
+        let (start, end) = (
+            column_of(found.start()),
+            column_of(found.start() + url.len()),
+        );
+        if cells[start..end].iter().all(|cell| cell.link.is_none()) {
+            links.push(Link {
+                row,
+                col: start,
+                target: url.to_string(),
+            });
+        }
+    }
+
+    links.sort_by_key(|link| link.col);
+    links
+}
+
+/// `url` without trailing punctuation, and without closing brackets it does not open
+pub fn trim_url(mut url: &str) -> &str {
+    loop {
+        let trimmed = url.trim_end_matches(TRAILING_PUNCTUATION);
+        let trimmed = match trimmed.chars().last() {
+            Some(close @ (')' | ']')) => {
+                let open = if close == ')' { '(' } else { '[' };
+                let balance = trimmed.matches(open).count() as isize
+                    - trimmed.matches(close).count() as isize;
+                if balance < 0 {
+                    &trimmed[..trimmed.len() - 1]
+                } else {
+                    trimmed
+                }
+            }
+            _ => trimmed,
+        };
+        if trimmed == url {
+            return url;
+        }
+        url = trimmed;
+    }
+}
+
+/// Labels for `count` hints, all the same length so that none is a prefix of another
+pub fn hint_labels(alphabet: &[char], count: usize) -> Vec<String> {
+    if alphabet.len() < 2 || count == 0 {
+        return Vec::new();
+    }
+    let mut length = 1;
+    while alphabet.len().pow(length) < count {
+        length += 1;
+    }
+    (0..count)
+        .map(|mut index| {
+            let mut label = vec![alphabet[0]; length as usize];
+            for slot in label.iter_mut().rev() {
+                *slot = alphabet[index % alphabet.len()];
+                index /= alphabet.len();
+            }
+            label.into_iter().collect()
+        })
+        .collect()
+}
+
+/// A labelled link in hint mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    pub label: String,
+    pub link: Link,
+}
+
+/// What to do with the link whose label was typed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HintAction {
+    Open(String),
+    Copy(String),
+}
+
+/// Keyboard link selection: every visible link gets a label, and typing one opens the link,
+/// or copies it when any of the label was typed in upper case
+#[derive(Debug, Default)]
+pub struct Hints {
+    hints: Vec<Hint>,
+    typed: String,
+    copy: bool,
+}
+
+impl Hints {
+    /// Label `links` with keys from `alphabet` (lower case, at least two distinct keys)
+    pub fn new(links: Vec<Link>, alphabet: &[char]) -> Self {
+        let hints = hint_labels(alphabet, links.len())
+            .into_iter()
+            .zip(links)
+            .map(|(label, link)| Hint { label, link })
+            .collect();
+        Self {
+            hints,
+            typed: String::new(),
+            copy: false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hints.is_empty()
+    }
+
+    /// Hints still reachable with what was typed so far
+    pub fn matching(&self) -> Vec<Hint> {
+        self.hints
+            .iter()
+            .filter(|hint| hint.label.starts_with(&self.typed))
+            .cloned()
+            .collect()
+    }
+
+    /// Take a typed key. Keys that match no label are ignored; the action is returned once a
+    /// whole label has been typed.
+    pub fn input(&mut self, c: char) -> Option<HintAction> {
+        let mut typed = self.typed.clone();
+        typed.extend(c.to_lowercase());
+        let hint = self
+            .hints
+            .iter()
+            .find(|hint| hint.label.starts_with(&typed))?;
+        self.typed = typed;
+        self.copy |= c.is_uppercase();
+
+        (hint.label == self.typed).then(|| {
+            let target = hint.link.target.clone();
+            if self.copy {
+                HintAction::Copy(target)
+            } else {
+                HintAction::Open(target)
+            }
+        })
+    }
+
+    pub fn backspace(&mut self) {
+        self.typed.pop();
+        if self.typed.is_empty() {
+            self.copy = false;
+        }
+    }
+}
+
+/// The configured hint alphabet as distinct lower-case keys, or the default when it has
+/// fewer than two
+pub fn hint_alphabet(configured: &str) -> Vec<char> {
+    let mut alphabet: Vec<char> = Vec::new();
+    for c in configured.chars().flat_map(char::to_lowercase) {
+        if !c.is_whitespace() && !alphabet.contains(&c) {
+            alphabet.push(c);
+        }
+    }
+    if alphabet.len() < 2 {
+        log::warn!(
+            "Hint alphabet {:?} needs at least two distinct keys, using {:?}",
+            configured,
+            DEFAULT_HINT_ALPHABET
+        );
+        return DEFAULT_HINT_ALPHABET.chars().collect();
+    }
+    alphabet
+}
+
+/// Schemes of the links that may be opened. Anything else, e.g. an OSC 8 hyperlink a
+/// program set to `javascript:` or a command-line option, is refused.
+pub const OPEN_SCHEMES: &[&str] = &["http", "https", "ftp", "file"];
+
+/// Whether `target` starts with one of `OPEN_SCHEMES`
+pub fn can_open(target: &str) -> bool {
+    target.split_once(':').is_some_and(|(scheme, _)| {
+        OPEN_SCHEMES
+            .iter()
+            .any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+    })
+}
+
+/// The command that opens `target` with the platform's default handler, or `None` when its
+/// scheme is not allowed
+pub fn open_command(target: &str) -> Option<Command> {
+    if !can_open(target) {
+        return None;
+    }
+
+    // None of these take `--` to end their options (xdg-open rejects it); an allowed target
+    // starts with its scheme, so it can't be mistaken for an option either
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        // Goes through ShellExecute without cmd.exe, which would interpret `&` in URLs
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+
+    command.arg(target);
+    Some(command)
+}
+
+/// Open `target` with the platform's default handler
+pub fn open(target: &str) {
+    match open_command(target) {
+        Some(command) => triggers::spawn_detached(command),
+        None => log::warn!(
+            "Not opening {:?}: only {} links can be opened",
+            target,
+            OPEN_SCHEMES.join(", ")
+        ),
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{commands::ClientCommand, config::Config, terminal::Terminal};
+
+use super::{
+    can_open, hint_labels, open_command, trim_url, visible_links, HintAction, Hints, Link,
+};
+
+fn terminal() -> Terminal {
+    Terminal::new(&Config {
+        cols: 20,
+        rows: 3,
+        ..Config::default()
+    })
+}
+
+fn print(terminal: &mut Terminal, text: &str) {
+    for c in text.chars() {
+        terminal.handle_command(ClientCommand::Print(c));
+    }
+}
+
+fn link(row: usize, col: usize, target: &str) -> Link {
+    Link {
+        row,
+        col,
+        target: target.to_string(),
+    }
+}
+
+#[test]
+fn osc_8_links_and_urls_should_be_found_on_screen() {
+    let mut terminal = terminal();
+    print(&mut terminal, "see ");
+    terminal.handle_command(ClientCommand::SetHyperlink(Some(
+        "https://a.dev".to_string(),
+    )));
+    print(&mut terminal, "docs");
+    terminal.handle_command(ClientCommand::SetHyperlink(None));
+    print(&mut terminal, " or");
+    terminal.handle_command(ClientCommand::MoveCursor(1, 0));
+    print(&mut terminal, "(http://b.io/x).");
+
+    assert_eq!(
+        visible_links(&terminal.grid),
+        [link(0, 4, "https://a.dev"), link(1, 1, "http://b.io/x")]
+    );
+}
+
+#[test]
+fn wrapped_hyperlink_should_be_found_once() {
+    let mut terminal = terminal();
+    print(&mut terminal, "x".repeat(18).as_str());
+    terminal.handle_command(ClientCommand::SetHyperlink(Some("file:///tmp".to_string())));
+    print(&mut terminal, "long name");
+
+    assert_eq!(visible_links(&terminal.grid), [link(0, 18, "file:///tmp")]);
+}
+
+#[test]
+fn trailing_punctuation_should_not_be_part_of_a_url() {
+    assert_eq!(trim_url("https://x.org/a."), "https://x.org/a");
+    assert_eq!(trim_url("https://x.org/a),"), "https://x.org/a");
+    assert_eq!(
+        trim_url("https://en.wikipedia.org/wiki/Rust_(language)"),
+        "https://en.wikipedia.org/wiki/Rust_(language)"
+    );
+}
+
+#[test]
+fn labels_should_have_equal_length() {
+    let alphabet = ['a', 's', 'd'];
+
+    assert_eq!(hint_labels(&alphabet, 2), ["a", "s"]);
+    assert_eq!(hint_labels(&alphabet, 4), ["aa", "as", "ad", "sa"]);
+}
+
+#[test]
+fn typing_a_label_should_open_and_upper_case_should_copy() {
+    let links = vec![link(0, 0, "https://a"), link(1, 0, "https://b")];
+
+    let mut hints = Hints::new(links.clone(), &['a', 's']);
+    assert_eq!(hints.input('x'), None);
+    assert_eq!(hints.matching().len(), 2);
+    assert_eq!(
+        hints.input('s'),
+        Some(HintAction::Open("https://b".to_string()))
+    );
+
+    let mut hints = Hints::new(links, &['a', 's']);
+    assert_eq!(
+        hints.input('A'),
+        Some(HintAction::Copy("https://a".to_string()))
+    );
+}
+
+#[test]
+fn only_allowed_schemes_should_be_opened() {
+    assert!(can_open("https://example.com"));
+    assert!(can_open("HTTP://example.com"));
+    assert!(can_open("file:///tmp/notes.txt"));
+
+    assert!(!can_open("javascript:alert(1)"));
+    assert!(!can_open("ssh://host"));
+    assert!(!can_open("--help"));
+    assert!(!can_open("example.com"));
+    assert!(open_command("javascript:alert(1)").is_none());
+
+    let command = open_command("https://example.com/?a=1&b=2").unwrap();
+    assert_eq!(
+        command.get_args().last(),
+        Some(std::ffi::OsStr::new("https://example.com/?a=1&b=2"))
+    );
+}
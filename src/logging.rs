@@ -0,0 +1,217 @@
+use std::{
+    collections::VecDeque,
+    io::Write,
+    sync::{Mutex, OnceLock},
+};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of log records kept for the in-window log viewer
+const LOG_CAPACITY: usize = 1000;
+
+/// Least severe level captured from this crate (dependencies are captured from `Warn` up)
+const CAPTURE_LEVEL: Level = Level::Info;
+
+/// A captured log record
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub time: chrono::DateTime<chrono::Local>,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+impl LogEntry {
+    /// Single line shown in the log viewer
+    pub fn format(&self) -> String {
+        format!(
+            "{} {:<5} {}: {}",
+            self.time.format("%H:%M:%S%.3f"),
+            self.level,
+            self.target,
+            self.message
+        )
+    }
+}
+
+/// Fixed-size ring buffer of recent log records
+pub struct LogBuffer {
+    capacity: usize,
+    entries: Mutex<VecDeque<LogEntry>>,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn push(&self, entry: LogEntry) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Entries at or above `min_level` whose text contains `query` (case-insensitive), oldest first
+    pub fn filtered(&self, min_level: LevelFilter, query: &str) -> Vec<LogEntry> {
+        let query = query.to_lowercase();
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .iter()
+            .filter(|entry| entry.level <= min_level)
+            .filter(|entry| {
+                query.is_empty()
+                    || entry.message.to_lowercase().contains(&query)
+                    || entry.target.to_lowercase().contains(&query)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// The process-wide buffer that `init` captures records into
+pub fn buffer() -> &'static LogBuffer {
+    static BUFFER: OnceLock<LogBuffer> = OnceLock::new();
+    BUFFER.get_or_init(|| LogBuffer::new(LOG_CAPACITY))
+}
+
+/// Logger that writes through env_logger and also captures records into `buffer()`
+struct CaptureLogger {
+    inner: env_logger::Logger,
+}
+
+impl CaptureLogger {
+    fn should_capture(metadata: &Metadata) -> bool {
+        let own_crate = metadata.target() == env!("CARGO_CRATE_NAME")
+            || metadata
+                .target()
+                .starts_with(concat!(env!("CARGO_CRATE_NAME"), "::"));
+        if own_crate {
+            metadata.level() <= CAPTURE_LEVEL
+        } else {
+            metadata.level() <= Level::Warn
+        }
+    }
+}
+
+impl Log for CaptureLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata) || Self::should_capture(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.inner.matches(record) {
+            self.inner.log(record);
+        }
+        if Self::should_capture(record.metadata()) {
+            buffer().push(LogEntry {
+                time: chrono::Local::now(),
+                level: record.level(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+            });
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Install the global logger (configured from `RUST_LOG`) with in-window capture
+pub fn init() {
+    let inner = env_logger::Builder::from_default_env()
+        .format(|buf, record| {
+            writeln!(
+                buf,
+                "{}:{} - [{}] {}",
+                record.file().unwrap_or("unknown"),
+                record.line().unwrap_or(0),
+                record.level(),
+                record.args()
+            )
+        })
+        .build();
+    let max_level = inner.filter().max(CAPTURE_LEVEL.to_level_filter());
+
+    if log::set_boxed_logger(Box::new(CaptureLogger { inner })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// State of the log viewer overlay (toggled with Ctrl+Shift+L)
+pub struct LogViewer {
+    pub show: bool,
+    /// Least severe level shown
+    pub min_level: LevelFilter,
+    /// Case-insensitive text filter
+    pub query: String,
+    /// Lines scrolled back from the newest entry
+    pub scroll: usize,
+}
+
+impl Default for LogViewer {
+    fn default() -> Self {
+        Self {
+            show: false,
+            min_level: LevelFilter::Trace,
+            query: String::new(),
+            scroll: 0,
+        }
+    }
+}
+
+impl LogViewer {
+    /// Cycle the level filter: all -> debug -> info -> warn -> error -> all
+    pub fn cycle_level(&mut self) {
+        self.min_level = match self.min_level {
+            LevelFilter::Trace => LevelFilter::Debug,
+            LevelFilter::Debug => LevelFilter::Info,
+            LevelFilter::Info => LevelFilter::Warn,
+            LevelFilter::Warn => LevelFilter::Error,
+            _ => LevelFilter::Trace,
+        };
+        self.scroll = 0;
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_add(lines);
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    /// Overlay text for `entries`, fitting at most `rows` lines including the header
+    pub fn text(&mut self, entries: &[LogEntry], rows: usize) -> String {
+        let body_rows = rows.saturating_sub(1).max(1);
+        let max_scroll = entries.len().saturating_sub(body_rows);
+        self.scroll = self.scroll.min(max_scroll);
+        let end = entries.len() - self.scroll;
+        let start = end.saturating_sub(body_rows);
+
+        let level = match self.min_level {
+            LevelFilter::Trace | LevelFilter::Off => "all".to_string(),
+            level => format!(">= {}", level),
+        };
+        let mut text = format!(
+            "Logs [{}] filter: {}_  ({} of {})  Tab: level  Up/Down: scroll  Esc: close",
+            level,
+            self.query,
+            end,
+            entries.len()
+        );
+        for entry in &entries[start..end] {
+            text.push('\n');
+            text.push_str(&entry.format());
+        }
+        text
+    }
+}
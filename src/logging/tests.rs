@@ -0,0 +1,84 @@
+use log::{Level, LevelFilter, Metadata};
+
+use crate::profiling::SPAN_TARGET;
+
+use super::{CaptureLogger, LogBuffer, LogEntry, LogViewer};
+
+fn entry(level: Level, message: &str) -> LogEntry {
+    LogEntry {
+        time: chrono::Local::now(),
+        level,
+        target: "MTTY::ui".to_string(),
+        message: message.to_string(),
+    }
+}
+
+#[test]
+fn log_buffer_should_drop_oldest_entries_when_full() {
+    let buffer = LogBuffer::new(2);
+    buffer.push(entry(Level::Info, "first"));
+    buffer.push(entry(Level::Info, "second"));
+    buffer.push(entry(Level::Info, "third"));
+
+    let messages: Vec<String> = buffer
+        .filtered(LevelFilter::Trace, "")
+        .into_iter()
+        .map(|entry| entry.message)
+        .collect();
+
+    assert_eq!(messages, ["second", "third"]);
+}
+
+#[test]
+fn log_buffer_should_filter_by_level_and_text() {
+    let buffer = LogBuffer::new(10);
+    buffer.push(entry(Level::Info, "Using graphics adapter"));
+    buffer.push(entry(Level::Warn, "Present mode not supported"));
+    buffer.push(entry(Level::Error, "Render error"));
+
+    assert_eq!(buffer.filtered(LevelFilter::Warn, "").len(), 2);
+    assert_eq!(buffer.filtered(LevelFilter::Trace, "ADAPTER").len(), 1);
+    assert_eq!(buffer.filtered(LevelFilter::Error, "adapter").len(), 0);
+}
+
+#[test]
+fn log_viewer_should_show_newest_entries_that_fit() {
+    let entries: Vec<LogEntry> = (0..10)
+        .map(|i| entry(Level::Info, &format!("line {}", i)))
+        .collect();
+    let mut viewer = LogViewer::default();
+
+    let text = viewer.text(&entries, 4);
+
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[1].ends_with("line 7"));
+    assert!(lines[3].ends_with("line 9"));
+}
+
+#[test]
+fn log_viewer_scroll_should_be_clamped_to_oldest_entry() {
+    let entries: Vec<LogEntry> = (0..10)
+        .map(|i| entry(Level::Info, &format!("line {}", i)))
+        .collect();
+    let mut viewer = LogViewer::default();
+    viewer.scroll_up(100);
+
+    let text = viewer.text(&entries, 4);
+
+    assert_eq!(viewer.scroll, 7);
+    assert!(text.lines().nth(1).unwrap().ends_with("line 0"));
+}
+
+#[test]
+fn profiling_spans_should_not_be_captured() {
+    let metadata = |target| {
+        Metadata::builder()
+            .level(Level::Info)
+            .target(target)
+            .build()
+    };
+
+    assert!(CaptureLogger::should_capture(&metadata("mtty::ui")));
+    assert!(!CaptureLogger::should_capture(&metadata(SPAN_TARGET)));
+}
@@ -1,28 +1,15 @@
-use clap::Parser;
-use commands::ClientCommand;
-use config::Config;
-use std::io::Write;
+use clap::{Parser, Subcommand};
+use mtty::{
+    app,
+    config::{self, Config, GpuBackend, PowerPreference},
+    doctor, fonts,
+    frontend::{Frontend, Session},
+    gpu, logging, paths,
+    profiling::{self, SPAN_TARGET},
+    ui::WgpuRunner,
+};
 use std::path::PathBuf;
 use std::sync::{atomic::AtomicBool, Arc};
-use tokio::sync::broadcast::{Receiver, Sender};
-
-use crate::{
-    commands::ServerCommand,
-    ui::{Runner, WgpuRunner},
-};
-
-pub mod app;
-pub mod commands;
-pub mod config;
-pub mod fonts;
-pub mod grid;
-pub mod recording;
-pub mod renderer;
-pub mod snapshot;
-pub mod statemachine;
-pub mod styles;
-pub mod term;
-pub mod ui;
 
 #[derive(Parser, Debug, Clone)]
 #[command(name = "mtty")]
@@ -35,65 +22,130 @@ pub struct Args {
     /// Start recording immediately when the terminal launches
     #[arg(long)]
     pub record: bool,
+
+    /// Graphics backend to use (overrides the config file)
+    #[arg(long, value_enum)]
+    pub backend: Option<GpuBackend>,
+
+    /// GPU power preference (overrides the config file)
+    #[arg(long, value_enum)]
+    pub power_preference: Option<PowerPreference>,
+
+    /// Use the first adapter whose name contains this string (overrides the config file)
+    #[arg(long, value_name = "NAME")]
+    pub adapter: Option<String>,
+
+    /// Serve grid state for external tooling on this Unix socket (overrides the config file)
+    #[arg(long, value_name = "PATH")]
+    pub debug_socket: Option<PathBuf>,
+
+    /// Write a chrome://tracing compatible performance trace to this file
+    #[arg(long, value_name = "FILE")]
+    pub trace: Option<PathBuf>,
+
+    /// Directory for recordings, snapshots and other saved state (default: XDG data dir)
+    #[arg(long, value_name = "DIR")]
+    pub data_dir: Option<PathBuf>,
+
+    /// Validate the config file (or FILE) and exit, non-zero when it has errors
+    #[arg(long, value_name = "FILE", num_args = 0..=1)]
+    pub check_config: Option<Option<PathBuf>>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Print GPU, display, font and terminfo diagnostics
+    Doctor,
+}
+
+/// Load the config file and apply command line overrides
+fn load_config(args: &Args) -> Config {
+    let mut config = Config::load();
+
+    if let Some(backend) = args.backend {
+        config.gpu_backend = backend;
+    }
+    if let Some(power_preference) = args.power_preference {
+        config.power_preference = power_preference;
+    }
+    if let Some(adapter) = &args.adapter {
+        config.adapter_name = Some(adapter.clone());
+    }
+    if let Some(debug_socket) = &args.debug_socket {
+        config.debug_socket = Some(debug_socket.clone());
+    }
+    if let Some(trace) = &args.trace {
+        config.trace_file = Some(trace.clone());
+    }
+
+    config
 }
 
 #[tokio::main]
 async fn main() -> Result<(), std::io::Error> {
-    env_logger::Builder::from_default_env()
-        .format(|buf, record| {
-            writeln!(
-                buf,
-                "{}:{} - [{}] {}",
-                record.file().unwrap_or("unknown"),
-                record.line().unwrap_or(0),
-                record.level(),
-                record.args()
-            )
-        })
-        .init();
+    logging::init();
 
     let args = Args::parse();
+    if let Some(data_dir) = &args.data_dir {
+        paths::set_data_dir(data_dir.clone());
+    }
+    if let Some(path) = &args.check_config {
+        std::process::exit(check_config(path.clone()));
+    }
+    let config = load_config(&args);
+    if let Some(Command::Doctor) = args.command {
+        let checks = doctor::diagnose(&config);
+        print!("{}", doctor::format_report(&checks));
+        std::process::exit(i32::from(doctor::has_errors(&checks)));
+    }
+    // Flushes the trace file when main returns
+    let _trace_guard = config.trace_file.as_deref().and_then(profiling::init);
+
+    // Scan fonts and open the GPU in the background while the shell and window start
+    fonts::preload_font_system();
+    gpu::preload(&config);
 
     if let Some(replay_path) = args.replay {
         // Replay mode - no PTY, just playback
-        start_replay_ui(&Config::load(), &replay_path);
+        start_replay_ui(&config, &replay_path);
     } else {
         // Normal terminal mode
-        let app = app::App::new(Config::load(), Arc::new(AtomicBool::new(false)));
-
-        start_ui(
-            &app.config,
-            &app.is_running,
-            &app.server_channel.input_transmitter,
-            &app.client_channel.output_receiver,
-            args.record,
-        );
+        let app = {
+            let _span = tracing::info_span!(target: SPAN_TARGET, "spawn_shell").entered();
+            app::App::new(config, Arc::new(AtomicBool::new(false)))
+        };
+
+        WgpuRunner::new(None, args.record).run(Session::new(&app));
     }
 
     Ok(())
 }
 
-fn start_ui(
-    config: &Config,
-    exit_flag: &Arc<AtomicBool>,
-    tx: &Sender<ServerCommand>,
-    ui_update_receiver: &Receiver<ClientCommand>,
-    auto_record: bool,
-) {
-    let runner = WgpuRunner::new(
-        exit_flag.clone(),
-        config.clone(),
-        tx.clone(),
-        ui_update_receiver.resubscribe(),
-        None,
-        auto_record,
-    );
-
-    runner.run();
+/// Print problems in the config file, returning the process exit code
+fn check_config(path: Option<PathBuf>) -> i32 {
+    let Some(path) = path.or_else(Config::config_path) else {
+        eprintln!("Could not determine the config file location");
+        return 1;
+    };
+    match config::check_file(&path) {
+        Ok(()) => {
+            println!("{}: OK", path.display());
+            0
+        }
+        Err(errors) => {
+            for error in &errors {
+                eprintln!("{}:{}", path.display(), error);
+            }
+            1
+        }
+    }
 }
 
 fn start_replay_ui(config: &Config, replay_path: &PathBuf) {
-    use crate::recording::Player;
+    use mtty::recording::Player;
 
     let player = match Player::load_from_file(replay_path) {
         Ok(p) => p,
@@ -104,12 +156,5 @@ fn start_replay_ui(config: &Config, replay_path: &PathBuf) {
         }
     };
 
-    let exit_flag = Arc::new(AtomicBool::new(false));
-    // Create dummy channels for replay mode (won't be used)
-    let (tx, _) = tokio::sync::broadcast::channel::<ServerCommand>(1);
-    let (_, rx) = tokio::sync::broadcast::channel::<ClientCommand>(1);
-
-    let runner = WgpuRunner::new(exit_flag, config.clone(), tx, rx, Some(player), false);
-
-    runner.run();
+    WgpuRunner::new(Some(player), false).run(Session::detached(config.clone()));
 }
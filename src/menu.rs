@@ -0,0 +1,128 @@
+use muda::{
+    accelerator::{Accelerator, Code, Modifiers},
+    Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem, Submenu,
+};
+
+use crate::actions::Action;
+
+/// The native macOS menu bar, mapping its items to actions
+pub struct MenuBar {
+    _menu: Menu,
+    items: Vec<(MenuId, Action)>,
+}
+
+impl MenuBar {
+    /// Build the Shell, Edit, View and Window menus and install them for the application.
+    /// Must be called once the event loop is running.
+    pub fn install() -> Result<Self, muda::Error> {
+        let mut items = Vec::new();
+        let mut item = |label: &str, action: Action, modifiers: Modifiers, key: Code| {
+            let item = MenuItem::new(label, true, Some(Accelerator::new(Some(modifiers), key)));
+            items.push((item.id().clone(), action));
+            item
+        };
+        let cmd = Modifiers::SUPER;
+
+        let app_menu = Submenu::with_items(
+            "MTTY",
+            true,
+            &[
+                &PredefinedMenuItem::about(Some("About MTTY"), None),
+                &PredefinedMenuItem::separator(),
+                &PredefinedMenuItem::services(None),
+                &PredefinedMenuItem::separator(),
+                &PredefinedMenuItem::hide(None),
+                &PredefinedMenuItem::hide_others(None),
+                &PredefinedMenuItem::show_all(None),
+                &PredefinedMenuItem::separator(),
+                &item("Quit MTTY", Action::Quit, cmd, Code::KeyQ),
+            ],
+        )?;
+        let shell_menu = Submenu::with_items(
+            "Shell",
+            true,
+            &[
+                &item("New Window", Action::NewWindow, cmd, Code::KeyN),
+                &item("New Tab", Action::NewTab, cmd, Code::KeyT),
+                &PredefinedMenuItem::separator(),
+                &item("Close Window", Action::CloseWindow, cmd, Code::KeyW),
+            ],
+        )?;
+        let edit_menu = Submenu::with_items(
+            "Edit",
+            true,
+            &[
+                &item("Copy", Action::Copy, cmd, Code::KeyC),
+                &item("Paste", Action::Paste, cmd, Code::KeyV),
+                &item(
+                    "Open Link…",
+                    Action::ShowLinkHints,
+                    Modifiers::CONTROL | Modifiers::SHIFT,
+                    Code::KeyU,
+                ),
+                &PredefinedMenuItem::separator(),
+                &item(
+                    "Clear Scrollback",
+                    Action::ClearScrollback,
+                    Modifiers::CONTROL | Modifiers::SHIFT,
+                    Code::KeyK,
+                ),
+            ],
+        )?;
+        let view_menu = Submenu::with_items(
+            "View",
+            true,
+            &[
+                &item("Bigger", Action::IncreaseFontSize, cmd, Code::Equal),
+                &item("Smaller", Action::DecreaseFontSize, cmd, Code::Minus),
+                &item("Actual Size", Action::ResetFontSize, cmd, Code::Digit0),
+                &PredefinedMenuItem::separator(),
+                &item(
+                    "Debug Overlay",
+                    Action::ToggleDebugOverlay,
+                    Modifiers::CONTROL | Modifiers::SHIFT,
+                    Code::KeyI,
+                ),
+                &item(
+                    "Escape Sequence Inspector",
+                    Action::ToggleInspector,
+                    Modifiers::CONTROL | Modifiers::SHIFT,
+                    Code::KeyE,
+                ),
+                &PredefinedMenuItem::fullscreen(None),
+            ],
+        )?;
+        let window_menu = Submenu::with_items(
+            "Window",
+            true,
+            &[
+                &PredefinedMenuItem::minimize(None),
+                &PredefinedMenuItem::maximize(None),
+                &PredefinedMenuItem::separator(),
+                &PredefinedMenuItem::bring_all_to_front(None),
+            ],
+        )?;
+
+        let menu =
+            Menu::with_items(&[&app_menu, &shell_menu, &edit_menu, &view_menu, &window_menu])?;
+        menu.init_for_nsapp();
+        window_menu.set_as_windows_menu_for_nsapp();
+
+        Ok(Self { _menu: menu, items })
+    }
+
+    /// The next action picked from the menu since the last call
+    pub fn poll(&self) -> Option<Action> {
+        while let Ok(event) = MenuEvent::receiver().try_recv() {
+            let action = self
+                .items
+                .iter()
+                .find(|(id, _)| *id == event.id)
+                .map(|(_, action)| *action);
+            if action.is_some() {
+                return action;
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+
+#[cfg(test)]
+mod tests;
+
+/// Runtime counters and gauges shared between the PTY threads and the UI.
+///
+/// Read by the debug HUD, the debug socket and tests through `snapshot`.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    bytes_read: AtomicU64,
+    commands_processed: AtomicU64,
+    frames_rendered: AtomicU64,
+    dropped_messages: AtomicU64,
+    resize_events: AtomicU64,
+    pty_write_queue: AtomicU64,
+}
+
+/// Point-in-time copy of all counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct MetricsSnapshot {
+    /// Bytes read from the PTY
+    pub bytes_read: u64,
+    /// Commands applied to the grid by the UI
+    pub commands_processed: u64,
+    /// Frames successfully rendered
+    pub frames_rendered: u64,
+    /// Commands dropped because the UI receiver lagged behind the broadcast channel
+    pub dropped_messages: u64,
+    /// Window resize events handled
+    pub resize_events: u64,
+    /// Input bytes queued because the PTY is not accepting them yet
+    pub pty_write_queue: u64,
+}
+
+impl Metrics {
+    pub fn add_bytes_read(&self, bytes: u64) {
+        self.bytes_read.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn add_commands_processed(&self, count: u64) {
+        self.commands_processed.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn add_frame_rendered(&self) {
+        self.frames_rendered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn add_dropped_messages(&self, count: u64) {
+        self.dropped_messages.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn add_resize_event(&self) {
+        self.resize_events.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn set_pty_write_queue(&self, bytes: u64) {
+        self.pty_write_queue.store(bytes, Ordering::Relaxed);
+    }
+
+    /// Input bytes still waiting for the PTY. Large inputs such as pastes should wait for
+    /// this to drain instead of flooding the input channel.
+    pub fn pty_write_queue(&self) -> u64 {
+        self.pty_write_queue.load(Ordering::Relaxed)
+    }
+
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            bytes_read: self.bytes_read.load(Ordering::Relaxed),
+            commands_processed: self.commands_processed.load(Ordering::Relaxed),
+            frames_rendered: self.frames_rendered.load(Ordering::Relaxed),
+            dropped_messages: self.dropped_messages.load(Ordering::Relaxed),
+            resize_events: self.resize_events.load(Ordering::Relaxed),
+            pty_write_queue: self.pty_write_queue.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::{sync::Arc, thread};
+
+use super::{Metrics, MetricsSnapshot};
+
+#[test]
+fn snapshot_should_reflect_all_counters() {
+    let metrics = Metrics::default();
+    metrics.add_bytes_read(10);
+    metrics.add_bytes_read(5);
+    metrics.add_commands_processed(3);
+    metrics.add_frame_rendered();
+    metrics.add_dropped_messages(7);
+    metrics.add_resize_event();
+    metrics.add_resize_event();
+    metrics.set_pty_write_queue(64);
+    metrics.set_pty_write_queue(42);
+
+    assert_eq!(
+        metrics.snapshot(),
+        MetricsSnapshot {
+            bytes_read: 15,
+            commands_processed: 3,
+            frames_rendered: 1,
+            dropped_messages: 7,
+            resize_events: 2,
+            pty_write_queue: 42,
+        }
+    );
+}
+
+#[test]
+fn counters_should_be_shared_across_threads() {
+    let metrics = Arc::new(Metrics::default());
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let metrics = metrics.clone();
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    metrics.add_bytes_read(1);
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(metrics.bytes_read(), 400);
+}
@@ -0,0 +1,133 @@
+use crate::grid::Grid;
+
+#[cfg(test)]
+mod tests;
+
+/// Width of the minimap strip at the right edge of the window, in pixels
+pub const WIDTH: f32 = 60.0;
+/// Height of one minimap line, in pixels
+const LINE_HEIGHT: f32 = 2.0;
+/// Space between the strip's edges and the line blocks, in pixels
+const PADDING: f32 = 4.0;
+const BACKGROUND: (u8, u8, u8) = (30, 30, 30);
+const BACKGROUND_OPACITY: f32 = 0.85;
+const LINE_OPACITY: f32 = 0.7;
+const VIEWPORT_OPACITY: f32 = 0.25;
+
+/// A filled rectangle in window pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Block {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub color: (u8, u8, u8),
+    pub alpha: f32,
+}
+
+/// Where the minimap strip sits and how buffer rows map onto its lines.
+///
+/// Short buffers get one line per row; longer ones are squeezed so the whole buffer fits,
+/// each line standing for the first of the rows it covers.
+#[derive(Debug, Clone, Copy)]
+pub struct Layout {
+    left: f32,
+    top: f32,
+    height: f32,
+    total_rows: usize,
+    visible_rows: usize,
+}
+
+impl Layout {
+    /// The strip for a `window_width` wide window, from `top` down `height` pixels
+    pub fn new(
+        window_width: f32,
+        top: f32,
+        height: f32,
+        total_rows: usize,
+        visible_rows: usize,
+    ) -> Self {
+        Self {
+            left: (window_width - WIDTH).max(0.0),
+            top,
+            height,
+            total_rows: total_rows.max(1),
+            visible_rows: visible_rows.max(1),
+        }
+    }
+
+    fn lines(&self) -> usize {
+        ((self.height / LINE_HEIGHT) as usize).max(1)
+    }
+
+    fn rows_per_line(&self) -> f32 {
+        (self.total_rows as f32 / self.lines() as f32).max(1.0)
+    }
+
+    /// Top of the line showing buffer row `row`
+    fn row_y(&self, row: usize) -> f32 {
+        self.top + (row as f32 / self.rows_per_line()).floor() * LINE_HEIGHT
+    }
+
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.left && (self.top..self.top + self.height).contains(&y)
+    }
+
+    /// The last visible row that centers the view on the row under `y`
+    pub fn bottom_row_at(&self, y: f32) -> usize {
+        let line = ((y - self.top).max(0.0) / LINE_HEIGHT) as usize;
+        let row = (line as f32 * self.rows_per_line()) as usize;
+        (row + self.visible_rows / 2).clamp(self.visible_rows - 1, self.total_rows - 1)
+    }
+
+    /// The strip, a block per line sized to its text and colored like it, and the window
+    /// showing which rows are on screen
+    pub fn blocks(&self, grid: &Grid) -> Vec<Block> {
+        let width = grid.width as usize;
+        let mut blocks = vec![Block {
+            x: self.left,
+            y: self.top,
+            width: WIDTH,
+            height: self.height,
+            color: BACKGROUND,
+            alpha: BACKGROUND_OPACITY,
+        }];
+
+        let all_cells = grid.active_grid_ref();
+        let line_width = WIDTH - PADDING * 2.0;
+        for line in 0..self.lines() {
+            let row = (line as f32 * self.rows_per_line()) as usize;
+            // Only the sampled rows are read, not the whole scrollback
+            let start = row * width;
+            if start >= all_cells.len() {
+                break;
+            }
+            let cells = &all_cells[start..(start + width).min(all_cells.len())];
+            let Some(last) = cells.iter().rposition(|cell| cell.char != ' ') else {
+                continue;
+            };
+            let first = cells.iter().position(|cell| cell.char != ' ').unwrap_or(0);
+            blocks.push(Block {
+                x: self.left + PADDING + line_width * first as f32 / width as f32,
+                y: self.top + line as f32 * LINE_HEIGHT,
+                width: line_width * (last + 1 - first) as f32 / width as f32,
+                height: LINE_HEIGHT,
+                color: grid.styles.to_rgb(cells[first].fg),
+                alpha: LINE_OPACITY,
+            });
+        }
+
+        let last_visible = grid.bottom_row().min(self.total_rows - 1);
+        let first_visible = (last_visible + 1).saturating_sub(self.visible_rows);
+        let y = self.row_y(first_visible);
+        blocks.push(Block {
+            x: self.left,
+            y,
+            width: WIDTH,
+            height: (self.row_y(last_visible + 1) - y).max(LINE_HEIGHT),
+            color: grid.styles.to_rgb(grid.styles.default_text_color),
+            alpha: VIEWPORT_OPACITY,
+        });
+        blocks
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{config::Config, terminal::HeadlessPipeline};
+
+use super::{Layout, LINE_HEIGHT, WIDTH};
+
+fn pipeline(output: &str) -> HeadlessPipeline {
+    let mut pipeline = HeadlessPipeline::new(&Config {
+        cols: 10,
+        rows: 4,
+        ..Config::default()
+    });
+    pipeline.feed(output.as_bytes());
+    pipeline
+}
+
+#[test]
+fn blocks_should_follow_text_and_mark_the_visible_rows() {
+    let pipeline = pipeline("one\r\n\r\n  three\r\n4\r\n5\r\n6");
+    let grid = &pipeline.terminal.grid;
+    let layout = Layout::new(200.0, 0.0, 100.0, 6, 4);
+
+    let blocks = layout.blocks(grid);
+
+    // Strip, five non-empty rows, viewport
+    assert_eq!(blocks.len(), 7);
+    let three = blocks[2];
+    assert_eq!(three.y, 2.0 * LINE_HEIGHT);
+    assert!(three.x > blocks[1].x);
+    let viewport = blocks[6];
+    assert_eq!(viewport.x, 200.0 - WIDTH);
+    assert_eq!(viewport.y, 2.0 * LINE_HEIGHT);
+    assert_eq!(viewport.height, 4.0 * LINE_HEIGHT);
+}
+
+#[test]
+fn long_buffers_should_be_squeezed_to_fit() {
+    let layout = Layout::new(200.0, 0.0, 100.0, 1000, 4);
+
+    assert_eq!(layout.bottom_row_at(50.0), 502);
+    assert_eq!(layout.bottom_row_at(0.0), 3);
+    assert_eq!(layout.bottom_row_at(500.0), 999);
+}
+
+#[test]
+fn contains_should_cover_only_the_strip() {
+    let layout = Layout::new(200.0, 20.0, 100.0, 10, 4);
+
+    assert!(layout.contains(200.0 - WIDTH, 20.0));
+    assert!(!layout.contains(100.0, 50.0));
+    assert!(!layout.contains(190.0, 130.0));
+}
@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod tests;
+
+/// Something a monitor noticed about the terminal's output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alert {
+    /// Output arrived while the window was in the background
+    Activity,
+    /// No output for the configured time
+    Silence(Duration),
+}
+
+impl Alert {
+    pub fn message(&self) -> String {
+        match self {
+            Alert::Activity => "Activity in terminal".to_string(),
+            Alert::Silence(after) => format!("Terminal silent for {}s", after.as_secs()),
+        }
+    }
+}
+
+/// Activity and silence monitoring, like tmux's monitor-activity and monitor-silence.
+///
+/// Each alert fires once: activity again after the window has been focused, silence
+/// again after more output.
+#[derive(Debug)]
+pub struct Monitor {
+    activity: bool,
+    silence: Option<Duration>,
+    last_output: Instant,
+    activity_alerted: bool,
+    silence_alerted: bool,
+}
+
+impl Monitor {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            activity: false,
+            silence: None,
+            last_output: now,
+            activity_alerted: false,
+            silence_alerted: false,
+        }
+    }
+
+    pub fn activity(&self) -> bool {
+        self.activity
+    }
+
+    pub fn silence(&self) -> Option<Duration> {
+        self.silence
+    }
+
+    pub fn set_activity(&mut self, enabled: bool) {
+        self.activity = enabled;
+        self.activity_alerted = false;
+    }
+
+    /// Start watching for `after` without output (counted from now), or stop with `None`
+    pub fn set_silence(&mut self, after: Option<Duration>, now: Instant) {
+        self.silence = after;
+        self.last_output = now;
+        self.silence_alerted = false;
+    }
+
+    /// Record output from the shell
+    pub fn output(&mut self, focused: bool, now: Instant) -> Option<Alert> {
+        self.last_output = now;
+        self.silence_alerted = false;
+        if !self.activity || focused || self.activity_alerted {
+            return None;
+        }
+        self.activity_alerted = true;
+        Some(Alert::Activity)
+    }
+
+    /// The window came to the foreground, so the activity has been seen
+    pub fn focus(&mut self) {
+        self.activity_alerted = false;
+    }
+
+    /// Check for silence
+    pub fn update(&mut self, now: Instant) -> Option<Alert> {
+        let after = self.silence?;
+        if self.silence_alerted || now.duration_since(self.last_output) < after {
+            return None;
+        }
+        self.silence_alerted = true;
+        Some(Alert::Silence(after))
+    }
+}
@@ -0,0 +1,55 @@
+use std::time::{Duration, Instant};
+
+use super::{Alert, Monitor};
+
+#[test]
+fn activity_should_alert_once_while_unfocused() {
+    let now = Instant::now();
+    let mut monitor = Monitor::new(now);
+    monitor.set_activity(true);
+
+    assert_eq!(monitor.output(true, now), None);
+    assert_eq!(monitor.output(false, now), Some(Alert::Activity));
+    assert_eq!(monitor.output(false, now), None);
+
+    monitor.focus();
+    assert_eq!(monitor.output(false, now), Some(Alert::Activity));
+}
+
+#[test]
+fn activity_should_be_ignored_when_disabled() {
+    let now = Instant::now();
+    let mut monitor = Monitor::new(now);
+
+    assert_eq!(monitor.output(false, now), None);
+}
+
+#[test]
+fn silence_should_alert_once_until_more_output() {
+    let start = Instant::now();
+    let after = Duration::from_secs(10);
+    let mut monitor = Monitor::new(start);
+    monitor.set_silence(Some(after), start);
+
+    assert_eq!(monitor.update(start + Duration::from_secs(9)), None);
+    assert_eq!(
+        monitor.update(start + Duration::from_secs(10)),
+        Some(Alert::Silence(after))
+    );
+    assert_eq!(monitor.update(start + Duration::from_secs(30)), None);
+
+    monitor.output(true, start + Duration::from_secs(30));
+    assert_eq!(monitor.update(start + Duration::from_secs(35)), None);
+    assert_eq!(
+        monitor.update(start + Duration::from_secs(40)),
+        Some(Alert::Silence(after))
+    );
+}
+
+#[test]
+fn silence_should_be_ignored_when_disabled() {
+    let start = Instant::now();
+    let mut monitor = Monitor::new(start);
+
+    assert_eq!(monitor.update(start + Duration::from_secs(3600)), None);
+}
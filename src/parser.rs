@@ -0,0 +1,383 @@
+use std::borrow::Cow;
+
+use vte::{ansi::Processor, Params, Perform};
+
+use crate::{
+    commands::{ClientCommand, Progress, Rectangle, ShellMark},
+    inspector,
+    statemachine::StateMachine,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Parses PTY output with vte's ANSI processor plus the sequences it has no `Handler` method for.
+///
+/// The processor silently drops unknown sequences, so a second low-level parser runs over the
+/// same bytes and stops right after each sequence we handle ourselves. The processor is fed up
+/// to that point first, keeping the extra commands in order with everything around them.
+///
+/// tmux passthrough wrappers are removed before either parser sees the bytes, and overlong
+/// control strings are cut off.
+///
+/// While the state machine's inspector is recording, both parsers are fed a byte at a time so
+/// each sequence is recorded just before the commands it produces.
+#[derive(Default)]
+pub struct Parser {
+    processor: Processor,
+    extensions: vte::Parser,
+    passthrough: TmuxPassthrough,
+    strings: StringLimit,
+}
+
+impl Parser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse the next read from the PTY.
+    ///
+    /// Reads may end anywhere, including inside a UTF-8 code point: both vte parsers hold the
+    /// leading bytes back until the rest arrives, and the pre-passes only look at ASCII bytes,
+    /// so a split character is decoded as if it had been read in one piece.
+    pub fn advance(&mut self, statemachine: &mut StateMachine, bytes: &[u8]) {
+        let bytes = self.passthrough.unwrap(bytes);
+        let bytes = self.strings.limit(&bytes);
+        let bytes = bytes.as_ref();
+        let inspector = statemachine.inspector().cloned();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let mut performer = ExtensionPerformer {
+                inspect: inspector.is_some(),
+                ..Default::default()
+            };
+            let end = if inspector.is_some() {
+                offset + 1
+            } else {
+                bytes.len()
+            };
+            let consumed = self
+                .extensions
+                .advance_until_terminated(&mut performer, &bytes[offset..end]);
+            if let (Some(inspector), Some(description)) = (&inspector, performer.description.take())
+            {
+                inspector.record_sequence(description);
+            }
+            self.processor
+                .advance(statemachine, &bytes[offset..offset + consumed]);
+            match performer.command {
+                // Without DECLRMM this is the save-cursor sequence the processor already handled
+                Some(ClientCommand::SetLeftRightMargins(..))
+                    if !statemachine.left_right_margin_mode() => {}
+                Some(command) => statemachine.send(command),
+                None => {}
+            }
+            offset += consumed;
+        }
+    }
+}
+
+/// Start of tmux's DCS passthrough wrapper, `ESC P tmux ; <sequence> ESC \`
+const TMUX_PREFIX: &[u8] = b"\x1bPtmux;";
+const ESC: u8 = 0x1b;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum PassthroughState {
+    #[default]
+    Ground,
+    /// Matched this many bytes of `TMUX_PREFIX`
+    Prefix(usize),
+    /// Inside the wrapper, where tmux doubles every ESC of the inner sequence
+    Inner,
+    InnerEscape,
+}
+
+/// Streams bytes through unchanged, except that tmux passthrough wrappers are replaced by the
+/// sequence they carry, so clipboard and graphics escapes sent from inside tmux take effect.
+/// A wrapper may be split across reads.
+#[derive(Debug, Default)]
+struct TmuxPassthrough {
+    state: PassthroughState,
+}
+
+impl TmuxPassthrough {
+    fn unwrap<'a>(&mut self, bytes: &'a [u8]) -> Cow<'a, [u8]> {
+        // Almost all output has no wrapper, so avoid copying it
+        if self.state == PassthroughState::Ground
+            && bytes.last() != Some(&ESC)
+            && !bytes.windows(2).any(|pair| pair == b"\x1bP")
+        {
+            return Cow::Borrowed(bytes);
+        }
+
+        let mut output = Vec::with_capacity(bytes.len());
+        for &byte in bytes {
+            self.push(byte, &mut output);
+        }
+        Cow::Owned(output)
+    }
+
+    fn push(&mut self, byte: u8, output: &mut Vec<u8>) {
+        self.state = match self.state {
+            PassthroughState::Ground if byte == ESC => PassthroughState::Prefix(1),
+            PassthroughState::Ground => {
+                output.push(byte);
+                PassthroughState::Ground
+            }
+            PassthroughState::Prefix(matched) if byte == TMUX_PREFIX[matched] => {
+                if matched + 1 == TMUX_PREFIX.len() {
+                    PassthroughState::Inner
+                } else {
+                    PassthroughState::Prefix(matched + 1)
+                }
+            }
+            PassthroughState::Prefix(matched) => {
+                // Not a wrapper after all: release what was held back, then look at this byte again
+                output.extend_from_slice(&TMUX_PREFIX[..matched]);
+                self.state = PassthroughState::Ground;
+                return self.push(byte, output);
+            }
+            PassthroughState::Inner if byte == ESC => PassthroughState::InnerEscape,
+            PassthroughState::Inner => {
+                output.push(byte);
+                PassthroughState::Inner
+            }
+            // String terminator: the wrapper is done
+            PassthroughState::InnerEscape if byte == b'\\' => PassthroughState::Ground,
+            PassthroughState::InnerEscape => {
+                // A doubled ESC stands for one; a lone one is passed on as sent
+                output.push(ESC);
+                if byte != ESC {
+                    output.push(byte);
+                }
+                PassthroughState::Inner
+            }
+        };
+    }
+}
+
+/// Longest OSC, APC, PM or SOS string let through; big enough for chunked kitty graphics
+/// and clipboard payloads
+const MAX_STRING_LEN: usize = 1 << 20;
+/// Cancels the string being parsed without dispatching anything else
+const CAN: u8 = 0x18;
+const SUB: u8 = 0x1a;
+const BEL: u8 = 0x07;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum StringState {
+    #[default]
+    Ground,
+    Escape,
+    /// Inside a control string, with its length so far
+    Osc(usize),
+    /// APC, PM or SOS, which unlike OSC are not ended by BEL
+    Other(usize),
+}
+
+/// Aborts OSC, APC, PM and SOS strings that run past `MAX_STRING_LEN` by injecting CAN.
+///
+/// vte buffers OSC strings without bound and skips the other three until a terminator, so an
+/// unterminated one (e.g. from catting a binary file) would grow memory or swallow all further
+/// output. Past the limit the parser returns to the ground state and the rest is shown as text.
+#[derive(Debug, Default)]
+struct StringLimit {
+    state: StringState,
+}
+
+impl StringLimit {
+    fn limit<'a>(&mut self, bytes: &'a [u8]) -> Cow<'a, [u8]> {
+        let mut index = 0;
+        while index < bytes.len() {
+            if self.state == StringState::Ground {
+                // Only an escape can start a string, so skip the text up to the next one
+                match bytes[index..].iter().position(|&byte| byte == ESC) {
+                    Some(offset) => index += offset,
+                    None => break,
+                }
+            }
+            if self.advance(bytes[index]) {
+                // Copying is only needed once something has to be injected
+                let mut output = bytes[..=index].to_vec();
+                output.push(CAN);
+                output.extend_from_slice(&self.limit(&bytes[index + 1..]));
+                return Cow::Owned(output);
+            }
+            index += 1;
+        }
+        Cow::Borrowed(bytes)
+    }
+
+    /// Track `byte`, returning true when the current string just went over the limit
+    fn advance(&mut self, byte: u8) -> bool {
+        let (next, aborted) = match (self.state, byte) {
+            (_, ESC) => (StringState::Escape, false),
+            (StringState::Escape, b']') => (StringState::Osc(0), false),
+            (StringState::Escape, b'_' | b'^' | b'X') => (StringState::Other(0), false),
+            (StringState::Ground | StringState::Escape, _) => (StringState::Ground, false),
+            (StringState::Osc(_), BEL) | (_, CAN | SUB) => (StringState::Ground, false),
+            (StringState::Osc(len), _) if len < MAX_STRING_LEN => {
+                (StringState::Osc(len + 1), false)
+            }
+            (StringState::Other(len), _) if len < MAX_STRING_LEN => {
+                (StringState::Other(len + 1), false)
+            }
+            (StringState::Osc(_) | StringState::Other(_), _) => (StringState::Ground, true),
+        };
+        self.state = next;
+        aborted
+    }
+}
+
+/// Picks out the sequences vte's processor ignores, stopping after the first one
+#[derive(Default)]
+struct ExtensionPerformer {
+    command: Option<ClientCommand>,
+    /// Describe the sequence for the inspector
+    inspect: bool,
+    description: Option<String>,
+}
+
+impl Perform for ExtensionPerformer {
+    fn execute(&mut self, byte: u8) {
+        if self.inspect {
+            self.description = Some(inspector::describe_control(byte));
+        }
+    }
+
+    fn hook(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
+        if self.inspect {
+            self.description = Some(inspector::describe_dcs(
+                params,
+                intermediates,
+                ignore,
+                action,
+            ));
+        }
+    }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], _ignore: bool, byte: u8) {
+        if self.inspect {
+            self.description = Some(inspector::describe_esc(intermediates, byte));
+        }
+    }
+
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
+        if self.inspect {
+            self.description = Some(inspector::describe_csi(
+                params,
+                intermediates,
+                ignore,
+                action,
+            ));
+        }
+        if ignore {
+            return;
+        }
+        let params: Vec<u16> = params.iter().map(|param| param[0]).collect();
+        self.command = match (action, intermediates) {
+            // DECCRA - copy rectangular area
+            ('v', [b'$']) => Some(ClientCommand::CopyRectangle {
+                source: rectangle(&params, 0),
+                top: param(&params, 5, 1),
+                left: param(&params, 6, 1),
+            }),
+            // DECFRA - fill rectangular area with a character
+            ('x', [b'$']) => fill_character(param(&params, 0, 0))
+                .map(|c| ClientCommand::FillRectangle(c, rectangle(&params, 1))),
+            // DECERA - erase rectangular area
+            ('z', [b'$']) => Some(ClientCommand::EraseRectangle(rectangle(&params, 0))),
+            // DECRQCRA - request rectangle checksum; the page parameter is ignored
+            ('y', [b'*']) => Some(ClientCommand::ReportRectangleChecksum {
+                id: params.first().copied().unwrap_or(0),
+                rect: rectangle(&params, 2),
+            }),
+            // DECSLRM - set left and right margins, only meaningful while DECLRMM is set
+            ('s', []) => Some(ClientCommand::SetLeftRightMargins(
+                param(&params, 0, 1).into(),
+                params
+                    .get(1)
+                    .filter(|&&right| right != 0)
+                    .map(|&right| right.into()),
+            )),
+            _ => None,
+        };
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        if self.inspect {
+            self.description = Some(inspector::describe_osc(params));
+        }
+        self.command = match params {
+            // ConEmu progress: OSC 9 ; 4 ; state ; percent
+            [b"9", b"4", rest @ ..] => progress(rest).map(ClientCommand::SetProgress),
+            // Shell integration: OSC 133 ; A|B|C|D [; exit status]
+            [b"133", mark, rest @ ..] => shell_mark(mark, rest).map(ClientCommand::ShellMark),
+            _ => None,
+        };
+    }
+
+    fn terminated(&self) -> bool {
+        self.command.is_some()
+    }
+}
+
+/// Parameter `index`, with missing or zero values replaced by `default`
+fn param(params: &[u16], index: usize, default: u16) -> u16 {
+    match params.get(index) {
+        Some(&value) if value != 0 => value,
+        _ => default,
+    }
+}
+
+/// Rectangle from the four parameters starting at `start`, defaulting to the whole screen
+fn rectangle(params: &[u16], start: usize) -> Rectangle {
+    Rectangle {
+        top: param(params, start, 1),
+        left: param(params, start + 1, 1),
+        bottom: param(params, start + 2, u16::MAX),
+        right: param(params, start + 3, u16::MAX),
+    }
+}
+
+/// Progress from the state and percentage parameters of OSC 9;4
+fn progress(params: &[&[u8]]) -> Option<Progress> {
+    let number = |index: usize| -> Option<u8> {
+        match params.get(index) {
+            None | Some(&b"") => Some(0),
+            Some(value) => std::str::from_utf8(value).ok()?.parse().ok(),
+        }
+    };
+    let percent = number(1)?.min(100);
+    match number(0)? {
+        0 => Some(Progress::None),
+        1 => Some(Progress::Normal(percent)),
+        2 => Some(Progress::Error(percent)),
+        3 => Some(Progress::Indeterminate),
+        4 => Some(Progress::Paused(percent)),
+        _ => None,
+    }
+}
+
+fn shell_mark(mark: &[u8], params: &[&[u8]]) -> Option<ShellMark> {
+    match mark {
+        b"A" => Some(ShellMark::PromptStart),
+        b"B" => Some(ShellMark::CommandStart),
+        b"C" => Some(ShellMark::CommandExecuted),
+        b"D" => {
+            let status = params
+                .first()
+                .and_then(|value| std::str::from_utf8(value).ok()?.parse().ok());
+            Some(ShellMark::CommandFinished(status))
+        }
+        _ => None,
+    }
+}
+
+/// DECFRA only accepts graphic characters from the GL and GR sets
+fn fill_character(code: u16) -> Option<char> {
+    match code {
+        32..=126 | 160..=255 => char::from_u32(code.into()),
+        _ => None,
+    }
+}
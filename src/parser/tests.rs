@@ -0,0 +1,282 @@
+use std::sync::Arc;
+
+use tokio::sync::broadcast;
+
+use crate::{
+    commands::{ClientCommand, Progress, Rectangle, ShellMark},
+    inspector::{Inspector, InspectorEntry},
+    statemachine::StateMachine,
+};
+
+use super::Parser;
+
+/// Parse `bytes` in one go and collect the commands sent
+fn parse(bytes: &[u8]) -> Vec<ClientCommand> {
+    let (tx, mut rx) = broadcast::channel(1024);
+    let mut statemachine = StateMachine::new(tx);
+    Parser::new().advance(&mut statemachine, bytes);
+
+    let mut commands = Vec::new();
+    while let Ok(command) = rx.try_recv() {
+        commands.push(command);
+    }
+    commands
+}
+
+#[test]
+fn extension_commands_should_keep_stream_order() {
+    let commands = parse(b"a\x1b[2;3;4;5$zb");
+
+    assert!(matches!(
+        commands.as_slice(),
+        [
+            ClientCommand::Print('a'),
+            ClientCommand::EraseRectangle(Rectangle {
+                top: 2,
+                left: 3,
+                bottom: 4,
+                right: 5
+            }),
+            ClientCommand::Print('b'),
+        ]
+    ));
+}
+
+#[test]
+fn rectangle_should_default_to_whole_screen() {
+    let commands = parse(b"\x1b[$z\x1b[;;;;;3;4$v");
+
+    let whole_screen = Rectangle {
+        top: 1,
+        left: 1,
+        bottom: u16::MAX,
+        right: u16::MAX,
+    };
+    assert!(matches!(
+        commands.as_slice(),
+        [
+            ClientCommand::EraseRectangle(erase),
+            ClientCommand::CopyRectangle { source, top: 3, left: 4 },
+        ] if *erase == whole_screen && *source == whole_screen
+    ));
+}
+
+#[test]
+fn fill_should_ignore_control_characters() {
+    let commands = parse(b"\x1b[10;1;1;2;2$x\x1b[65;1;1;2;2$x");
+
+    assert!(matches!(
+        commands.as_slice(),
+        [ClientCommand::FillRectangle('A', _)]
+    ));
+}
+
+#[test]
+fn checksum_request_should_skip_page_parameter() {
+    let commands = parse(b"\x1b[3;1;2;2;4;5*y");
+
+    assert!(matches!(
+        commands.as_slice(),
+        [ClientCommand::ReportRectangleChecksum {
+            id: 3,
+            rect: Rectangle {
+                top: 2,
+                left: 2,
+                bottom: 4,
+                right: 5
+            }
+        }]
+    ));
+}
+
+#[test]
+fn csi_s_should_set_margins_only_in_left_right_margin_mode() {
+    let commands = parse(b"\x1b[2;9s\x1b[?69h\x1b[2;9s");
+
+    assert!(matches!(
+        commands.as_slice(),
+        [
+            ClientCommand::SaveCursor,
+            ClientCommand::LeftRightMarginMode(true),
+            ClientCommand::SetLeftRightMargins(2, Some(9)),
+        ]
+    ));
+}
+
+#[test]
+fn progress_osc_should_report_state_and_clamp_percent() {
+    let commands = parse(b"\x1b]9;4;1;42\x07\x1b]9;4;2;250\x1b\\\x1b]9;4;3\x07\x1b]9;4;0;0\x07");
+
+    assert!(matches!(
+        commands.as_slice(),
+        [
+            ClientCommand::SetProgress(Progress::Normal(42)),
+            ClientCommand::SetProgress(Progress::Error(100)),
+            ClientCommand::SetProgress(Progress::Indeterminate),
+            ClientCommand::SetProgress(Progress::None),
+        ]
+    ));
+}
+
+#[test]
+fn progress_osc_should_ignore_unknown_states_and_notifications() {
+    let commands = parse(b"\x1b]9;4;7;10\x07\x1b]9;hello\x07a");
+
+    assert!(matches!(commands.as_slice(), [ClientCommand::Print('a')]));
+}
+
+#[test]
+fn shell_integration_osc_should_report_marks_and_exit_status() {
+    let commands =
+        parse(b"\x1b]133;A\x07\x1b]133;B\x07\x1b]133;C\x07\x1b]133;D;2\x07\x1b]133;D\x1b\\");
+
+    assert!(matches!(
+        commands.as_slice(),
+        [
+            ClientCommand::ShellMark(ShellMark::PromptStart),
+            ClientCommand::ShellMark(ShellMark::CommandStart),
+            ClientCommand::ShellMark(ShellMark::CommandExecuted),
+            ClientCommand::ShellMark(ShellMark::CommandFinished(Some(2))),
+            ClientCommand::ShellMark(ShellMark::CommandFinished(None)),
+        ]
+    ));
+}
+
+#[test]
+fn tmux_passthrough_should_unwrap_inner_sequence() {
+    let commands = parse(b"\x1bPtmux;\x1b\x1b]2;inner\x1b\x1b\\\x1b\\a");
+
+    assert!(matches!(
+        commands.as_slice(),
+        [ClientCommand::SetTitle(Some(title)), ClientCommand::Print('a')] if title == "inner"
+    ));
+}
+
+#[test]
+fn tmux_passthrough_should_survive_split_reads() {
+    let (tx, mut rx) = broadcast::channel(1024);
+    let mut statemachine = StateMachine::new(tx);
+    let mut parser = Parser::new();
+    for chunk in [&b"x\x1bPtm"[..], b"ux;\x1b", b"\x1b]2;t\x07\x1b", b"\\y"] {
+        parser.advance(&mut statemachine, chunk);
+    }
+
+    let mut commands = Vec::new();
+    while let Ok(command) = rx.try_recv() {
+        commands.push(command);
+    }
+    assert!(matches!(
+        commands.as_slice(),
+        [
+            ClientCommand::Print('x'),
+            ClientCommand::SetTitle(Some(title)),
+            ClientCommand::Print('y'),
+        ] if title == "t"
+    ));
+}
+
+#[test]
+fn other_dcs_sequences_should_pass_through_untouched() {
+    let commands = parse(b"\x1bPtmx\x1b\\\x1b[2;3;4;5$zb");
+
+    assert!(matches!(
+        commands.as_slice(),
+        [ClientCommand::EraseRectangle(_), ClientCommand::Print('b')]
+    ));
+}
+
+#[test]
+fn apc_pm_and_sos_strings_should_be_consumed() {
+    let commands = parse(b"a\x1b_Gf=100;AAAA\x1b\\\x1b^bell\x07ignored\x1b\\\x1bXsos\x1b\\b");
+
+    assert!(matches!(
+        commands.as_slice(),
+        [ClientCommand::Print('a'), ClientCommand::Print('b')]
+    ));
+}
+
+#[test]
+fn overlong_strings_should_be_cut_off() {
+    for start in [&b"\x1b_"[..], b"\x1b]0;"] {
+        let mut bytes = start.to_vec();
+        bytes.resize(start.len() + super::MAX_STRING_LEN + 2, b'x');
+        bytes.push(b'z');
+
+        let commands = parse(&bytes);
+        let printed: String = commands
+            .iter()
+            .filter_map(|command| match command {
+                ClientCommand::Print(c) => Some(*c),
+                _ => None,
+            })
+            .collect();
+        assert!(printed.ends_with('z'));
+        assert!(printed.len() <= 4);
+    }
+}
+
+/// Feed `chunks` as separate reads and collect the printed characters
+fn print_chunks(chunks: &[&[u8]]) -> String {
+    let (tx, mut rx) = broadcast::channel(1024);
+    let mut statemachine = StateMachine::new(tx);
+    let mut parser = Parser::new();
+    for chunk in chunks {
+        parser.advance(&mut statemachine, chunk);
+    }
+
+    let mut printed = String::new();
+    while let Ok(command) = rx.try_recv() {
+        if let ClientCommand::Print(c) = command {
+            printed.push(c);
+        }
+    }
+    printed
+}
+
+#[test]
+fn utf8_split_across_reads_should_decode() {
+    let text = "é€😀";
+    let bytes = text.as_bytes();
+    // Every way of cutting the text into two reads
+    for cut in 0..=bytes.len() {
+        assert_eq!(
+            print_chunks(&[&bytes[..cut], &bytes[cut..]]),
+            text,
+            "cut at {}",
+            cut
+        );
+    }
+    // One byte per read
+    let single: Vec<&[u8]> = bytes.chunks(1).collect();
+    assert_eq!(print_chunks(&single), text);
+}
+
+#[test]
+fn utf8_split_after_extension_sequence_should_decode() {
+    let bytes = "\x1b[1;1;1;1$zé".as_bytes();
+    let cut = bytes.len() - 1;
+    assert_eq!(print_chunks(&[&bytes[..cut], &bytes[cut..]]), "é");
+}
+
+#[test]
+fn inspector_should_record_each_sequence_before_its_commands() {
+    let (tx, _rx) = broadcast::channel(1024);
+    let inspector = Arc::new(Inspector::new());
+    inspector.set_enabled(true);
+    let mut statemachine = StateMachine::new(tx).with_inspector(inspector.clone());
+    Parser::new().advance(&mut statemachine, b"ab\x1b[?25l\r\x1b]0;hi\x07c");
+
+    assert_eq!(
+        inspector.filtered(""),
+        [
+            InspectorEntry::Text("ab".to_string()),
+            InspectorEntry::Sequence("CSI ?25l".to_string()),
+            InspectorEntry::Command("HideCursor".to_string()),
+            InspectorEntry::Sequence("C0 CR".to_string()),
+            InspectorEntry::Command("CarriageReturn".to_string()),
+            InspectorEntry::Sequence("OSC 0;hi".to_string()),
+            InspectorEntry::Command("SetTitle(Some(\"hi\"))".to_string()),
+            InspectorEntry::Text("c".to_string()),
+        ]
+    );
+}
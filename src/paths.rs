@@ -0,0 +1,72 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+#[cfg(test)]
+mod tests;
+
+const APP_DIR: &str = "mtty";
+
+/// Set by `--data-dir`, replacing the data and state directories
+static DATA_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use `dir` for data and state files instead of the XDG/platform locations.
+/// Only the first call has an effect.
+pub fn set_data_dir(dir: PathBuf) {
+    let _ = DATA_DIR_OVERRIDE.set(dir);
+}
+
+/// Pick a directory: an override wins, then an XDG variable (ignored when empty or
+/// relative, as the spec requires), then the platform default. `mtty` is appended to
+/// the latter two.
+pub fn resolve(
+    override_dir: Option<&Path>,
+    xdg_value: Option<&str>,
+    platform_dir: Option<PathBuf>,
+) -> Option<PathBuf> {
+    if let Some(dir) = override_dir {
+        return Some(dir.to_path_buf());
+    }
+    xdg_value
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or(platform_dir)
+        .map(|dir| dir.join(APP_DIR))
+}
+
+/// `$XDG_CONFIG_HOME/mtty`, or `~/.config/mtty`, `~/Library/Application Support/mtty`,
+/// `%APPDATA%\mtty`
+pub fn config_dir() -> Option<PathBuf> {
+    resolve(
+        None,
+        env::var("XDG_CONFIG_HOME").ok().as_deref(),
+        dirs::config_dir(),
+    )
+}
+
+pub fn config_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// `--data-dir`, `$XDG_DATA_HOME/mtty`, or `~/.local/share/mtty`,
+/// `~/Library/Application Support/mtty`, `%APPDATA%\mtty`
+pub fn data_dir() -> Option<PathBuf> {
+    resolve(
+        DATA_DIR_OVERRIDE.get().map(PathBuf::as_path),
+        env::var("XDG_DATA_HOME").ok().as_deref(),
+        dirs::data_dir(),
+    )
+}
+
+/// `--data-dir`/state, `$XDG_STATE_HOME/mtty`, or `~/.local/state/mtty`; macOS and Windows
+/// have no state directory and use `~/Library/Application Support/mtty`, `%LOCALAPPDATA%\mtty`
+pub fn state_dir() -> Option<PathBuf> {
+    let override_dir = DATA_DIR_OVERRIDE.get().map(|dir| dir.join("state"));
+    resolve(
+        override_dir.as_deref(),
+        env::var("XDG_STATE_HOME").ok().as_deref(),
+        dirs::state_dir().or_else(dirs::data_local_dir),
+    )
+}
@@ -0,0 +1,30 @@
+use super::*;
+
+#[test]
+fn resolve_should_prefer_override_without_appending_app_dir() {
+    let dir = resolve(
+        Some(Path::new("/tmp/custom")),
+        Some("/xdg"),
+        Some(PathBuf::from("/platform")),
+    );
+    assert_eq!(dir, Some(PathBuf::from("/tmp/custom")));
+}
+
+#[test]
+fn resolve_should_use_xdg_variable_before_platform_default() {
+    let dir = resolve(None, Some("/xdg"), Some(PathBuf::from("/platform")));
+    assert_eq!(dir, Some(PathBuf::from("/xdg/mtty")));
+}
+
+#[test]
+fn resolve_should_ignore_empty_and_relative_xdg_values() {
+    let platform = Some(PathBuf::from("/platform"));
+    let expected = Some(PathBuf::from("/platform/mtty"));
+    assert_eq!(resolve(None, Some(""), platform.clone()), expected);
+    assert_eq!(
+        resolve(None, Some("relative/dir"), platform.clone()),
+        expected
+    );
+    assert_eq!(resolve(None, None, platform), expected);
+    assert_eq!(resolve(None, None, None), None);
+}
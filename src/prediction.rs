@@ -0,0 +1,108 @@
+use std::time::{Duration, Instant};
+
+use crate::grid::Grid;
+
+#[cfg(test)]
+mod tests;
+
+/// How long a prediction may go unconfirmed before it is assumed wrong
+const PREDICTION_TIMEOUT: Duration = Duration::from_secs(1);
+const DELETE: char = '\x7f';
+
+/// A character expected to be echoed at a buffer position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Prediction {
+    pub row: usize,
+    pub col: usize,
+    pub c: char,
+    typed_at: Instant,
+}
+
+/// Mosh-style local echo: guesses where typed characters will appear and checks
+/// the guesses against the real output as it arrives.
+///
+/// Predictions are only shown once one of them has been confirmed since the last
+/// Enter or misprediction, so input that is never echoed (passwords) stays hidden.
+#[derive(Debug, Default)]
+pub struct Predictor {
+    predictions: Vec<Prediction>,
+    confirmed: bool,
+}
+
+impl Predictor {
+    /// Predict the echo of `data` typed at the keyboard
+    pub fn input(&mut self, data: &[u8], grid: &Grid, now: Instant) {
+        for c in String::from_utf8_lossy(data).chars() {
+            match c {
+                DELETE => {
+                    self.predictions.pop();
+                }
+                c if c.is_control() => {
+                    // Enter, escape sequences and control keys have unpredictable effects
+                    self.reset();
+                    return;
+                }
+                c => {
+                    let (row, col) = match self.predictions.last() {
+                        Some(last) => (last.row, last.col + 1),
+                        None => grid.cursor_pos,
+                    };
+                    // Leave wrapping to the real output
+                    if col >= grid.width as usize {
+                        return;
+                    }
+                    self.predictions.push(Prediction {
+                        row,
+                        col,
+                        c,
+                        typed_at: now,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Check pending predictions against the grid, in typing order.
+    ///
+    /// Echoed ones are dropped as confirmed. Once the cursor passes one that was not
+    /// echoed, or it times out, all are discarded. Returns true when the shown set changed.
+    pub fn reconcile(&mut self, grid: &Grid, now: Instant) -> bool {
+        let before = self.visible().len();
+        let cells = grid.active_grid_ref();
+        let width = grid.width as usize;
+
+        while let Some(first) = self.predictions.first().copied() {
+            let echoed = cells
+                .get(first.row * width + first.col)
+                .is_some_and(|cell| cell.char == first.c);
+            let (row, col) = grid.cursor_pos;
+            if echoed {
+                self.predictions.remove(0);
+                self.confirmed = true;
+            } else if row != first.row
+                || col > first.col
+                || now.duration_since(first.typed_at) > PREDICTION_TIMEOUT
+            {
+                self.reset();
+                return before > 0;
+            } else {
+                break;
+            }
+        }
+        before != self.visible().len()
+    }
+
+    /// Predictions to draw, empty until the current run has been confirmed
+    pub fn visible(&self) -> &[Prediction] {
+        if self.confirmed {
+            &self.predictions
+        } else {
+            &[]
+        }
+    }
+
+    fn reset(&mut self) {
+        self.predictions.clear();
+        self.confirmed = false;
+    }
+}
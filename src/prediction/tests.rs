@@ -0,0 +1,86 @@
+use std::time::{Duration, Instant};
+
+use crate::{config::Config, grid::Grid};
+
+use super::Predictor;
+
+fn grid() -> Grid {
+    Grid::new(&Config {
+        cols: 10,
+        rows: 3,
+        ..Config::default()
+    })
+}
+
+fn echo(grid: &mut Grid, text: &str) {
+    for c in text.chars() {
+        grid.place_character_in_grid(grid.width, c);
+    }
+}
+
+fn predicted(predictor: &Predictor) -> String {
+    predictor.visible().iter().map(|p| p.c).collect()
+}
+
+#[test]
+fn predictions_should_stay_hidden_until_first_echo() {
+    let mut grid = grid();
+    let mut predictor = Predictor::default();
+    let now = Instant::now();
+
+    predictor.input(b"ls", &grid, now);
+    assert_eq!(predicted(&predictor), "");
+
+    echo(&mut grid, "l");
+    assert!(predictor.reconcile(&grid, now));
+
+    assert_eq!(predicted(&predictor), "s");
+    assert_eq!(predictor.visible()[0].col, 1);
+}
+
+#[test]
+fn misprediction_should_discard_all_predictions() {
+    let mut grid = grid();
+    let mut predictor = Predictor::default();
+    let now = Instant::now();
+    predictor.input(b"ab", &grid, now);
+    echo(&mut grid, "a");
+    predictor.reconcile(&grid, now);
+    predictor.input(b"c", &grid, now);
+
+    echo(&mut grid, "X");
+    predictor.reconcile(&grid, now);
+
+    assert_eq!(predicted(&predictor), "");
+    // A new run has to be confirmed again before it is shown
+    predictor.input(b"d", &grid, now);
+    assert_eq!(predicted(&predictor), "");
+}
+
+#[test]
+fn unechoed_predictions_should_time_out() {
+    let grid = grid();
+    let mut predictor = Predictor::default();
+    let now = Instant::now();
+    predictor.input(b"secret", &grid, now);
+
+    predictor.reconcile(&grid, now + Duration::from_secs(2));
+
+    assert!(predictor.predictions.is_empty());
+}
+
+#[test]
+fn control_keys_should_reset_and_delete_should_retract() {
+    let mut grid = grid();
+    let mut predictor = Predictor::default();
+    let now = Instant::now();
+    predictor.input(b"ab", &grid, now);
+    echo(&mut grid, "a");
+    predictor.reconcile(&grid, now);
+
+    predictor.input(b"cd\x7f", &grid, now);
+    assert_eq!(predicted(&predictor), "bc");
+
+    predictor.input(b"\r", &grid, now);
+    assert!(predictor.predictions.is_empty());
+}
@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use tracing::{level_filters::LevelFilter, Subscriber};
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::{layer::SubscriberExt, Layer};
+
+#[cfg(test)]
+mod tests;
+
+/// Target of the profiling spans. With `log-always`, creating a span also emits a `log` record;
+/// keeping them outside this crate's `mtty::` targets keeps one per PTY read and frame out of
+/// the log viewer.
+pub const SPAN_TARGET: &str = "mtty_profile";
+
+/// Most verbose level written to the trace. The hot-path spans are at INFO; per-sequence debug
+/// events would make traces of busy output unmanageably large.
+const TRACE_LEVEL: LevelFilter = LevelFilter::INFO;
+
+/// Build a subscriber that writes chrome://tracing (Perfetto) compatible JSON to `path`.
+///
+/// The trace is only complete once the returned guard is dropped.
+pub fn chrome_subscriber(path: &Path) -> (impl Subscriber + Send + Sync, FlushGuard) {
+    let (layer, guard) = ChromeLayerBuilder::new()
+        .file(path)
+        .include_args(true)
+        .build();
+    let subscriber = tracing_subscriber::registry().with(layer.with_filter(TRACE_LEVEL));
+    (subscriber, guard)
+}
+
+/// Install the chrome trace exporter globally. Keep the guard alive until shutdown.
+pub fn init(path: &Path) -> Option<FlushGuard> {
+    let (subscriber, guard) = chrome_subscriber(path);
+    match tracing::subscriber::set_global_default(subscriber) {
+        Ok(()) => {
+            log::info!("Writing trace to {:?}", path);
+            Some(guard)
+        }
+        Err(e) => {
+            log::warn!("Failed to install trace exporter: {}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use super::{chrome_subscriber, SPAN_TARGET};
+
+#[test]
+fn chrome_trace_should_contain_spans() {
+    let path = std::env::temp_dir().join(format!("mtty-trace-test-{}.json", std::process::id()));
+    let (subscriber, guard) = chrome_subscriber(&path);
+
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info_span!(target: SPAN_TARGET, "render_pass").in_scope(|| {});
+        // Below the trace level, so it must not be written
+        tracing::debug_span!("noisy").in_scope(|| {});
+    });
+    drop(guard);
+
+    let trace = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let events: serde_json::Value = serde_json::from_str(&trace).unwrap();
+    let names: Vec<&str> = events
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|event| event["name"].as_str())
+        .collect();
+    assert!(names.contains(&"render_pass"));
+    assert!(!names.contains(&"noisy"));
+}
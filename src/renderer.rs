@@ -1,41 +1,46 @@
-use std::sync::Arc;
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use glyphon::{
-    Attrs, Buffer, Cache, Color as GlyphonColor, Family, FontSystem, Metrics, Resolution, Shaping,
-    SwashCache, TextArea, TextAtlas, TextBounds, TextRenderer, Viewport,
+    cosmic_text::{LayoutGlyph, ShapeGlyph},
+    Attrs, Buffer, Cache, Color as GlyphonColor, ColorMode, Family, FontSystem, Metrics,
+    Resolution, Shaping, SwashCache, TextArea, TextAtlas, TextBounds, TextRenderer, Viewport,
 };
 use wgpu::{
-    Backends, Buffer as WgpuBuffer, Device, DeviceDescriptor, Features, Instance,
-    InstanceDescriptor, Limits, PipelineCompilationOptions, PresentMode, Queue, RenderPipeline,
-    RequestAdapterOptions, Surface, SurfaceConfiguration, TextureUsages,
+    BindGroup, BindGroupLayout, Buffer as WgpuBuffer, CommandEncoder, Device,
+    PipelineCompilationOptions, Queue, RenderPipeline, Sampler, Surface, SurfaceConfiguration,
+    TextureFormat, TextureUsages, TextureView,
 };
 use winit::{dpi::PhysicalSize, window::Window};
 
-/// Detect if running under WSL2 by checking for WSL-specific indicators
-fn is_wsl2() -> bool {
-    // Check for WSL-specific environment variable
-    if std::env::var("WSL_DISTRO_NAME").is_ok() {
-        return true;
-    }
-
-    // Check /proc/version for Microsoft/WSL indicators
-    if let Ok(version) = std::fs::read_to_string("/proc/version") {
-        let version_lower = version.to_lowercase();
-        if version_lower.contains("microsoft") || version_lower.contains("wsl") {
-            return true;
-        }
-    }
-
-    false
-}
-
 use crate::{
-    config::Config,
+    accessibility::ensure_contrast,
+    config::{ColorFilter, Config, CursorAnimationStyle},
+    cursor_animation::{CursorAnimation, Point},
+    fonts::{self, FontRendering},
+    gpu::{self, is_wsl2},
     grid::Grid,
-    styles::{Color, Styles},
-    ui::DebugInfo,
+    highlights::HighlightRules,
+    links::Hint,
+    minimap::Block,
+    prediction::Prediction,
+    profiling::SPAN_TARGET,
+    selection::Selection,
+    shell_integration::Annotation,
+    styles::{srgb_to_linear, Color, CursorShape, Styles},
 };
 
+mod color_filter;
+mod post_process;
+#[cfg(test)]
+mod tests;
+
+use color_filter::ColorFilterPass;
+use post_process::{PostProcessPass, ShaderSource};
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 struct BgVertex {
@@ -61,6 +66,8 @@ pub struct Renderer {
     queue: Queue,
     surface: Surface<'static>,
     surface_config: SurfaceConfiguration,
+    /// Whether the surface encodes to sRGB on write (colors must then be given in linear space)
+    surface_is_srgb: bool,
     size: PhysicalSize<u32>,
 
     // Text rendering (glyphon)
@@ -71,8 +78,18 @@ pub struct Renderer {
     viewport: Viewport,
     text_buffer: Buffer,
 
-    // FPS overlay text buffer
-    fps_buffer: Buffer,
+    // Overlay panels drawn above the grid (debug HUD, etc.)
+    overlay_text_renderer: TextRenderer,
+    overlay_buffers: Vec<Buffer>,
+    overlay_vertex_buffer: WgpuBuffer,
+    overlay_index_buffer: WgpuBuffer,
+    overlay_index_count: u32,
+
+    // Minimap strip drawn above the text at the right edge
+    minimap_vertex_buffer: WgpuBuffer,
+    minimap_index_buffer: WgpuBuffer,
+    minimap_capacity: usize,
+    minimap_index_count: u32,
 
     // Background rendering
     bg_pipeline: RenderPipeline,
@@ -82,9 +99,41 @@ pub struct Renderer {
     // Cell dimensions
     cell_width: f32,
     cell_height: f32,
+    // Rows kept free above the grid (top status bar)
+    top_rows: usize,
 
     // Font family name (None = system monospace)
     font_family: Option<String>,
+    font_rendering: FontRendering,
+    // Regex recoloring applied while building rows
+    highlights: HighlightRules,
+    // Locally echoed characters, drawn underlined over the grid
+    predictions: Vec<Prediction>,
+    // Mouse selection, drawn with foreground and background swapped
+    selection: Option<Selection>,
+    // Command durations and exit statuses, drawn at the end of prompt lines
+    annotations: Vec<Annotation>,
+    // Link hint labels, drawn over the start of each link
+    hints: Vec<Hint>,
+    // Shade the cursor's row on the main screen
+    cursor_line_highlight: bool,
+    // Beam width and underline height in pixels, automatic when unset
+    cursor_thickness: Option<f32>,
+    // Columns after which a vertical guide is drawn
+    rulers: Vec<u16>,
+    // Text is lightened or darkened to at least this contrast ratio against its background
+    minimum_contrast: Option<f32>,
+    // Blink phase; blinking cursors are left out while it is off
+    blink_visible: bool,
+    // Animated cursor drawn as a quad instead of a glyph (None draws the glyph)
+    cursor_animation: Option<CursorAnimation>,
+    cursor_vertex_buffer: WgpuBuffer,
+    cursor_index_buffer: WgpuBuffer,
+    cursor_index_count: u32,
+    // Offscreen pass the frame goes through while a color filter is active
+    color_filter: Option<ColorFilterPass>,
+    // User or preset shader run over the frame before the color filter
+    post_process: Option<PostProcessPass>,
 
     // Per-row cached render data for incremental updates
     cached_row_bg_vertices: Vec<Vec<BgVertex>>,
@@ -118,7 +167,8 @@ impl Renderer {
                 log::error!("Please ensure WSLg is enabled: run 'wsl --update' from Windows and restart WSL");
                 log::error!("Or set DISPLAY if using an X server like VcXsrv");
                 panic!("No display server available. WSL2 requires WSLg or an X server for GUI applications. \
-                       Run 'wsl --update' from Windows PowerShell and restart WSL with 'wsl --shutdown'.");
+                       Run 'wsl --update' from Windows PowerShell and restart WSL with 'wsl --shutdown'. \
+                       Run 'mtty doctor' for details.");
             }
             log::info!(
                 "WSL2 detected, DISPLAY={:?}, WAYLAND_DISPLAY={:?}",
@@ -127,18 +177,21 @@ impl Renderer {
             );
         }
 
-        // On WSL2, try Vulkan first (native WSLg support), then GL as fallback
-        let backends = if is_wsl2() {
-            log::info!("WSL2 detected, trying Vulkan and GL backends");
-            Backends::VULKAN | Backends::GL
-        } else {
-            Backends::all()
-        };
-
-        let instance = Instance::new(&InstanceDescriptor {
+        let backends = gpu::backends_for(config.gpu_backend);
+        log::info!(
+            "Requested GPU backend: {:?} ({:?}), power preference: {:?}",
+            config.gpu_backend,
             backends,
-            ..Default::default()
-        });
+            config.power_preference
+        );
+
+        // Reuse the instance the device was preloaded on, so its adapter can be checked
+        // against the window's surface
+        let preloaded = gpu::take_preloaded();
+        let instance = match &preloaded {
+            Some(context) => context.instance.clone(),
+            None => gpu::create_instance(config),
+        };
 
         // Create surface with better error handling
         let surface = instance.create_surface(window.clone()).unwrap_or_else(|e| {
@@ -148,52 +201,35 @@ impl Renderer {
                     "Surface creation failed on WSL2. Ensure WSLg is properly configured: \
                        1. Run 'wsl --update' from Windows PowerShell \
                        2. Restart WSL with 'wsl --shutdown' \
-                       3. Ensure your GPU drivers are up to date on Windows"
+                       3. Ensure your GPU drivers are up to date on Windows \
+                       Run 'mtty doctor' for details."
                 );
             } else {
-                panic!("Failed to create rendering surface: {:?}", e);
+                panic!(
+                    "Failed to create rendering surface: {:?}. Run 'mtty doctor' for details.",
+                    e
+                );
             }
         });
 
-        // Request adapter and device
-        let (adapter, device, queue) = pollster::block_on(async {
-            // In WSL2, try with fallback adapter enabled for better compatibility
-            // Also try fallback if the primary adapter request fails
-            let adapter = instance
-                .request_adapter(&RequestAdapterOptions {
-                    power_preference: wgpu::PowerPreference::LowPower,
-                    compatible_surface: Some(&surface),
-                    force_fallback_adapter: false,
-                })
-                .await
-                .or_else(|| {
-                    log::warn!("Primary adapter not available, trying fallback adapter");
-                    pollster::block_on(instance.request_adapter(&RequestAdapterOptions {
-                        power_preference: wgpu::PowerPreference::LowPower,
-                        compatible_surface: Some(&surface),
-                        force_fallback_adapter: true,
-                    }))
-                })
-                .expect("Failed to find an appropriate adapter. Ensure your graphics drivers are installed and up to date. On WSL2, enable GPU support with 'wsl --update'.");
-
-            log::info!("Using graphics adapter: {:?}", adapter.get_info());
-
-            let (device, queue) = adapter
-                .request_device(
-                    &DeviceDescriptor {
-                        label: Some("MTTY Device"),
-                        required_features: Features::empty(),
-                        required_limits: Limits::downlevel_webgl2_defaults()
-                            .using_resolution(adapter.limits()),
-                        memory_hints: Default::default(),
-                    },
-                    None,
-                )
-                .await
-                .expect("Failed to create device");
-
-            (adapter, device, queue)
-        });
+        // Request adapter and device, unless the preloaded ones can present to this window
+        let preloaded = preloaded.filter(|context| context.adapter.is_surface_supported(&surface));
+        let (adapter, device, queue) = match preloaded {
+            Some(context) => (context.adapter, context.device, context.queue),
+            None => pollster::block_on(async {
+                let adapter = gpu::select_adapter(&instance, &surface, config)
+                    .await
+                    .expect("Failed to find an appropriate adapter. Ensure your graphics drivers are installed and up to date. On WSL2, enable GPU support with 'wsl --update'. Run 'mtty doctor' to list adapters.");
+                let (device, queue) = gpu::request_device(&adapter)
+                    .await
+                    .expect("Failed to create device");
+                (adapter, device, queue)
+            }),
+        };
+        log::info!(
+            "Using graphics adapter: {}",
+            gpu::describe_adapter(&adapter.get_info())
+        );
 
         // Configure surface - use sRGB format for correct color output
         let surface_caps = surface.get_capabilities(&adapter);
@@ -203,13 +239,27 @@ impl Renderer {
             .find(|f| f.is_srgb())
             .copied()
             .unwrap_or(surface_caps.formats[0]);
+        let surface_is_srgb = surface_format.is_srgb();
+        if !surface_is_srgb {
+            log::warn!(
+                "No sRGB surface format available, using {:?} without GPU color encoding",
+                surface_format
+            );
+        }
+
+        let present_mode = gpu::present_mode_for(config.present_mode, &surface_caps.present_modes);
+        log::info!(
+            "Present mode: {:?} (configured: {:?})",
+            present_mode,
+            config.present_mode
+        );
 
         let surface_config = SurfaceConfiguration {
             usage: TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
             width: size.width,
             height: size.height,
-            present_mode: PresentMode::AutoVsync,
+            present_mode,
             alpha_mode: surface_caps.alpha_modes[0],
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
@@ -217,17 +267,32 @@ impl Renderer {
         surface.configure(&device, &surface_config);
 
         // Initialize glyphon for text rendering (uses system fonts)
-        let mut font_system = FontSystem::new();
+        let mut font_system = fonts::font_system();
 
-        let swash_cache = SwashCache::new();
+        let font_rendering = FontRendering::from_config(config);
+        let swash_cache = font_rendering.create_swash_cache();
         let cache = Cache::new(&device);
-        let mut text_atlas = TextAtlas::new(&device, &queue, &cache, surface_format);
+        // Both the text and background paths derive their color handling from the same format:
+        // glyphon only linearizes glyph colors when the surface will encode them back to sRGB
+        let color_mode = if surface_is_srgb {
+            ColorMode::Accurate
+        } else {
+            ColorMode::Web
+        };
+        let mut text_atlas =
+            TextAtlas::with_color_mode(&device, &queue, &cache, surface_format, color_mode);
         let text_renderer = TextRenderer::new(
             &mut text_atlas,
             &device,
             wgpu::MultisampleState::default(),
             None,
         );
+        let overlay_text_renderer = TextRenderer::new(
+            &mut text_atlas,
+            &device,
+            wgpu::MultisampleState::default(),
+            None,
+        );
 
         let viewport = Viewport::new(&device, &cache);
 
@@ -243,25 +308,7 @@ impl Renderer {
             Some(size.height as f32),
         );
 
-        // Create FPS overlay buffer
-        let fps_buffer = Buffer::new(&mut font_system, Metrics::new(font_size, line_height));
-
-        // Measure actual cell width from font by shaping a character
-        let mut measure_buffer =
-            Buffer::new(&mut font_system, Metrics::new(font_size, line_height));
-        let font_attrs = match &font_family {
-            Some(name) => Attrs::new().family(Family::Name(name)),
-            None => Attrs::new().family(Family::Monospace),
-        };
-        measure_buffer.set_text(&mut font_system, "M", font_attrs, Shaping::Advanced);
-        measure_buffer.shape_until_scroll(&mut font_system, false);
-
-        let cell_width = measure_buffer
-            .layout_runs()
-            .next()
-            .and_then(|run| run.glyphs.first())
-            .map(|g| g.w)
-            .unwrap_or(font_size * 0.6);
+        let cell_width = measure_cell_width(&mut font_system, font_family.as_deref(), font_size);
         let cell_height = line_height;
 
         log::info!(
@@ -272,54 +319,12 @@ impl Renderer {
             font_family
         );
 
-        // Create background rendering pipeline
-        let bg_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Background Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/bg.wgsl").into()),
-        });
+        let bg_pipeline = create_bg_pipeline(&device, surface_format);
 
-        let bg_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("Background Pipeline Layout"),
-            bind_group_layouts: &[],
-            push_constant_ranges: &[],
-        });
-
-        let bg_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Background Pipeline"),
-            layout: Some(&bg_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &bg_shader,
-                entry_point: Some("vs_main"),
-                buffers: &[BgVertex::desc()],
-                compilation_options: PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &bg_shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: surface_format,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
-            cache: None,
+        let color_filter = (config.color_filter != ColorFilter::None).then(|| {
+            let pass = ColorFilterPass::new(&device, surface_format, size.width, size.height);
+            pass.set_filter(&queue, config.color_filter, surface_is_srgb);
+            pass
         });
 
         // Pre-allocate buffers for background quads
@@ -341,11 +346,74 @@ impl Renderer {
             mapped_at_creation: false,
         });
 
+        let (minimap_vertex_buffer, minimap_index_buffer) =
+            create_quad_buffers(&device, "Minimap", MINIMAP_INITIAL_BLOCKS);
+
+        let overlay_vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Overlay Vertex Buffer"),
+            size: (MAX_OVERLAYS * 4 * std::mem::size_of::<BgVertex>()) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let overlay_index_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Overlay Index Buffer"),
+            size: (MAX_OVERLAYS * 6 * std::mem::size_of::<u32>()) as u64,
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let shader_source = match (&config.shader_path, config.shader_preset) {
+            (Some(path), _) => Some(ShaderSource::File(path.clone())),
+            (None, Some(preset)) => Some(ShaderSource::Preset(preset)),
+            (None, None) => None,
+        };
+        let post_process = shader_source.map(|source| {
+            let pass =
+                PostProcessPass::new(&device, surface_format, size.width, size.height, source);
+            pass.set_params(
+                &queue,
+                (size.width as f32, size.height as f32),
+                (cell_width, cell_height),
+            );
+            pass
+        });
+
+        let cursor_animation = (config.cursor_animation != CursorAnimationStyle::None
+            && !config.reduce_motion)
+            .then(|| {
+                CursorAnimation::new(
+                    config.cursor_animation,
+                    Duration::from_millis(config.cursor_animation_duration),
+                    Instant::now(),
+                )
+            });
+
+        let cursor_vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Cursor Vertex Buffer"),
+            size: (4 * std::mem::size_of::<BgVertex>()) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let cursor_index_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Cursor Index Buffer"),
+            size: (6 * std::mem::size_of::<u32>()) as u64,
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        queue.write_buffer(
+            &cursor_index_buffer,
+            0,
+            bytemuck::cast_slice(&[0u32, 3, 2, 0, 2, 1]),
+        );
+
         Self {
             device,
             queue,
             surface,
             surface_config,
+            surface_is_srgb,
             size,
             font_system,
             swash_cache,
@@ -353,13 +421,39 @@ impl Renderer {
             text_renderer,
             viewport,
             text_buffer,
-            fps_buffer,
+            overlay_text_renderer,
+            overlay_buffers: Vec::new(),
+            overlay_vertex_buffer,
+            overlay_index_buffer,
+            overlay_index_count: 0,
+            minimap_vertex_buffer,
+            minimap_index_buffer,
+            minimap_capacity: MINIMAP_INITIAL_BLOCKS,
+            minimap_index_count: 0,
             bg_pipeline,
             bg_vertex_buffer,
             bg_index_buffer,
             cell_width,
             cell_height,
+            top_rows: 0,
             font_family,
+            font_rendering,
+            highlights: HighlightRules::new(&config.highlights),
+            predictions: Vec::new(),
+            selection: None,
+            annotations: Vec::new(),
+            hints: Vec::new(),
+            cursor_line_highlight: config.cursor_line_highlight,
+            cursor_thickness: config.cursor_thickness,
+            rulers: config.rulers.clone(),
+            minimum_contrast: None,
+            blink_visible: true,
+            cursor_animation,
+            cursor_vertex_buffer,
+            cursor_index_buffer,
+            cursor_index_count: 0,
+            color_filter,
+            post_process,
             cached_row_bg_vertices: Vec::new(),
             cached_row_text_spans: Vec::new(),
             num_cached_rows: 0,
@@ -376,6 +470,17 @@ impl Renderer {
             self.surface_config.width = new_size.width;
             self.surface_config.height = new_size.height;
             self.surface.configure(&self.device, &self.surface_config);
+            if let Some(pass) = &mut self.color_filter {
+                pass.resize(&self.device, new_size.width, new_size.height);
+            }
+            if let Some(pass) = &mut self.post_process {
+                pass.resize(&self.device, new_size.width, new_size.height);
+                pass.set_params(
+                    &self.queue,
+                    (new_size.width as f32, new_size.height as f32),
+                    (self.cell_width, self.cell_height),
+                );
+            }
 
             // Update text buffer size
             self.text_buffer.set_size(
@@ -414,19 +519,206 @@ impl Renderer {
         }
     }
 
+    /// Change the font size at runtime, re-measuring the cell size.
+    ///
+    /// The caller is responsible for resizing the grid to the new cell dimensions.
+    pub fn set_font_size(&mut self, font_size: f32) {
+        let line_height = font_size * 1.2;
+        self.text_buffer
+            .set_metrics(&mut self.font_system, Metrics::new(font_size, line_height));
+        self.cell_width = measure_cell_width(
+            &mut self.font_system,
+            self.font_family.as_deref(),
+            font_size,
+        );
+        self.cell_height = line_height;
+        // Overlay buffers pick up the new metrics when they are recreated
+        self.overlay_buffers.clear();
+
+        log::info!(
+            "Font size changed to {}, cell dimensions: {}x{}",
+            font_size,
+            self.cell_width,
+            self.cell_height
+        );
+
+        // Reallocate background buffers and invalidate row caches
+        self.resize(self.size);
+    }
+
+    /// Shift the grid down by `rows` cell rows, leaving room for a top status bar
+    pub fn set_top_rows(&mut self, rows: usize) {
+        if rows != self.top_rows {
+            self.top_rows = rows;
+            // Cached background quads carry absolute positions
+            self.resize(self.size);
+        }
+    }
+
+    /// Replace the predicted characters; the caller marks their rows dirty
+    pub fn set_predictions(&mut self, predictions: &[Prediction]) {
+        self.predictions.clear();
+        self.predictions.extend_from_slice(predictions);
+    }
+
+    /// Replace the mouse selection; the caller marks its rows dirty
+    pub fn set_selection(&mut self, selection: Option<Selection>) {
+        self.selection = selection;
+    }
+
+    /// Replace the command annotations; the caller marks their rows dirty
+    pub fn set_annotations(&mut self, annotations: &[Annotation]) {
+        self.annotations.clear();
+        self.annotations.extend_from_slice(annotations);
+    }
+
+    /// Replace the link hint labels; the caller marks their rows dirty
+    pub fn set_hints(&mut self, hints: &[Hint]) {
+        self.hints.clear();
+        self.hints.extend_from_slice(hints);
+    }
+
+    /// Replace the minimap blocks drawn over the grid (empty to hide it)
+    pub fn set_minimap(&mut self, blocks: &[Block]) {
+        if blocks.len() > self.minimap_capacity {
+            self.minimap_capacity = blocks.len().next_power_of_two();
+            (self.minimap_vertex_buffer, self.minimap_index_buffer) =
+                create_quad_buffers(&self.device, "Minimap", self.minimap_capacity);
+        }
+
+        let (width, height) = (self.size.width as f32, self.size.height as f32);
+        let mut vertices = Vec::with_capacity(blocks.len() * 4);
+        let mut indices = Vec::with_capacity(blocks.len() * 6);
+        for block in blocks {
+            let (r, g, b) = block.color;
+            let color = [
+                srgb_component(r, self.surface_is_srgb),
+                srgb_component(g, self.surface_is_srgb),
+                srgb_component(b, self.surface_is_srgb),
+                block.alpha,
+            ];
+            let base = vertices.len() as u32;
+            vertices.extend_from_slice(&quad_vertices(
+                block.x,
+                block.y,
+                block.width,
+                block.height,
+                width,
+                height,
+                color,
+            ));
+            indices.extend_from_slice(&[base, base + 3, base + 2, base, base + 2, base + 1]);
+        }
+
+        if !vertices.is_empty() {
+            self.queue.write_buffer(
+                &self.minimap_vertex_buffer,
+                0,
+                bytemuck::cast_slice(&vertices),
+            );
+            self.queue.write_buffer(
+                &self.minimap_index_buffer,
+                0,
+                bytemuck::cast_slice(&indices),
+            );
+        }
+        self.minimap_index_count = indices.len() as u32;
+    }
+
+    /// Enforce a minimum text contrast ratio (None to render colors as they are)
+    pub fn set_minimum_contrast(&mut self, ratio: Option<f32>) {
+        self.minimum_contrast = ratio;
+        // Cached rows were built with the old colors
+        self.resize(self.size);
+    }
+
+    /// Set the blink phase; the caller marks the cursor row dirty
+    pub fn set_blink_visible(&mut self, visible: bool) {
+        self.blink_visible = visible;
+    }
+
+    /// Simulate or correct for a color-vision deficiency over the whole frame
+    pub fn set_color_filter(&mut self, filter: ColorFilter) {
+        if filter == ColorFilter::None {
+            self.color_filter = None;
+            return;
+        }
+        let pass = self.color_filter.get_or_insert_with(|| {
+            ColorFilterPass::new(
+                &self.device,
+                self.surface_config.format,
+                self.size.width,
+                self.size.height,
+            )
+        });
+        pass.set_filter(&self.queue, filter, self.surface_is_srgb);
+    }
+
+    /// Pick up edits to the post-processing shader file, returning true when it was reloaded
+    pub fn reload_shader_if_changed(&mut self) -> bool {
+        self.post_process
+            .as_mut()
+            .is_some_and(|pass| pass.reload_if_changed(&self.device))
+    }
+
     pub fn size(&self) -> PhysicalSize<u32> {
         self.size
     }
 
+    /// Rows kept free above the grid
+    pub fn top_rows(&self) -> usize {
+        self.top_rows
+    }
+
     pub fn cell_dimensions(&self) -> (f32, f32) {
         (self.cell_width, self.cell_height)
     }
 
+    /// Present a frame cleared to the default background, without drawing the grid
+    pub fn present_blank(&mut self, grid: &Grid) -> Result<(), wgpu::SurfaceError> {
+        let output = self.surface.get_current_texture()?;
+        let view = output
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let [r, g, b, _] = grid
+            .styles
+            .to_wgpu_color(grid.styles.default_background_color, self.surface_is_srgb);
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Blank Frame Encoder"),
+            });
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Blank Frame"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color {
+                        r: r as f64,
+                        g: g as f64,
+                        b: b as f64,
+                        a: 1.0,
+                    }),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        self.queue.submit(std::iter::once(encoder.finish()));
+        output.present();
+        Ok(())
+    }
+
     pub fn render(
         &mut self,
         grid: &mut Grid,
-        debug_info: &DebugInfo,
+        overlays: &[Overlay],
     ) -> Result<(), wgpu::SurfaceError> {
+        let _span = tracing::info_span!(target: SPAN_TARGET, "render").entered();
         let output = self.surface.get_current_texture()?;
         let view = output
             .texture
@@ -440,6 +732,13 @@ impl Renderer {
         let needs_rebuild = grid.is_dirty() || self.num_cached_rows != num_visible_rows;
 
         if needs_rebuild {
+            let _shape_span = tracing::info_span!(
+                target: SPAN_TARGET,
+                "shape",
+                dirty_rows = grid.dirty_row_count()
+            )
+            .entered();
+
             // Ensure caches are properly sized
             if self.num_cached_rows != num_visible_rows {
                 self.cached_row_bg_vertices
@@ -485,6 +784,32 @@ impl Renderer {
                     .extend(self.cached_row_text_spans[row_idx].iter().cloned());
             }
 
+            // Rulers go above the cell backgrounds but behind the text
+            let mut ruler_color = grid
+                .styles
+                .to_wgpu_color(grid.styles.default_text_color, self.surface_is_srgb);
+            ruler_color[3] = RULER_OPACITY;
+            let rulers = ruler_quads(
+                &self.rulers,
+                (grid.width, grid.height),
+                (self.cell_width, self.cell_height),
+                self.top_rows,
+                (self.size.width as f32, self.size.height as f32),
+                ruler_color,
+            );
+            for quad in rulers {
+                let base = self.combined_bg_vertices.len() as u32;
+                self.combined_bg_vertices.extend_from_slice(&quad);
+                self.combined_bg_indices.extend_from_slice(&[
+                    base,
+                    base + 3,
+                    base + 2,
+                    base,
+                    base + 2,
+                    base + 1,
+                ]);
+            }
+
             // Store index count for draw call
             self.current_bg_index_count = self.combined_bg_indices.len() as u32;
 
@@ -542,40 +867,19 @@ impl Renderer {
             },
         );
 
-        // Prepare FPS overlay if debug mode is enabled
-        if debug_info.show {
-            let fps_text = format!("{:.1}", debug_info.fps);
-            let fps_attrs = match &self.font_family {
-                Some(name) => Attrs::new()
-                    .family(Family::Name(name))
-                    .color(GlyphonColor::rgb(0, 255, 0)),
-                None => Attrs::new()
-                    .family(Family::Monospace)
-                    .color(GlyphonColor::rgb(0, 255, 0)),
-            };
-            self.fps_buffer.set_text(
-                &mut self.font_system,
-                &fps_text,
-                fps_attrs,
-                Shaping::Advanced,
-            );
-            self.fps_buffer
-                .shape_until_scroll(&mut self.font_system, false);
-        }
-
-        // Calculate FPS text position (top-right corner)
-        let fps_width = 100.0; // Approximate width for FPS text
-        let fps_left = self.size.width as f32 - fps_width;
+        self.prepare_cursor(grid);
+        let overlay_rects = self.prepare_overlays(overlays);
 
         // Build text areas
+        let grid_top = self.top_rows as f32 * self.cell_height;
         let main_text_area = TextArea {
             buffer: &self.text_buffer,
             left: 0.0,
-            top: 0.0,
+            top: grid_top,
             scale: 1.0,
             bounds: TextBounds {
                 left: 0,
-                top: 0,
+                top: grid_top as i32,
                 right: self.size.width as i32,
                 bottom: self.size.height as i32,
             },
@@ -583,47 +887,49 @@ impl Renderer {
             custom_glyphs: &[],
         };
 
-        let fps_text_area = TextArea {
-            buffer: &self.fps_buffer,
-            left: fps_left,
-            top: 4.0,
-            scale: 1.0,
-            bounds: TextBounds {
-                left: fps_left as i32,
-                top: 0,
-                right: self.size.width as i32,
-                bottom: self.size.height as i32,
-            },
-            default_color: GlyphonColor::rgb(0, 255, 0),
-            custom_glyphs: &[],
-        };
-
-        if debug_info.show {
-            self.text_renderer
-                .prepare(
-                    &self.device,
-                    &self.queue,
-                    &mut self.font_system,
-                    &mut self.text_atlas,
-                    &self.viewport,
-                    [main_text_area, fps_text_area],
-                    &mut self.swash_cache,
-                )
-                .unwrap();
-        } else {
-            self.text_renderer
-                .prepare(
-                    &self.device,
-                    &self.queue,
-                    &mut self.font_system,
-                    &mut self.text_atlas,
-                    &self.viewport,
-                    [main_text_area],
-                    &mut self.swash_cache,
-                )
-                .unwrap();
-        }
-
+        self.text_renderer
+            .prepare(
+                &self.device,
+                &self.queue,
+                &mut self.font_system,
+                &mut self.text_atlas,
+                &self.viewport,
+                [main_text_area],
+                &mut self.swash_cache,
+            )
+            .unwrap();
+
+        let overlay_text_areas =
+            overlay_rects
+                .iter()
+                .zip(&self.overlay_buffers)
+                .map(|(rect, buffer)| TextArea {
+                    buffer,
+                    left: rect.x + OVERLAY_PADDING,
+                    top: rect.y + rect.padding_y,
+                    scale: 1.0,
+                    bounds: TextBounds {
+                        left: rect.x as i32,
+                        top: rect.y as i32,
+                        right: (rect.x + rect.width) as i32,
+                        bottom: (rect.y + rect.height) as i32,
+                    },
+                    default_color: GlyphonColor::rgb(255, 255, 255),
+                    custom_glyphs: &[],
+                });
+        self.overlay_text_renderer
+            .prepare(
+                &self.device,
+                &self.queue,
+                &mut self.font_system,
+                &mut self.text_atlas,
+                &self.viewport,
+                overlay_text_areas,
+                &mut self.swash_cache,
+            )
+            .unwrap();
+
+        let _render_pass_span = tracing::info_span!(target: SPAN_TARGET, "render_pass").entered();
         let mut encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
@@ -631,7 +937,9 @@ impl Renderer {
             });
 
         // Convert default background color to wgpu::Color for clearing
-        let default_bg = color_to_rgba(grid.styles.default_background_color, &grid.styles);
+        let default_bg = grid
+            .styles
+            .to_wgpu_color(grid.styles.default_background_color, self.surface_is_srgb);
         let clear_color = wgpu::Color {
             r: default_bg[0] as f64,
             g: default_bg[1] as f64,
@@ -639,11 +947,20 @@ impl Renderer {
             a: 1.0,
         };
 
+        // With post-processing the frame is drawn offscreen, then run through the shader and
+        // the color filter (in that order) on its way to the surface
+        let post_process = self.post_process.as_ref().filter(|pass| pass.is_active());
+        let frame_view = match (post_process, &self.color_filter) {
+            (Some(pass), _) => pass.target(),
+            (None, Some(filter)) => filter.target(),
+            (None, None) => &view,
+        };
+
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
+                    view: frame_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(clear_color),
@@ -664,10 +981,57 @@ impl Renderer {
                 render_pass.draw_indexed(0..self.current_bg_index_count, 0, 0..1);
             }
 
+            // Animated cursor sits between the backgrounds and the glyph under it
+            if self.cursor_index_count > 0 {
+                render_pass.set_pipeline(&self.bg_pipeline);
+                render_pass.set_vertex_buffer(0, self.cursor_vertex_buffer.slice(..));
+                render_pass.set_index_buffer(
+                    self.cursor_index_buffer.slice(..),
+                    wgpu::IndexFormat::Uint32,
+                );
+                render_pass.draw_indexed(0..self.cursor_index_count, 0, 0..1);
+            }
+
             // Render text
             self.text_renderer
                 .render(&self.text_atlas, &self.viewport, &mut render_pass)
                 .unwrap();
+
+            // Minimap strip over the right edge of the grid
+            if self.minimap_index_count > 0 {
+                render_pass.set_pipeline(&self.bg_pipeline);
+                render_pass.set_vertex_buffer(0, self.minimap_vertex_buffer.slice(..));
+                render_pass.set_index_buffer(
+                    self.minimap_index_buffer.slice(..),
+                    wgpu::IndexFormat::Uint32,
+                );
+                render_pass.draw_indexed(0..self.minimap_index_count, 0, 0..1);
+            }
+
+            // Render overlay panels and their text above the grid
+            if self.overlay_index_count > 0 {
+                render_pass.set_pipeline(&self.bg_pipeline);
+                render_pass.set_vertex_buffer(0, self.overlay_vertex_buffer.slice(..));
+                render_pass.set_index_buffer(
+                    self.overlay_index_buffer.slice(..),
+                    wgpu::IndexFormat::Uint32,
+                );
+                render_pass.draw_indexed(0..self.overlay_index_count, 0, 0..1);
+            }
+            self.overlay_text_renderer
+                .render(&self.text_atlas, &self.viewport, &mut render_pass)
+                .unwrap();
+        }
+
+        if let Some(pass) = post_process {
+            let output = self
+                .color_filter
+                .as_ref()
+                .map_or(&view, |filter| filter.target());
+            pass.draw(&mut encoder, output);
+        }
+        if let Some(filter) = &self.color_filter {
+            filter.draw(&mut encoder, &view);
         }
 
         self.queue.submit(std::iter::once(encoder.finish()));
@@ -679,6 +1043,272 @@ impl Renderer {
         Ok(())
     }
 
+    /// Estimate the memory used by shaping data, caches and GPU buffers
+    pub fn memory_usage(&self) -> RendererMemory {
+        let mut memory = RendererMemory::default();
+        let mut glyphs = HashSet::new();
+        for buffer in std::iter::once(&self.text_buffer).chain(&self.overlay_buffers) {
+            for line in &buffer.lines {
+                memory.shaping += line.text().len();
+                if let Some(shape) = line.shape_opt() {
+                    let shaped = shape
+                        .spans
+                        .iter()
+                        .flat_map(|span| &span.words)
+                        .map(|word| word.glyphs.len())
+                        .sum::<usize>();
+                    memory.shaping += shaped * std::mem::size_of::<ShapeGlyph>();
+                }
+                for layout in line.layout_opt().iter().flat_map(|lines| lines.iter()) {
+                    memory.shaping += layout.glyphs.len() * std::mem::size_of::<LayoutGlyph>();
+                    glyphs.extend(layout.glyphs.iter().map(|g| (g.font_id, g.glyph_id)));
+                }
+            }
+        }
+        memory.glyphs = glyphs.len();
+        // One byte per pixel in the mask atlas, each glyph taking about a cell
+        memory.glyph_atlas =
+            glyphs.len() * (self.cell_width.ceil() * self.cell_height.ceil()) as usize;
+
+        memory.row_caches = self
+            .cached_row_bg_vertices
+            .iter()
+            .map(|row| row.capacity() * std::mem::size_of::<BgVertex>())
+            .sum::<usize>()
+            + self
+                .cached_row_text_spans
+                .iter()
+                .flatten()
+                .map(|(text, _)| text.capacity() + std::mem::size_of::<(String, GlyphonColor)>())
+                .sum::<usize>()
+            + self.combined_bg_vertices.capacity() * std::mem::size_of::<BgVertex>()
+            + self.combined_bg_indices.capacity() * std::mem::size_of::<u32>();
+
+        let frame = self.size.width as usize * self.size.height as usize * 4;
+        let offscreen_frames =
+            usize::from(self.color_filter.is_some()) + usize::from(self.post_process.is_some());
+        memory.gpu_buffers = [
+            &self.bg_vertex_buffer,
+            &self.bg_index_buffer,
+            &self.overlay_vertex_buffer,
+            &self.overlay_index_buffer,
+            &self.cursor_vertex_buffer,
+            &self.cursor_index_buffer,
+        ]
+        .iter()
+        .map(|buffer| buffer.size() as usize)
+        .sum::<usize>()
+            + offscreen_frames * frame;
+        memory
+    }
+
+    /// Block until the GPU has finished the submitted frames, returning when it did.
+    ///
+    /// Only called for frames that show a latency probe's echo. Timestamp queries are not used:
+    /// they measure GPU time in GPU ticks with no link to the CPU clock the keypress was timed
+    /// with, and reading them back needs the same wait.
+    pub fn wait_for_gpu(&self) -> Instant {
+        let _ = self.device.poll(wgpu::Maintain::Wait);
+        Instant::now()
+    }
+
+    /// Whether the cursor is mid-animation and needs further frames
+    pub fn is_animating(&self) -> bool {
+        self.cursor_animation
+            .as_ref()
+            .is_some_and(|animation| animation.is_animating(Instant::now()))
+    }
+
+    /// Advance the animated cursor and upload its quad
+    fn prepare_cursor(&mut self, grid: &Grid) {
+        self.cursor_index_count = 0;
+        let Some(animation) = &mut self.cursor_animation else {
+            return;
+        };
+        let now = Instant::now();
+        // Animate in buffer rows so scrolling the view moves the cursor with the text
+        let (row, col) = grid.cursor_pos;
+        animation.move_to((col as f32, row as f32), now);
+
+        let state = grid.styles.cursor_state;
+        let start_row = grid.top_row();
+        if state.hidden || (state.blinking && !self.blink_visible) || row < start_row {
+            return;
+        }
+        // Thin shapes are given in cell units, at least two pixels thick unless configured
+        let (beam, bar) = match self.cursor_thickness {
+            Some(thickness) => (
+                (thickness / self.cell_width).min(1.0),
+                (thickness / self.cell_height).min(1.0),
+            ),
+            None => (
+                (2.0 / self.cell_width).max(0.125),
+                (2.0 / self.cell_height).max(0.125),
+            ),
+        };
+        let (offsets, alpha): ([Point; 4], f32) = match state.shape {
+            CursorShape::Hidden => return,
+            CursorShape::Beam => ([(0.0, 0.0), (beam, 0.0), (beam, 1.0), (0.0, 1.0)], 1.0),
+            CursorShape::Underline => (
+                [(0.0, 1.0 - bar), (1.0, 1.0 - bar), (1.0, 1.0), (0.0, 1.0)],
+                1.0,
+            ),
+            // Translucent so the glyph underneath stays readable
+            CursorShape::Block | CursorShape::HollowBlock => {
+                ([(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)], 0.5)
+            }
+        };
+
+        let mut color = grid
+            .styles
+            .to_wgpu_color(grid.styles.default_text_color, self.surface_is_srgb);
+        color[3] = alpha;
+        let (width, height) = (self.size.width as f32, self.size.height as f32);
+        let vertices = animation.corners(offsets, now).map(|(x, y)| {
+            let x = x * self.cell_width;
+            let y = (self.top_rows as f32 + y - start_row as f32) * self.cell_height;
+            BgVertex {
+                position: [(x / width) * 2.0 - 1.0, 1.0 - (y / height) * 2.0],
+                color,
+            }
+        });
+        self.queue.write_buffer(
+            &self.cursor_vertex_buffer,
+            0,
+            bytemuck::cast_slice(&vertices),
+        );
+        self.cursor_index_count = 6;
+    }
+
+    /// Shape overlay text and upload the panel quads, returning each panel's pixel rect
+    fn prepare_overlays(&mut self, overlays: &[Overlay]) -> Vec<OverlayRect> {
+        let overlays = &overlays[..overlays.len().min(MAX_OVERLAYS)];
+        let metrics = Metrics::new(self.cell_height / 1.2, self.cell_height);
+        while self.overlay_buffers.len() < overlays.len() {
+            self.overlay_buffers
+                .push(Buffer::new(&mut self.font_system, metrics));
+        }
+
+        let mut rects = Vec::with_capacity(overlays.len());
+        let mut vertices = Vec::with_capacity(overlays.len() * 4);
+        let mut indices = Vec::with_capacity(overlays.len() * 6);
+
+        for (i, overlay) in overlays.iter().enumerate() {
+            let rect = self.overlay_rect(overlay);
+
+            let (r, g, b) = overlay.color;
+            let attrs = match &self.font_family {
+                Some(name) => Attrs::new()
+                    .family(Family::Name(name))
+                    .color(GlyphonColor::rgb(r, g, b)),
+                None => Attrs::new()
+                    .family(Family::Monospace)
+                    .color(GlyphonColor::rgb(r, g, b)),
+            };
+            let buffer = &mut self.overlay_buffers[i];
+            buffer.set_size(
+                &mut self.font_system,
+                Some(rect.width - OVERLAY_PADDING * 2.0),
+                Some(rect.height - rect.padding_y * 2.0),
+            );
+            buffer.set_text(
+                &mut self.font_system,
+                &overlay.text,
+                attrs,
+                Shaping::Advanced,
+            );
+            buffer.shape_until_scroll(&mut self.font_system, false);
+
+            let background = [
+                srgb_component(OVERLAY_BACKGROUND.0, self.surface_is_srgb),
+                srgb_component(OVERLAY_BACKGROUND.1, self.surface_is_srgb),
+                srgb_component(OVERLAY_BACKGROUND.2, self.surface_is_srgb),
+                OVERLAY_OPACITY,
+            ];
+            let base = vertices.len() as u32;
+            vertices.extend_from_slice(&quad_vertices(
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+                self.size.width as f32,
+                self.size.height as f32,
+                background,
+            ));
+            indices.extend_from_slice(&[base, base + 3, base + 2, base, base + 2, base + 1]);
+            rects.push(rect);
+        }
+
+        if !vertices.is_empty() {
+            self.queue.write_buffer(
+                &self.overlay_vertex_buffer,
+                0,
+                bytemuck::cast_slice(&vertices),
+            );
+            self.queue.write_buffer(
+                &self.overlay_index_buffer,
+                0,
+                bytemuck::cast_slice(&indices),
+            );
+        }
+        self.overlay_index_count = indices.len() as u32;
+
+        rects
+    }
+
+    /// Compute where an overlay panel goes on screen, sized to fit its text where possible
+    fn overlay_rect(&self, overlay: &Overlay) -> OverlayRect {
+        let width = self.size.width as f32;
+        let height = self.size.height as f32;
+        let text_cols = overlay
+            .text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let text_rows = overlay.text.lines().count().max(1);
+        let fit_width = (text_cols as f32 * self.cell_width + OVERLAY_PADDING * 2.0).min(width);
+        let fit_height = (text_rows as f32 * self.cell_height + OVERLAY_PADDING * 2.0).min(height);
+
+        match overlay.anchor {
+            OverlayAnchor::TopRight => OverlayRect {
+                x: (width - fit_width - OVERLAY_MARGIN).max(0.0),
+                y: OVERLAY_MARGIN,
+                width: fit_width,
+                height: fit_height,
+                padding_y: OVERLAY_PADDING,
+            },
+            OverlayAnchor::Bottom => OverlayRect {
+                x: 0.0,
+                y: (height - fit_height).max(0.0),
+                width,
+                height: fit_height,
+                padding_y: OVERLAY_PADDING,
+            },
+            OverlayAnchor::Fill => OverlayRect {
+                x: OVERLAY_MARGIN,
+                y: OVERLAY_MARGIN,
+                width: (width - OVERLAY_MARGIN * 2.0).max(0.0),
+                height: (height - OVERLAY_MARGIN * 2.0).max(0.0),
+                padding_y: OVERLAY_PADDING,
+            },
+            OverlayAnchor::StatusTop => OverlayRect {
+                x: 0.0,
+                y: 0.0,
+                width,
+                height: self.cell_height.min(height),
+                padding_y: 0.0,
+            },
+            OverlayAnchor::StatusBottom => OverlayRect {
+                x: 0.0,
+                y: (height - self.cell_height).max(0.0),
+                width,
+                height: self.cell_height.min(height),
+                padding_y: 0.0,
+            },
+        }
+    }
+
     /// Build render data incrementally, only updating dirty rows
     fn build_render_data_incremental(&mut self, grid: &Grid, dirty_rows: &[bool]) {
         let styles = &grid.styles;
@@ -686,9 +1316,10 @@ impl Renderer {
         let height = self.size.height as f32;
 
         // Get default background for comparison (skip rendering cells that match default)
-        let default_bg = color_to_rgba(styles.default_background_color, styles);
+        let default_bg =
+            styles.to_wgpu_color(styles.default_background_color, self.surface_is_srgb);
 
-        let start_row = grid.scroll_pos.saturating_sub(grid.height as usize - 1);
+        let start_row = grid.top_row();
         let active_cells = grid.active_grid_ref();
         let grid_len = active_cells.len();
         let num_visible_rows = grid.height as usize;
@@ -701,11 +1332,39 @@ impl Renderer {
             }
 
             let row_idx = start_row + display_row;
+            let on_cursor_line =
+                self.cursor_line_highlight && !grid.is_alternate() && row_idx == grid.cursor_pos.0;
 
             // Clear and rebuild this row's cached data
             self.cached_row_bg_vertices[display_row].clear();
             self.cached_row_text_spans[display_row].clear();
 
+            let row_start = (row_idx * grid.width as usize).min(grid_len);
+            let row_end = (row_start + grid.width as usize).min(grid_len);
+            let highlight_colors = self
+                .highlights
+                .row_colors(&active_cells[row_start..row_end]);
+            // Annotations belong to the shell's prompts, which only appear on the main screen
+            let annotation = self
+                .annotations
+                .iter()
+                .filter(|_| !grid.is_alternate())
+                .find(|annotation| annotation.row == row_idx)
+                .and_then(|annotation| {
+                    let cursor_col = (row_idx == grid.cursor_pos.0).then_some(grid.cursor_pos.1);
+                    let start = annotation.column(&active_cells[row_start..row_end], cursor_col)?;
+                    let (r, g, b) = if annotation.failed {
+                        ANNOTATION_FAILED_COLOR
+                    } else {
+                        ANNOTATION_COLOR
+                    };
+                    Some((
+                        start,
+                        annotation.text.chars().collect::<Vec<_>>(),
+                        GlyphonColor::rgb(r, g, b),
+                    ))
+                });
+
             // Batch consecutive characters with same color for this row
             let mut current_span = String::new();
             let mut current_color: Option<GlyphonColor> = None;
@@ -722,19 +1381,42 @@ impl Renderer {
 
                 // Get cell from the active grid
                 let cell = &active_cells[cell_index];
+                let hinted = self.hints.iter().find_map(|hint| {
+                    let offset = col_idx.checked_sub(hint.link.col)?;
+                    (hint.link.row == row_idx)
+                        .then(|| hint.label.chars().nth(offset))
+                        .flatten()
+                });
+                let selected = self
+                    .selection
+                    .is_some_and(|selection| selection.contains(row_idx, col_idx));
+                let (cell_fg, cell_bg) = if selected {
+                    (cell.bg, cell.fg)
+                } else {
+                    (cell.fg, cell.bg)
+                };
 
                 // Calculate cell position in pixels
                 let x = col_idx as f32 * self.cell_width;
-                let y = display_row as f32 * self.cell_height;
+                let y = (self.top_rows + display_row) as f32 * self.cell_height;
 
                 // Get background color
-                let bg_color = color_to_rgba(cell.bg, styles);
+                let bg_color = styles.to_wgpu_color(cell_bg, self.surface_is_srgb);
                 // Only render backgrounds that differ from the default (optimization)
                 let colors_differ = (bg_color[0] - default_bg[0]).abs() > 0.01
                     || (bg_color[1] - default_bg[1]).abs() > 0.01
                     || (bg_color[2] - default_bg[2]).abs() > 0.01;
+                // The cursor line shade only replaces the default background
+                let bg_color = if hinted.is_some() {
+                    let (r, g, b) = HINT_BACKGROUND;
+                    styles.to_wgpu_color(Color::Rgb(r, g, b), self.surface_is_srgb)
+                } else if on_cursor_line && !colors_differ {
+                    styles.to_wgpu_color(styles.cursor_line_color, self.surface_is_srgb)
+                } else {
+                    bg_color
+                };
 
-                if colors_differ {
+                if colors_differ || on_cursor_line || hinted.is_some() {
                     // Convert to normalized device coordinates (-1 to 1)
                     let x0 = (x / width) * 2.0 - 1.0;
                     let y0 = 1.0 - (y / height) * 2.0;
@@ -759,8 +1441,42 @@ impl Renderer {
                     });
                 }
 
-                // Build text content - handle cursor
-                let char_to_render = if row_idx == grid.cursor_pos.0 && col_idx == grid.cursor_pos.1
+                let predicted = self
+                    .predictions
+                    .iter()
+                    .find(|p| p.row == row_idx && p.col == col_idx)
+                    .map(|p| p.c);
+                if predicted.is_some() {
+                    // Underline predictions so they stand apart from confirmed output
+                    let thickness = (self.cell_height / 12.0).max(1.0);
+                    let fg = styles.to_wgpu_color(cell_fg, self.surface_is_srgb);
+                    self.cached_row_bg_vertices[display_row].extend(quad_vertices(
+                        x,
+                        y + self.cell_height - thickness,
+                        self.cell_width,
+                        thickness,
+                        width,
+                        height,
+                        fg,
+                    ));
+                }
+
+                let annotated = annotation.as_ref().and_then(|(start, text, color)| {
+                    let c = text.get(col_idx.checked_sub(*start)?)?;
+                    Some((*c, *color))
+                });
+
+                // Build text content - hints, annotations, predictions, then the cursor
+                let char_to_render = if let Some(c) = hinted {
+                    c
+                } else if let Some((c, _)) = annotated {
+                    c
+                } else if let Some(c) = predicted {
+                    c
+                } else if row_idx == grid.cursor_pos.0
+                    && col_idx == grid.cursor_pos.1
+                    && self.cursor_animation.is_none()
+                    && (self.blink_visible || !styles.cursor_state.blinking)
                 {
                     styles
                         .cursor_state
@@ -772,8 +1488,29 @@ impl Renderer {
                     cell.char
                 };
 
-                // Get foreground color for this cell
-                let fg_color = color_to_glyphon(cell.fg, styles);
+                // Get foreground color for this cell, unless a highlight rule recolors it
+                let highlight = highlight_colors.get(col_idx).copied().flatten();
+                let fg_color = match highlight.filter(|_| !selected) {
+                    Some((r, g, b)) => GlyphonColor::rgb(r, g, b),
+                    None => color_to_glyphon(cell_fg, styles),
+                };
+                let fg_color = match self.minimum_contrast {
+                    Some(ratio) => {
+                        let fg = (fg_color.r(), fg_color.g(), fg_color.b());
+                        let (r, g, b) = ensure_contrast(fg, styles.to_rgb(cell_bg), ratio);
+                        GlyphonColor::rgb(r, g, b)
+                    }
+                    None => fg_color,
+                };
+                let fg_color = self.font_rendering.apply_gamma(fg_color);
+                let fg_color = annotated.map_or(fg_color, |(_, color)| color);
+                let fg_color = match hinted {
+                    Some(_) => {
+                        let (r, g, b) = HINT_TEXT;
+                        GlyphonColor::rgb(r, g, b)
+                    }
+                    None => fg_color,
+                };
 
                 // Batch characters with same color
                 match current_color {
@@ -812,77 +1549,352 @@ fn colors_equal(a: GlyphonColor, b: GlyphonColor) -> bool {
     a.r() == b.r() && a.g() == b.g() && a.b() == b.b() && a.a() == b.a()
 }
 
-fn color_to_glyphon(color: Color, styles: &Styles) -> GlyphonColor {
-    let (r, g, b) = match color {
-        Color::Black => (0, 0, 0),
-        Color::Red => (205, 49, 49),
-        Color::Green => (13, 188, 121),
-        Color::Yellow => (229, 229, 16),
-        Color::Blue => (36, 114, 200),
-        Color::Magenta => (188, 63, 188),
-        Color::Cyan => (17, 168, 205),
-        Color::White => (229, 229, 229),
-        Color::Gray => (102, 102, 102),
-        Color::BrightRed => (241, 76, 76),
-        Color::BrightGreen => (35, 209, 139),
-        Color::BrightYellow => (245, 245, 67),
-        Color::BrightBlue => (59, 142, 234),
-        Color::BrightMagenta => (214, 112, 214),
-        Color::BrightCyan => (41, 184, 219),
-        Color::BrightWhite => (255, 255, 255),
-        Color::Rgb(r, g, b) => (r, g, b),
-        Color::Foreground => {
-            return color_to_glyphon(styles.default_text_color, styles);
-        }
-        Color::Background => {
-            return color_to_glyphon(styles.default_background_color, styles);
-        }
-        Color::ColorIndex(i) => {
-            return color_to_glyphon(styles.color_array[i as usize], styles);
-        }
-    };
-    GlyphonColor::rgb(r, g, b)
+/// Minimap blocks the buffers first have room for; they grow as needed
+const MINIMAP_INITIAL_BLOCKS: usize = 256;
+/// Opacity of column rulers over the background
+const RULER_OPACITY: f32 = 0.15;
+/// Command annotation text (sRGB), and for commands that failed
+const ANNOTATION_COLOR: (u8, u8, u8) = (110, 110, 110);
+const ANNOTATION_FAILED_COLOR: (u8, u8, u8) = (190, 80, 80);
+/// Link hint label background and text (sRGB)
+const HINT_BACKGROUND: (u8, u8, u8) = (245, 200, 66);
+const HINT_TEXT: (u8, u8, u8) = (20, 20, 20);
+/// Maximum number of overlay panels drawn in one frame
+const MAX_OVERLAYS: usize = 8;
+/// Space between an overlay panel's edge and its text, in pixels
+const OVERLAY_PADDING: f32 = 6.0;
+/// Space between an overlay panel and the window edge, in pixels
+const OVERLAY_MARGIN: f32 = 8.0;
+/// Overlay panel background (sRGB) and opacity
+const OVERLAY_BACKGROUND: (u8, u8, u8) = (20, 20, 20);
+const OVERLAY_OPACITY: f32 = 0.85;
+
+/// Where an overlay panel is placed in the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayAnchor {
+    /// Sized to its text in the top-right corner
+    TopRight,
+    /// Full width, sized to its text along the bottom edge
+    Bottom,
+    /// Covers the window apart from a small margin
+    Fill,
+    /// A single full-width row along the top edge (status bar)
+    StatusTop,
+    /// A single full-width row along the bottom edge (status bar)
+    StatusBottom,
 }
 
-/// Convert sRGB component (0-255) to linear color space for GPU rendering
-fn srgb_to_linear(srgb: u8) -> f32 {
-    let s = srgb as f32 / 255.0;
-    if s <= 0.04045 {
-        s / 12.92
+/// Approximate memory held by the renderer, in bytes unless noted
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RendererMemory {
+    /// Shaped and laid out glyph runs kept by the text buffers
+    pub shaping: usize,
+    /// Distinct glyphs currently laid out
+    pub glyphs: usize,
+    /// Estimated glyph atlas occupancy (glyphon does not expose the atlas size)
+    pub glyph_atlas: usize,
+    /// Per-row background quads and text spans kept for incremental updates
+    pub row_caches: usize,
+    /// Vertex, index and uniform buffers plus offscreen frames on the GPU
+    pub gpu_buffers: usize,
+}
+
+/// A translucent text panel drawn above the terminal grid
+#[derive(Debug, Clone)]
+pub struct Overlay {
+    pub text: String,
+    pub anchor: OverlayAnchor,
+    /// Text color (sRGB)
+    pub color: (u8, u8, u8),
+}
+
+/// Pixel rectangle of a laid out overlay panel
+struct OverlayRect {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    /// Space between the panel's top/bottom edges and its text
+    padding_y: f32,
+}
+
+/// Vertices (top-left, top-right, bottom-right, bottom-left) for a pixel rect in NDC
+/// Vertex and index buffers with room for `quads` quads
+fn create_quad_buffers(device: &Device, label: &str, quads: usize) -> (WgpuBuffer, WgpuBuffer) {
+    let vertices = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(&format!("{} Vertex Buffer", label)),
+        size: (quads * 4 * std::mem::size_of::<BgVertex>()) as u64,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let indices = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(&format!("{} Index Buffer", label)),
+        size: (quads * 6 * std::mem::size_of::<u32>()) as u64,
+        usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    (vertices, indices)
+}
+
+/// Vertical guide quads after each ruler column that fits in the grid, one pixel wide
+fn ruler_quads(
+    columns: &[u16],
+    (cols, rows): (u16, u16),
+    (cell_width, cell_height): (f32, f32),
+    top_rows: usize,
+    (screen_width, screen_height): (f32, f32),
+    color: [f32; 4],
+) -> Vec<[BgVertex; 4]> {
+    let top = top_rows as f32 * cell_height;
+    columns
+        .iter()
+        .filter(|&&column| column > 0 && column < cols)
+        .map(|&column| {
+            quad_vertices(
+                (column as f32 * cell_width).floor(),
+                top,
+                1.0,
+                rows as f32 * cell_height,
+                screen_width,
+                screen_height,
+                color,
+            )
+        })
+        .collect()
+}
+
+fn quad_vertices(
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+    screen_width: f32,
+    screen_height: f32,
+    color: [f32; 4],
+) -> [BgVertex; 4] {
+    let x0 = (x / screen_width) * 2.0 - 1.0;
+    let y0 = 1.0 - (y / screen_height) * 2.0;
+    let x1 = ((x + w) / screen_width) * 2.0 - 1.0;
+    let y1 = 1.0 - ((y + h) / screen_height) * 2.0;
+    [
+        BgVertex {
+            position: [x0, y0],
+            color,
+        },
+        BgVertex {
+            position: [x1, y0],
+            color,
+        },
+        BgVertex {
+            position: [x1, y1],
+            color,
+        },
+        BgVertex {
+            position: [x0, y1],
+            color,
+        },
+    ]
+}
+
+/// Convert an sRGB component for the surface, linearizing for sRGB targets
+fn srgb_component(c: u8, srgb_target: bool) -> f32 {
+    if srgb_target {
+        srgb_to_linear(c)
     } else {
-        ((s + 0.055) / 1.055).powf(2.4)
+        c as f32 / 255.0
     }
 }
 
-fn color_to_rgba(color: Color, styles: &Styles) -> [f32; 4] {
-    let (r, g, b) = match color {
-        Color::Black => (0, 0, 0),
-        Color::Red => (205, 49, 49),
-        Color::Green => (13, 188, 121),
-        Color::Yellow => (229, 229, 16),
-        Color::Blue => (36, 114, 200),
-        Color::Magenta => (188, 63, 188),
-        Color::Cyan => (17, 168, 205),
-        Color::White => (229, 229, 229),
-        Color::Gray => (102, 102, 102),
-        Color::BrightRed => (241, 76, 76),
-        Color::BrightGreen => (35, 209, 139),
-        Color::BrightYellow => (245, 245, 67),
-        Color::BrightBlue => (59, 142, 234),
-        Color::BrightMagenta => (214, 112, 214),
-        Color::BrightCyan => (41, 184, 219),
-        Color::BrightWhite => (255, 255, 255),
-        Color::Rgb(r, g, b) => (r, g, b),
-        Color::Foreground => {
-            return color_to_rgba(styles.default_text_color, styles);
-        }
-        Color::Background => {
-            return color_to_rgba(styles.default_background_color, styles);
-        }
-        Color::ColorIndex(i) => {
-            return color_to_rgba(styles.color_array[i as usize], styles);
-        }
+/// Measure the advance of a monospace cell by shaping a single character
+fn measure_cell_width(
+    font_system: &mut FontSystem,
+    font_family: Option<&str>,
+    font_size: f32,
+) -> f32 {
+    let mut measure_buffer = Buffer::new(font_system, Metrics::new(font_size, font_size * 1.2));
+    let font_attrs = match font_family {
+        Some(name) => Attrs::new().family(Family::Name(name)),
+        None => Attrs::new().family(Family::Monospace),
     };
-    // Convert sRGB to linear for the sRGB surface format (GPU will convert back to sRGB on output)
-    [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), 1.0]
+    measure_buffer.set_text(font_system, "M", font_attrs, Shaping::Advanced);
+    measure_buffer.shape_until_scroll(font_system, false);
+
+    measure_buffer
+        .layout_runs()
+        .next()
+        .and_then(|run| run.glyphs.first())
+        .map(|g| g.w)
+        .unwrap_or(font_size * 0.6)
+}
+
+/// Offscreen texture the frame is drawn into before a post-processing pass reads it
+fn create_frame_target(
+    device: &Device,
+    format: TextureFormat,
+    width: u32,
+    height: u32,
+) -> TextureView {
+    device
+        .create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Frame"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        })
+        .create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+/// Bindings of a post-processing pass: the offscreen frame, its sampler and a uniform buffer
+fn create_frame_bind_group_layout(device: &Device) -> BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Offscreen Frame Bind Group Layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ],
+    })
+}
+
+fn create_frame_bind_group(
+    device: &Device,
+    layout: &BindGroupLayout,
+    target: &TextureView,
+    sampler: &Sampler,
+    uniform_buffer: &WgpuBuffer,
+) -> BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Offscreen Frame Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(target),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+        ],
+    })
+}
+
+/// Run a post-processing pipeline over the whole of `view`
+fn draw_fullscreen(
+    encoder: &mut CommandEncoder,
+    view: &TextureView,
+    pipeline: &RenderPipeline,
+    bind_group: &BindGroup,
+) {
+    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Post-processing Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    render_pass.set_pipeline(pipeline);
+    render_pass.set_bind_group(0, bind_group, &[]);
+    render_pass.draw(0..3, 0..1);
+}
+
+/// Create the pipeline that draws solid-colored cell background quads
+fn create_bg_pipeline(device: &Device, format: wgpu::TextureFormat) -> RenderPipeline {
+    let bg_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Background Shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("shaders/bg.wgsl").into()),
+    });
+
+    let bg_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Background Pipeline Layout"),
+        bind_group_layouts: &[],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Background Pipeline"),
+        layout: Some(&bg_pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &bg_shader,
+            entry_point: Some("vs_main"),
+            buffers: &[BgVertex::desc()],
+            compilation_options: PipelineCompilationOptions::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &bg_shader,
+            entry_point: Some("fs_main"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                // Cell backgrounds are opaque; overlay panels rely on alpha blending
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: PipelineCompilationOptions::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: Some(wgpu::Face::Back),
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+        cache: None,
+    })
+}
+
+fn color_to_glyphon(color: Color, styles: &Styles) -> GlyphonColor {
+    // Glyph colors stay in sRGB; glyphon linearizes them itself for sRGB targets
+    let (r, g, b) = styles.to_rgb(color);
+    GlyphonColor::rgb(r, g, b)
 }
@@ -0,0 +1,138 @@
+use wgpu::{
+    BindGroup, BindGroupLayout, Buffer as WgpuBuffer, CommandEncoder, Device, Queue,
+    RenderPipeline, Sampler, TextureFormat, TextureView,
+};
+
+use super::{
+    create_frame_bind_group, create_frame_bind_group_layout, create_frame_target, draw_fullscreen,
+};
+use crate::{accessibility::color_filter_matrix, config::ColorFilter};
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct FilterUniform {
+    matrix: [[f32; 4]; 3],
+    flags: [u32; 4],
+}
+
+/// Renders the frame into an offscreen texture, then copies it to the surface through a
+/// color matrix (see `shaders/color_filter.wgsl`)
+pub struct ColorFilterPass {
+    pipeline: RenderPipeline,
+    bind_group_layout: BindGroupLayout,
+    bind_group: BindGroup,
+    sampler: Sampler,
+    uniform_buffer: WgpuBuffer,
+    target: TextureView,
+    format: TextureFormat,
+}
+
+impl ColorFilterPass {
+    pub fn new(device: &Device, format: TextureFormat, width: u32, height: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Color Filter Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/color_filter.wgsl").into()),
+        });
+
+        let bind_group_layout = create_frame_bind_group_layout(device);
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Color Filter Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Color Filter Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        // The offscreen frame matches the surface pixel for pixel
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Color Filter Sampler"),
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Color Filter Uniform Buffer"),
+            size: std::mem::size_of::<FilterUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let target = create_frame_target(device, format, width, height);
+        let bind_group = create_frame_bind_group(
+            device,
+            &bind_group_layout,
+            &target,
+            &sampler,
+            &uniform_buffer,
+        );
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            bind_group,
+            sampler,
+            uniform_buffer,
+            target,
+            format,
+        }
+    }
+
+    /// Upload the matrix for `filter`. `srgb_surface` tells the shader whether the frame
+    /// already holds linear values (sRGB surfaces) or has to be decoded first.
+    pub fn set_filter(&self, queue: &Queue, filter: ColorFilter, srgb_surface: bool) {
+        let rows = color_filter_matrix(filter);
+        let uniform = FilterUniform {
+            matrix: rows.map(|[r, g, b]| [r, g, b, 0.0]),
+            flags: [u32::from(!srgb_surface), 0, 0, 0],
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniform));
+    }
+
+    /// Recreate the offscreen frame for a new surface size
+    pub fn resize(&mut self, device: &Device, width: u32, height: u32) {
+        self.target = create_frame_target(device, self.format, width, height);
+        self.bind_group = create_frame_bind_group(
+            device,
+            &self.bind_group_layout,
+            &self.target,
+            &self.sampler,
+            &self.uniform_buffer,
+        );
+    }
+
+    /// Where the frame should be drawn before filtering
+    pub fn target(&self) -> &TextureView {
+        &self.target
+    }
+
+    /// Draw the filtered frame onto `view`
+    pub fn draw(&self, encoder: &mut CommandEncoder, view: &TextureView) {
+        draw_fullscreen(encoder, view, &self.pipeline, &self.bind_group);
+    }
+}
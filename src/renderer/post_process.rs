@@ -0,0 +1,235 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
+
+use wgpu::{
+    BindGroup, BindGroupLayout, Buffer as WgpuBuffer, CommandEncoder, Device, Queue,
+    RenderPipeline, Sampler, TextureFormat, TextureView,
+};
+
+use super::{
+    create_frame_bind_group, create_frame_bind_group_layout, create_frame_target, draw_fullscreen,
+};
+use crate::config::ShaderPreset;
+
+/// Declarations every post-processing shader is appended to
+const PRELUDE: &str = include_str!("../shaders/post_process.wgsl");
+/// How often a shader file is checked for changes
+const RELOAD_INTERVAL: Duration = Duration::from_millis(500);
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct Params {
+    resolution: [f32; 2],
+    cell_size: [f32; 2],
+}
+
+/// Where the fragment shader comes from
+#[derive(Debug, Clone)]
+pub enum ShaderSource {
+    Preset(ShaderPreset),
+    /// A WGSL file, reloaded when it changes
+    File(PathBuf),
+}
+
+impl ShaderSource {
+    fn read(&self) -> Result<String, String> {
+        match self {
+            ShaderSource::Preset(ShaderPreset::Crt) => {
+                Ok(include_str!("../shaders/presets/crt.wgsl").to_string())
+            }
+            ShaderSource::Preset(ShaderPreset::Scanlines) => {
+                Ok(include_str!("../shaders/presets/scanlines.wgsl").to_string())
+            }
+            ShaderSource::Preset(ShaderPreset::Bloom) => {
+                Ok(include_str!("../shaders/presets/bloom.wgsl").to_string())
+            }
+            ShaderSource::File(path) => fs::read_to_string(path)
+                .map_err(|e| format!("failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        match self {
+            ShaderSource::Preset(_) => None,
+            ShaderSource::File(path) => fs::metadata(path).and_then(|m| m.modified()).ok(),
+        }
+    }
+}
+
+/// Runs a user-supplied or built-in fragment shader over the finished frame
+pub struct PostProcessPass {
+    source: ShaderSource,
+    modified: Option<SystemTime>,
+    checked_at: Instant,
+    /// None until the shader has compiled; the pass is skipped until then
+    pipeline: Option<RenderPipeline>,
+    bind_group_layout: BindGroupLayout,
+    bind_group: BindGroup,
+    sampler: Sampler,
+    uniform_buffer: WgpuBuffer,
+    target: TextureView,
+    format: TextureFormat,
+}
+
+impl PostProcessPass {
+    pub fn new(
+        device: &Device,
+        format: TextureFormat,
+        width: u32,
+        height: u32,
+        source: ShaderSource,
+    ) -> Self {
+        let bind_group_layout = create_frame_bind_group_layout(device);
+
+        // Filtered so effects can sample between pixels (e.g. curvature)
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Post-processing Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Post-processing Uniform Buffer"),
+            size: std::mem::size_of::<Params>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let target = create_frame_target(device, format, width, height);
+        let bind_group = create_frame_bind_group(
+            device,
+            &bind_group_layout,
+            &target,
+            &sampler,
+            &uniform_buffer,
+        );
+
+        let mut pass = Self {
+            modified: source.modified(),
+            source,
+            checked_at: Instant::now(),
+            pipeline: None,
+            bind_group_layout,
+            bind_group,
+            sampler,
+            uniform_buffer,
+            target,
+            format,
+        };
+        pass.compile(device);
+        pass
+    }
+
+    /// Recompile a shader file that changed on disk, returning true when it was reloaded
+    pub fn reload_if_changed(&mut self, device: &Device) -> bool {
+        let now = Instant::now();
+        if now.duration_since(self.checked_at) < RELOAD_INTERVAL {
+            return false;
+        }
+        self.checked_at = now;
+        let modified = self.source.modified();
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        self.compile(device);
+        true
+    }
+
+    /// Build the pipeline, keeping the previous one when the shader fails to compile
+    fn compile(&mut self, device: &Device) {
+        let shader = match self.source.read() {
+            Ok(shader) => shader,
+            Err(e) => {
+                log::error!("Post-processing shader: {}", e);
+                return;
+            }
+        };
+
+        // Validation errors would otherwise be fatal, so catch them while tinkering
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Post-processing Shader"),
+            source: wgpu::ShaderSource::Wgsl(format!("{}\n{}", PRELUDE, shader).into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Post-processing Pipeline Layout"),
+            bind_group_layouts: &[&self.bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Post-processing Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: self.format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        match pollster::block_on(device.pop_error_scope()) {
+            Some(error) => log::error!("Post-processing shader failed to compile: {}", error),
+            None => {
+                log::info!("Loaded post-processing shader {:?}", self.source);
+                self.pipeline = Some(pipeline);
+            }
+        }
+    }
+
+    /// Upload the frame and cell sizes the shader can read from `params`
+    pub fn set_params(&self, queue: &Queue, resolution: (f32, f32), cell_size: (f32, f32)) {
+        let params = Params {
+            resolution: [resolution.0, resolution.1],
+            cell_size: [cell_size.0, cell_size.1],
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&params));
+    }
+
+    /// Recreate the offscreen frame for a new surface size
+    pub fn resize(&mut self, device: &Device, width: u32, height: u32) {
+        self.target = create_frame_target(device, self.format, width, height);
+        self.bind_group = create_frame_bind_group(
+            device,
+            &self.bind_group_layout,
+            &self.target,
+            &self.sampler,
+            &self.uniform_buffer,
+        );
+    }
+
+    /// Whether a shader has compiled and the pass should run
+    pub fn is_active(&self) -> bool {
+        self.pipeline.is_some()
+    }
+
+    /// Where the frame should be drawn before post-processing
+    pub fn target(&self) -> &TextureView {
+        &self.target
+    }
+
+    /// Draw the processed frame onto `view`
+    pub fn draw(&self, encoder: &mut CommandEncoder, view: &TextureView) {
+        if let Some(pipeline) = &self.pipeline {
+            draw_fullscreen(encoder, view, pipeline, &self.bind_group);
+        }
+    }
+}
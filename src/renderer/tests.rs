@@ -0,0 +1,269 @@
+use wgpu::util::DeviceExt;
+
+use crate::{
+    config::ShaderPreset,
+    renderer::{
+        create_bg_pipeline,
+        post_process::{PostProcessPass, ShaderSource},
+        ruler_quads, BgVertex,
+    },
+    styles::{Color, Styles},
+};
+
+const SIZE: u32 = 4;
+/// Rows copied out of a texture must be aligned to 256 bytes
+const PADDED_ROW_BYTES: u32 = 256;
+
+/// Create a device without a surface, or None when no adapter is available (e.g. headless CI)
+fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::LowPower,
+        compatible_surface: None,
+        force_fallback_adapter: false,
+    }))?;
+    pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None)).ok()
+}
+
+/// Clear a small texture to `clear`, draw a full-target background quad with `quad`, and return
+/// the resulting (clear-only, quad) pixels read back from the top-left corner
+fn render_pixel(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    format: wgpu::TextureFormat,
+    clear: [f32; 4],
+    quad: Option<[f32; 4]>,
+) -> [u8; 4] {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Test Target"),
+        size: wgpu::Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let pipeline = create_bg_pipeline(device, format);
+
+    let color = quad.unwrap_or(clear);
+    let vertices = [
+        BgVertex {
+            position: [-1.0, 1.0],
+            color,
+        },
+        BgVertex {
+            position: [1.0, 1.0],
+            color,
+        },
+        BgVertex {
+            position: [1.0, -1.0],
+            color,
+        },
+        BgVertex {
+            position: [-1.0, -1.0],
+            color,
+        },
+    ];
+    let indices: [u32; 6] = [0, 3, 2, 0, 2, 1];
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Test Vertices"),
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Test Indices"),
+        contents: bytemuck::cast_slice(&indices),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Test Readback"),
+        size: (PADDED_ROW_BYTES * SIZE) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder =
+        device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color {
+                        r: clear[0] as f64,
+                        g: clear[1] as f64,
+                        b: clear[2] as f64,
+                        a: clear[3] as f64,
+                    }),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        if quad.is_some() {
+            render_pass.set_pipeline(&pipeline);
+            render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+            render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.draw_indexed(0..6, 0, 0..1);
+        }
+    }
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &readback,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(PADDED_ROW_BYTES),
+                rows_per_image: Some(SIZE),
+            },
+        },
+        wgpu::Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |result| result.expect("map failed"));
+    device.poll(wgpu::Maintain::Wait);
+    let data = slice.get_mapped_range();
+    [data[0], data[1], data[2], data[3]]
+}
+
+fn assert_pixel_close(actual: [u8; 4], expected: (u8, u8, u8)) {
+    let close = |a: u8, b: u8| (a as i16 - b as i16).abs() <= 1;
+    assert!(
+        close(actual[0], expected.0)
+            && close(actual[1], expected.1)
+            && close(actual[2], expected.2),
+        "rendered {:?}, expected {:?}",
+        actual,
+        expected
+    );
+}
+
+const SAMPLE_COLORS: [Color; 5] = [
+    Color::Red,
+    Color::BrightCyan,
+    Color::Gray,
+    Color::ColorIndex(137),
+    Color::Rgb(12, 200, 77),
+];
+
+#[test]
+fn background_quads_should_render_palette_values_on_srgb_target() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("No GPU adapter available, skipping");
+        return;
+    };
+    let styles = Styles::default();
+
+    for color in SAMPLE_COLORS {
+        let pixel = render_pixel(
+            &device,
+            &queue,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            [0.0, 0.0, 0.0, 1.0],
+            Some(styles.to_wgpu_color(color, true)),
+        );
+        assert_pixel_close(pixel, styles.to_rgb(color));
+    }
+}
+
+#[test]
+fn background_quads_should_render_palette_values_on_linear_target() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("No GPU adapter available, skipping");
+        return;
+    };
+    let styles = Styles::default();
+
+    for color in SAMPLE_COLORS {
+        let pixel = render_pixel(
+            &device,
+            &queue,
+            wgpu::TextureFormat::Rgba8Unorm,
+            [0.0, 0.0, 0.0, 1.0],
+            Some(styles.to_wgpu_color(color, false)),
+        );
+        assert_pixel_close(pixel, styles.to_rgb(color));
+    }
+}
+
+#[test]
+fn clear_color_should_render_default_background_on_srgb_target() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("No GPU adapter available, skipping");
+        return;
+    };
+    let mut styles = Styles::default();
+    styles.default_background_color = Color::Rgb(40, 44, 52);
+
+    let pixel = render_pixel(
+        &device,
+        &queue,
+        wgpu::TextureFormat::Rgba8UnormSrgb,
+        styles.to_wgpu_color(Color::Background, true),
+        None,
+    );
+
+    assert_pixel_close(pixel, (40, 44, 52));
+}
+
+#[test]
+fn shader_presets_should_compile() {
+    let Some((device, _queue)) = headless_device() else {
+        eprintln!("No GPU adapter available, skipping");
+        return;
+    };
+
+    for preset in [
+        ShaderPreset::Crt,
+        ShaderPreset::Scanlines,
+        ShaderPreset::Bloom,
+    ] {
+        let pass = PostProcessPass::new(
+            &device,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            SIZE,
+            SIZE,
+            ShaderSource::Preset(preset),
+        );
+        assert!(pass.is_active(), "{:?}", preset);
+    }
+}
+
+#[test]
+fn rulers_should_be_drawn_after_columns_inside_the_grid() {
+    let quads = ruler_quads(
+        &[0, 4, 80],
+        (10, 5),
+        (16.0, 32.0),
+        1,
+        (128.0, 256.0),
+        [1.0; 4],
+    );
+
+    assert_eq!(quads.len(), 1);
+    let [top_left, _, bottom_right, _] = quads[0];
+    // x = 64px, from below the status row (32px) to the end of the last grid row (192px)
+    assert_eq!(top_left.position, [0.0, 0.75]);
+    assert_eq!(bottom_right.position, [0.015625, -0.5]);
+}
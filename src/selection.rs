@@ -0,0 +1,122 @@
+use std::time::{Duration, Instant};
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::grid::Grid;
+
+#[cfg(test)]
+mod tests;
+
+/// Rows per second scrolled while dragging just past the edge; each further row's height of
+/// distance adds as much again
+const AUTO_SCROLL_RATE: f32 = 10.0;
+
+/// Longest step taken at once, so a stalled frame doesn't jump far through the scrollback
+const MAX_AUTO_SCROLL_STEP: Duration = Duration::from_millis(100);
+
+/// Text picked by dragging with the mouse.
+///
+/// Points are absolute buffer rows and the column boundaries between cells (0 is before the
+/// first cell, the grid width after the last), so a click without moving selects nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+    anchor: (usize, usize),
+    end: (usize, usize),
+}
+
+impl Selection {
+    /// Start a selection where the mouse button went down
+    pub fn new(point: (usize, usize)) -> Self {
+        Self {
+            anchor: point,
+            end: point,
+        }
+    }
+
+    /// Move the free end to where the mouse is now
+    pub fn extend(&mut self, point: (usize, usize)) {
+        self.end = point;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.anchor == self.end
+    }
+
+    /// Start and end in reading order
+    pub fn range(&self) -> ((usize, usize), (usize, usize)) {
+        if self.anchor <= self.end {
+            (self.anchor, self.end)
+        } else {
+            (self.end, self.anchor)
+        }
+    }
+
+    /// Absolute rows the selection touches
+    pub fn rows(&self) -> std::ops::RangeInclusive<usize> {
+        let (start, end) = self.range();
+        start.0..=end.0
+    }
+
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        let (start, end) = self.range();
+        (start.0, start.1) <= (row, col) && (row, col) < (end.0, end.1)
+    }
+
+    /// The selected text, one line per row without trailing blanks
+    pub fn text(&self, grid: &Grid) -> String {
+        let width = grid.width as usize;
+        let (start, end) = self.range();
+        let mut lines = Vec::new();
+        for (row, cells) in grid
+            .active_grid_ref()
+            .chunks(width)
+            .enumerate()
+            .take(end.0 + 1)
+            .skip(start.0)
+        {
+            let first = if row == start.0 { start.1 } else { 0 };
+            let last = if row == end.0 { end.1 } else { width };
+            let mut line = String::new();
+            let mut after_wide = false;
+            for cell in cells.iter().take(last).skip(first) {
+                // Wide characters are followed by a padding cell
+                if !after_wide {
+                    line.push(cell.char);
+                }
+                after_wide = cell.char.width() == Some(2);
+            }
+            lines.push(line.trim_end().to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+/// Scrolls the view while a selection is dragged past the top or bottom of the grid,
+/// faster the further away the mouse is
+#[derive(Debug, Default)]
+pub struct AutoScroll {
+    last: Option<Instant>,
+    remainder: f32,
+}
+
+impl AutoScroll {
+    /// Rows to scroll the view by (negative is up, into the scrollback), given how many rows
+    /// the mouse is past the top (negative) or bottom (positive) edge, or `None` inside
+    pub fn update(&mut self, overshoot: Option<f32>, now: Instant) -> isize {
+        let Some(overshoot) = overshoot else {
+            *self = Self::default();
+            return 0;
+        };
+        let elapsed = self
+            .last
+            .map_or(Duration::ZERO, |last| now.duration_since(last))
+            .min(MAX_AUTO_SCROLL_STEP);
+        self.last = Some(now);
+
+        let speed = AUTO_SCROLL_RATE * (1.0 + overshoot.abs()) * overshoot.signum();
+        self.remainder += speed * elapsed.as_secs_f32();
+        let rows = self.remainder.trunc();
+        self.remainder -= rows;
+        rows as isize
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::{Duration, Instant};
+
+use crate::{config::Config, terminal::HeadlessPipeline};
+
+use super::{AutoScroll, Selection};
+
+fn pipeline(output: &str) -> HeadlessPipeline {
+    let mut pipeline = HeadlessPipeline::new(&Config {
+        cols: 10,
+        rows: 4,
+        ..Config::default()
+    });
+    pipeline.feed(output.as_bytes());
+    pipeline
+}
+
+#[test]
+fn selection_should_be_ordered_whichever_way_it_was_dragged() {
+    let mut selection = Selection::new((2, 5));
+    selection.extend((0, 3));
+
+    assert_eq!(selection.range(), ((0, 3), (2, 5)));
+    assert!(selection.contains(1, 9));
+    assert!(selection.contains(2, 4));
+    assert!(!selection.contains(2, 5));
+    assert!(!selection.contains(0, 2));
+}
+
+#[test]
+fn click_without_drag_should_select_nothing() {
+    let selection = Selection::new((1, 1));
+
+    assert!(selection.is_empty());
+    assert!(!selection.contains(1, 1));
+}
+
+#[test]
+fn text_should_join_rows_and_trim_trailing_blanks() {
+    let pipeline = pipeline("hello\r\nworld\r\nagain");
+    let mut selection = Selection::new((0, 1));
+    selection.extend((2, 3));
+
+    assert_eq!(selection.text(&pipeline.terminal.grid), "ello\nworld\naga");
+}
+
+#[test]
+fn text_should_skip_wide_character_padding() {
+    let pipeline = pipeline("a\u{4e2d}b");
+    let mut selection = Selection::new((0, 0));
+    selection.extend((0, 10));
+
+    assert_eq!(selection.text(&pipeline.terminal.grid), "a\u{4e2d}b");
+}
+
+#[test]
+fn auto_scroll_should_speed_up_with_distance() {
+    let start = Instant::now();
+    let scrolled = |overshoot: f32| {
+        let mut auto_scroll = AutoScroll::default();
+        auto_scroll.update(Some(overshoot), start);
+        auto_scroll.update(Some(overshoot), start + Duration::from_millis(100))
+    };
+
+    assert_eq!(scrolled(0.0), 1);
+    assert_eq!(scrolled(3.0), 4);
+    assert_eq!(scrolled(-3.0), -4);
+}
+
+#[test]
+fn auto_scroll_should_carry_fractional_rows() {
+    let start = Instant::now();
+    let mut auto_scroll = AutoScroll::default();
+    auto_scroll.update(Some(0.0), start);
+
+    let rows: isize = (1..=10)
+        .map(|tick| auto_scroll.update(Some(0.0), start + Duration::from_millis(10 * tick)))
+        .sum();
+
+    assert_eq!(rows, 1);
+}
+
+#[test]
+fn auto_scroll_should_stop_inside_the_grid() {
+    let start = Instant::now();
+    let mut auto_scroll = AutoScroll::default();
+    auto_scroll.update(Some(2.0), start);
+
+    assert_eq!(auto_scroll.update(None, start + Duration::from_secs(1)), 0);
+    assert_eq!(
+        auto_scroll.update(Some(2.0), start + Duration::from_secs(2)),
+        0
+    );
+}
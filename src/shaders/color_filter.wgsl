@@ -0,0 +1,48 @@
+struct Params {
+    // Rows of the linear-RGB color matrix, stored as columns so `rgb * matrix` applies it
+    matrix: mat3x3<f32>,
+    // x: non-zero when the frame holds sRGB-encoded values rather than linear ones
+    flags: vec4<u32>,
+}
+
+@group(0) @binding(0) var frame: texture_2d<f32>;
+@group(0) @binding(1) var frame_sampler: sampler;
+@group(0) @binding(2) var<uniform> params: Params;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+// One triangle covering the whole screen
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+fn to_linear(c: vec3<f32>) -> vec3<f32> {
+    return select(pow((c + 0.055) / 1.055, vec3<f32>(2.4)), c / 12.92, c <= vec3<f32>(0.04045));
+}
+
+fn to_srgb(c: vec3<f32>) -> vec3<f32> {
+    return select(1.055 * pow(c, vec3<f32>(1.0 / 2.4)) - 0.055, c * 12.92, c <= vec3<f32>(0.0031308));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(frame, frame_sampler, in.uv);
+    let encoded = params.flags.x != 0u;
+    var rgb = color.rgb;
+    if encoded {
+        rgb = to_linear(rgb);
+    }
+    rgb = clamp(rgb * params.matrix, vec3<f32>(0.0), vec3<f32>(1.0));
+    if encoded {
+        rgb = to_srgb(rgb);
+    }
+    return vec4<f32>(rgb, color.a);
+}
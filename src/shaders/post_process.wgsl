@@ -0,0 +1,31 @@
+// Declarations shared by post-processing shaders, which are appended to this file.
+//
+// A shader defines `@fragment fn fs_main(in: VertexOutput) -> @location(0) vec4<f32>` and
+// samples the rendered frame with `textureSample(frame, frame_sampler, uv)`. `in.uv` runs
+// from (0, 0) at the top-left to (1, 1) at the bottom-right; `in.position.xy` is in pixels.
+
+struct Params {
+    // Frame size in pixels
+    resolution: vec2<f32>,
+    // Terminal cell size in pixels
+    cell_size: vec2<f32>,
+}
+
+@group(0) @binding(0) var frame: texture_2d<f32>;
+@group(0) @binding(1) var frame_sampler: sampler;
+@group(0) @binding(2) var<uniform> params: Params;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+// One triangle covering the whole screen
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
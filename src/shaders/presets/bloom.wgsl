@@ -0,0 +1,23 @@
+// Soft glow around bright text
+
+const THRESHOLD: f32 = 0.5;
+const STRENGTH: f32 = 0.6;
+// Pixels between taps
+const SPREAD: f32 = 2.0;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(frame, frame_sampler, in.uv);
+    let texel = SPREAD / params.resolution;
+
+    var glow = vec3<f32>(0.0);
+    for (var x = -2; x <= 2; x++) {
+        for (var y = -2; y <= 2; y++) {
+            let offset = vec2<f32>(f32(x), f32(y)) * texel;
+            let tap = textureSample(frame, frame_sampler, in.uv + offset).rgb;
+            glow += max(tap - vec3<f32>(THRESHOLD), vec3<f32>(0.0));
+        }
+    }
+    glow /= 25.0;
+    return vec4<f32>(min(color.rgb + glow * STRENGTH, vec3<f32>(1.0)), color.a);
+}
@@ -0,0 +1,20 @@
+// Curved CRT screen with scanlines and darkened corners
+
+const CURVATURE: f32 = 0.06;
+const SCANLINE_DEPTH: f32 = 0.15;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Barrel distortion: push points outwards the further they are from the center
+    let centered = in.uv * 2.0 - 1.0;
+    let warped = centered * (1.0 + CURVATURE * centered.yx * centered.yx);
+    let uv = warped * 0.5 + 0.5;
+
+    let color = textureSample(frame, frame_sampler, clamp(uv, vec2<f32>(0.0), vec2<f32>(1.0)));
+    let inside = all(uv >= vec2<f32>(0.0)) && all(uv <= vec2<f32>(1.0));
+
+    let scanline = 1.0 - SCANLINE_DEPTH * (0.5 + 0.5 * sin(in.position.y * 3.14159265));
+    let vignette = clamp(pow(16.0 * uv.x * uv.y * (1.0 - uv.x) * (1.0 - uv.y), 0.2), 0.0, 1.0);
+    let rgb = select(vec3<f32>(0.0), color.rgb * scanline * vignette, inside);
+    return vec4<f32>(rgb, color.a);
+}
@@ -0,0 +1,10 @@
+// Darken alternate pixel rows like the visible scanlines of a CRT
+
+const DEPTH: f32 = 0.2;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(frame, frame_sampler, in.uv);
+    let scanline = 1.0 - DEPTH * (0.5 + 0.5 * sin(in.position.y * 3.14159265));
+    return vec4<f32>(color.rgb * scanline, color.a);
+}
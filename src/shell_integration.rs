@@ -0,0 +1,112 @@
+use std::time::{Duration, Instant};
+
+use crate::{commands::ShellMark, grid::Cell};
+
+#[cfg(test)]
+mod tests;
+
+/// Most annotations kept; the oldest are dropped first
+const MAX_ANNOTATIONS: usize = 1000;
+
+/// A finished command's duration and exit status, shown at the end of its prompt line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    /// Absolute buffer row of the prompt line
+    pub row: usize,
+    pub text: String,
+    pub failed: bool,
+}
+
+impl Annotation {
+    /// First column to draw the annotation at, right-aligned one column from the edge of
+    /// `cells`. `None` when it would cover text or the cursor, with a blank column kept
+    /// in front of it.
+    pub fn column(&self, cells: &[Cell], cursor_col: Option<usize>) -> Option<usize> {
+        let len = self.text.chars().count();
+        let start = cells.len().checked_sub(len + 1)?;
+        let gap = start.saturating_sub(1);
+        let blank = cells[gap..].iter().all(|cell| cell.char == ' ');
+        let covers_cursor = cursor_col.is_some_and(|col| col >= gap);
+        (blank && !covers_cursor).then_some(start)
+    }
+}
+
+/// Follows OSC 133 marks to time commands and collect their annotations
+#[derive(Debug, Default)]
+pub struct CommandTracker {
+    /// Row of the current prompt line
+    prompt_row: Option<usize>,
+    /// When the current command started running
+    started: Option<Instant>,
+    annotations: Vec<Annotation>,
+}
+
+impl CommandTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a mark sent with the cursor on `cursor_row`. Returns whether the
+    /// annotations changed.
+    pub fn mark(&mut self, mark: ShellMark, cursor_row: usize, now: Instant) -> bool {
+        match mark {
+            ShellMark::PromptStart => {
+                self.prompt_row = Some(cursor_row);
+                self.started = None;
+            }
+            ShellMark::CommandStart => {
+                // Multi-line prompts end on the line the command is typed on
+                self.prompt_row = Some(cursor_row);
+            }
+            ShellMark::CommandExecuted => self.started = Some(now),
+            ShellMark::CommandFinished(status) => {
+                let (Some(row), Some(started)) = (self.prompt_row.take(), self.started.take())
+                else {
+                    return false;
+                };
+                let duration = format_duration(now.duration_since(started));
+                let failed = status.is_some_and(|status| status != 0);
+                let text = match status {
+                    Some(status) if failed => format!("exit {}, {}", status, duration),
+                    _ => duration,
+                };
+                self.annotations.retain(|annotation| annotation.row != row);
+                if self.annotations.len() >= MAX_ANNOTATIONS {
+                    self.annotations.remove(0);
+                }
+                self.annotations.push(Annotation { row, text, failed });
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// Forget everything, e.g. after the rows they point at were cleared
+    pub fn clear(&mut self) {
+        self.prompt_row = None;
+        self.started = None;
+        self.annotations.clear();
+    }
+}
+
+/// A short duration like `850ms`, `1.2s`, `42s` or `3m 05s`
+pub fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    let tenths = (millis + 50) / 100;
+    let secs = (millis + 500) / 1000;
+    if millis < 1000 {
+        format!("{}ms", millis)
+    } else if tenths < 100 {
+        format!("{}.{}s", tenths / 10, tenths % 10)
+    } else if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
@@ -0,0 +1,82 @@
+use std::time::{Duration, Instant};
+
+use crate::{commands::ShellMark, grid::Cell};
+
+use super::{format_duration, Annotation, CommandTracker};
+
+fn run(tracker: &mut CommandTracker, row: usize, status: Option<i32>, took: Duration) -> bool {
+    let start = Instant::now();
+    tracker.mark(ShellMark::PromptStart, row, start);
+    tracker.mark(ShellMark::CommandStart, row, start);
+    tracker.mark(ShellMark::CommandExecuted, row, start);
+    tracker.mark(ShellMark::CommandFinished(status), row + 1, start + took)
+}
+
+fn cells(text: &str, width: usize) -> Vec<Cell> {
+    let mut cells = vec![Cell::default(); width];
+    for (cell, c) in cells.iter_mut().zip(text.chars()) {
+        cell.char = c;
+    }
+    cells
+}
+
+#[test]
+fn finished_command_should_be_annotated_on_its_prompt_line() {
+    let mut tracker = CommandTracker::new();
+
+    assert!(run(&mut tracker, 4, Some(0), Duration::from_millis(1250)));
+    assert!(run(&mut tracker, 6, Some(2), Duration::from_millis(30)));
+
+    assert_eq!(
+        tracker.annotations(),
+        &[
+            Annotation {
+                row: 4,
+                text: "1.3s".to_string(),
+                failed: false,
+            },
+            Annotation {
+                row: 6,
+                text: "exit 2, 30ms".to_string(),
+                failed: true,
+            },
+        ]
+    );
+}
+
+#[test]
+fn finish_without_executed_command_should_not_annotate() {
+    let mut tracker = CommandTracker::new();
+    let now = Instant::now();
+
+    // An empty command line: the prompt is redrawn without running anything
+    tracker.mark(ShellMark::PromptStart, 2, now);
+    tracker.mark(ShellMark::CommandStart, 2, now);
+
+    assert!(!tracker.mark(ShellMark::CommandFinished(Some(0)), 2, now));
+    assert!(tracker.annotations().is_empty());
+}
+
+#[test]
+fn durations_should_be_formatted_compactly() {
+    assert_eq!(format_duration(Duration::from_millis(850)), "850ms");
+    assert_eq!(format_duration(Duration::from_millis(1_250)), "1.3s");
+    assert_eq!(format_duration(Duration::from_millis(9_960)), "10s");
+    assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+    assert_eq!(format_duration(Duration::from_secs(185)), "3m 05s");
+    assert_eq!(format_duration(Duration::from_secs(7_500)), "2h 05m");
+}
+
+#[test]
+fn annotation_should_only_cover_blank_cells_away_from_the_cursor() {
+    let annotation = Annotation {
+        row: 0,
+        text: "1.2s".to_string(),
+        failed: false,
+    };
+
+    assert_eq!(annotation.column(&cells("$ ls", 12), Some(4)), Some(7));
+    assert_eq!(annotation.column(&cells("$ ls -la /tmp", 12), None), None);
+    assert_eq!(annotation.column(&cells("$ ls", 12), Some(6)), None);
+    assert_eq!(annotation.column(&cells("", 4), None), None);
+}
@@ -0,0 +1,46 @@
+use std::sync::{atomic::AtomicBool, Arc};
+
+#[cfg(all(test, unix))]
+mod tests;
+
+/// Returns a flag that is set whenever the process receives SIGUSR1.
+///
+/// The UI polls it to snapshot the live grid, so the state of a wedged terminal can be captured
+/// from another shell with `kill -USR1 <pid>`. Must be called from within the tokio runtime;
+/// otherwise the flag is never set.
+pub fn snapshot_on_sigusr1() -> Arc<AtomicBool> {
+    let requested = Arc::new(AtomicBool::new(false));
+
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            log::warn!("No async runtime available, SIGUSR1 snapshots are disabled");
+            return requested;
+        };
+
+        // Register the handler before returning so an early signal doesn't kill the process
+        let registered = {
+            let _guard = handle.enter();
+            signal(SignalKind::user_defined1())
+        };
+        match registered {
+            Ok(mut stream) => {
+                log::info!(
+                    "Send SIGUSR1 to pid {} to take a snapshot",
+                    std::process::id()
+                );
+                let flag = requested.clone();
+                handle.spawn(async move {
+                    while stream.recv().await.is_some() {
+                        flag.store(true, std::sync::atomic::Ordering::Relaxed);
+                    }
+                });
+            }
+            Err(e) => log::warn!("Failed to install SIGUSR1 handler: {}", e),
+        }
+    }
+
+    requested
+}
@@ -0,0 +1,23 @@
+use std::{sync::atomic::Ordering, time::Duration};
+
+use super::snapshot_on_sigusr1;
+
+#[tokio::test]
+async fn sigusr1_should_set_snapshot_flag() {
+    let requested = snapshot_on_sigusr1();
+    assert!(!requested.load(Ordering::Relaxed));
+
+    let status = std::process::Command::new("kill")
+        .args(["-USR1", &std::process::id().to_string()])
+        .status()
+        .expect("failed to run kill");
+    assert!(status.success());
+
+    for _ in 0..200 {
+        if requested.load(Ordering::Relaxed) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("SIGUSR1 did not set the snapshot flag");
+}
@@ -1,4 +1,5 @@
 use crate::grid::{Cell, Grid};
+use crate::paths;
 use crate::styles::{Color, CursorState};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -31,8 +32,8 @@ impl TerminalSnapshot {
             width: grid.width,
             height: grid.height,
             cursor_pos: grid.cursor_pos,
-            saved_cursor_pos: grid.saved_cursor_pos,
-            scroll_pos: grid.scroll_pos,
+            saved_cursor_pos: grid.saved_cursor.pos,
+            scroll_pos: grid.bottom_row(),
             scroll_region: grid.get_scroll_region(),
             alternate_active: grid.is_alternate(),
             cursor_state: grid.styles.cursor_state,
@@ -56,10 +57,9 @@ impl TerminalSnapshot {
 
 /// Get the debug output directory, creating it if it doesn't exist
 pub fn get_debug_dir() -> io::Result<PathBuf> {
-    let config_dir = dirs::config_dir().ok_or_else(|| {
-        io::Error::new(io::ErrorKind::NotFound, "Could not find config directory")
-    })?;
-    let debug_dir = config_dir.join("mtty").join("debug");
+    let data_dir = paths::data_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Could not find data directory"))?;
+    let debug_dir = data_dir.join("debug");
     fs::create_dir_all(&debug_dir)?;
     Ok(debug_dir)
 }
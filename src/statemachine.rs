@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use tokio::sync::broadcast::Sender;
 use vte::ansi::{
     cursor_icon, Attr, CharsetIndex, ClearMode, CursorShape as VteCursorShape, CursorStyle,
@@ -7,23 +9,54 @@ use vte::ansi::{
 };
 
 use crate::{
-    commands::{ClientCommand, IdentifyTerminalMode, SgrAttribute},
+    commands::{Charset, ClientCommand, IdentifyTerminalMode, SgrAttribute},
+    inspector::Inspector,
     styles::{CursorShape, CursorState},
 };
 
+/// DEC private mode number for DECLRMM, which vte has no name for
+const LEFT_RIGHT_MARGIN_MODE: u16 = 69;
+
 pub struct StateMachine {
     tx: Sender<ClientCommand>,
+    /// While DECLRMM is set, `CSI s` sets the margins instead of saving the cursor
+    left_right_margin_mode: bool,
+    inspector: Option<Arc<Inspector>>,
 }
 
 impl StateMachine {
     pub fn new(tx: Sender<ClientCommand>) -> Self {
-        Self { tx }
+        Self {
+            tx,
+            left_right_margin_mode: false,
+            inspector: None,
+        }
+    }
+
+    /// Record sent commands in `inspector` while it is enabled
+    pub fn with_inspector(mut self, inspector: Arc<Inspector>) -> Self {
+        self.inspector = Some(inspector);
+        self
+    }
+
+    /// The inspector, if one is attached and currently recording
+    pub fn inspector(&self) -> Option<&Arc<Inspector>> {
+        self.inspector
+            .as_ref()
+            .filter(|inspector| inspector.is_enabled())
+    }
+
+    pub fn left_right_margin_mode(&self) -> bool {
+        self.left_right_margin_mode
     }
 
     /// Send a command, logging any errors instead of panicking
-    fn send(&self, cmd: ClientCommand) {
+    pub fn send(&self, cmd: ClientCommand) {
+        if let Some(inspector) = self.inspector() {
+            inspector.record_command(&cmd);
+        }
         if let Err(e) = self.tx.send(cmd) {
-            log::trace!("Failed to send command (channel closed): {}", e);
+            tracing::trace!("Failed to send command (channel closed): {}", e);
         }
     }
 }
@@ -34,7 +67,7 @@ impl Handler for StateMachine {
     }
 
     fn set_cursor_style(&mut self, s: Option<CursorStyle>) {
-        log::debug!("Set cursor style: {:?}", s);
+        tracing::debug!("Set cursor style: {:?}", s);
         match s {
             Some(s) => {
                 let blinking = s.blinking;
@@ -50,12 +83,12 @@ impl Handler for StateMachine {
                     shape, blinking,
                 )));
             }
-            _ => {}
+            None => self.send(ClientCommand::ResetCursorState),
         }
     }
 
     fn set_cursor_shape(&mut self, shape: VteCursorShape) {
-        log::debug!("Set cursor shape: {:?}", shape);
+        tracing::debug!("Set cursor shape: {:?}", shape);
         let cursor_shape = match shape {
             VteCursorShape::Block => CursorShape::Block,
             VteCursorShape::Underline => CursorShape::Underline,
@@ -68,42 +101,42 @@ impl Handler for StateMachine {
     }
 
     fn input(&mut self, c: char) {
-        log::trace!("Input character: {}", c);
+        tracing::trace!("Input character: {}", c);
         self.send(ClientCommand::Print(c));
     }
 
     fn goto(&mut self, line: i32, col: usize) {
-        log::debug!("Goto line: {}, col: {}", line, col);
+        tracing::debug!("Goto line: {}, col: {}", line, col);
         self.send(ClientCommand::MoveCursor(line as i16, col as i16));
     }
 
     fn goto_line(&mut self, line: i32) {
-        log::debug!("Goto line: {}", line);
+        tracing::debug!("Goto line: {}", line);
         self.send(ClientCommand::MoveCursor(line as i16, 0));
     }
 
     fn goto_col(&mut self, col: usize) {
-        log::debug!("Goto col: {}", col);
+        tracing::debug!("Goto col: {}", col);
         self.send(ClientCommand::MoveCursorAbsoluteHorizontal(col as i16));
     }
 
     fn insert_blank(&mut self, count: usize) {
-        log::debug!("Insert blank: {}", count);
+        tracing::debug!("Insert blank: {}", count);
         self.send(ClientCommand::InsertBlanks(count as i16));
     }
 
     fn move_up(&mut self, u: usize) {
-        log::debug!("Move up: {}", u);
+        tracing::debug!("Move up: {}", u);
         self.send(ClientCommand::MoveCursorVertical(-(u as i16)));
     }
 
     fn move_down(&mut self, d: usize) {
-        log::debug!("Move down: {}", d);
+        tracing::debug!("Move down: {}", d);
         self.send(ClientCommand::MoveCursorVertical(d as i16));
     }
 
     fn identify_terminal(&mut self, intermediate: Option<char>) {
-        log::debug!("Identify terminal: {:?}", intermediate);
+        tracing::debug!("Identify terminal: {:?}", intermediate);
         match intermediate {
             None => {
                 // Primary device attributes (DA1) - report as VT220
@@ -118,13 +151,13 @@ impl Handler for StateMachine {
                 ));
             }
             _ => {
-                log::debug!("Unknown identify terminal intermediate: {:?}", intermediate);
+                tracing::debug!("Unknown identify terminal intermediate: {:?}", intermediate);
             }
         }
     }
 
     fn device_status(&mut self, arg: usize) {
-        log::debug!("Device status: {}", arg);
+        tracing::debug!("Device status: {}", arg);
         match arg {
             5 => {
                 self.send(ClientCommand::ReportCondition(true));
@@ -139,47 +172,47 @@ impl Handler for StateMachine {
     }
 
     fn move_forward(&mut self, col: usize) {
-        log::debug!("Move forward: {}", col);
+        tracing::debug!("Move forward: {}", col);
         self.send(ClientCommand::MoveCursorHorizontal(col as i16));
     }
 
     fn move_backward(&mut self, col: usize) {
-        log::debug!("Move backward: {}", col);
+        tracing::debug!("Move backward: {}", col);
         self.send(ClientCommand::MoveCursorHorizontal(-(col as i16)));
     }
 
     fn move_down_and_cr(&mut self, _row: usize) {
-        log::debug!("Move down and CR");
+        tracing::debug!("Move down and CR");
         self.send(ClientCommand::MoveCursorVerticalWithCarriageReturn(1));
     }
 
     fn move_up_and_cr(&mut self, _row: usize) {
-        log::debug!("Move up and CR");
+        tracing::debug!("Move up and CR");
         self.send(ClientCommand::MoveCursorVerticalWithCarriageReturn(-1));
     }
 
     fn put_tab(&mut self, _count: u16) {
-        log::debug!("Put tab");
+        tracing::debug!("Put tab");
         self.send(ClientCommand::PutTab);
     }
 
     fn backspace(&mut self) {
-        log::debug!("Backspace");
+        tracing::debug!("Backspace");
         self.send(ClientCommand::Backspace);
     }
 
     fn carriage_return(&mut self) {
-        log::debug!("Carriage return");
+        tracing::debug!("Carriage return");
         self.send(ClientCommand::CarriageReturn);
     }
 
     fn linefeed(&mut self) {
-        log::debug!("Line feed");
+        tracing::debug!("Line feed");
         self.send(ClientCommand::LineFeed);
     }
 
     fn bell(&mut self) {
-        log::error!("Bell");
+        self.send(ClientCommand::Bell);
     }
 
     fn substitute(&mut self) {
@@ -187,7 +220,7 @@ impl Handler for StateMachine {
     }
 
     fn newline(&mut self) {
-        log::debug!("Newline");
+        tracing::debug!("Newline");
         self.send(ClientCommand::NewLine);
     }
 
@@ -196,32 +229,32 @@ impl Handler for StateMachine {
     }
 
     fn scroll_up(&mut self, count: usize) {
-        log::debug!("Scroll up: {}", count);
+        tracing::debug!("Scroll up: {}", count);
         self.send(ClientCommand::ScrollUp(count as i16));
     }
 
     fn scroll_down(&mut self, count: usize) {
-        log::debug!("Scroll down: {}", count);
+        tracing::debug!("Scroll down: {}", count);
         self.send(ClientCommand::ScrollDown(count as i16));
     }
 
     fn insert_blank_lines(&mut self, count: usize) {
-        log::debug!("Insert blank lines: {}", count);
+        tracing::debug!("Insert blank lines: {}", count);
         self.send(ClientCommand::InsertBlankLines(count as i16));
     }
 
     fn delete_lines(&mut self, l: usize) {
-        log::debug!("Delete lines: {}", l);
+        tracing::debug!("Delete lines: {}", l);
         self.send(ClientCommand::DeleteLines(l as i16));
     }
 
     fn erase_chars(&mut self, c: usize) {
-        log::debug!("Erase chars: {}", c);
+        tracing::debug!("Erase chars: {}", c);
         self.send(ClientCommand::ClearCount(c as i16));
     }
 
     fn delete_chars(&mut self, count: usize) {
-        log::debug!("Delete chars: {}", count);
+        tracing::debug!("Delete chars: {}", count);
         self.send(ClientCommand::DeleteChars(count as i16));
     }
 
@@ -234,17 +267,21 @@ impl Handler for StateMachine {
     }
 
     fn save_cursor_position(&mut self) {
-        log::debug!("Save cursor position");
+        if self.left_right_margin_mode {
+            // This is DECSLRM, which the parser reports itself
+            return;
+        }
+        tracing::debug!("Save cursor position");
         self.send(ClientCommand::SaveCursor);
     }
 
     fn restore_cursor_position(&mut self) {
-        log::debug!("Restore cursor position");
+        tracing::debug!("Restore cursor position");
         self.send(ClientCommand::RestoreCursor);
     }
 
     fn clear_line(&mut self, mode: LineClearMode) {
-        log::debug!("Clear line: {:?}", mode);
+        tracing::debug!("Clear line: {:?}", mode);
         match mode {
             LineClearMode::All => {
                 self.send(ClientCommand::ClearLine);
@@ -259,7 +296,7 @@ impl Handler for StateMachine {
     }
 
     fn clear_screen(&mut self, mode: ClearMode) {
-        log::debug!("Clear screen: {:?}", mode);
+        tracing::debug!("Clear screen: {:?}", mode);
         match mode {
             ClearMode::All => {
                 self.send(ClientCommand::ClearScreen);
@@ -271,8 +308,7 @@ impl Handler for StateMachine {
                 self.send(ClientCommand::ClearBelow);
             }
             ClearMode::Saved => {
-                // Should also delete lines saved in the scrollback buffer
-                self.send(ClientCommand::ClearScreen);
+                self.send(ClientCommand::ClearScrollback);
             }
         }
     }
@@ -290,12 +326,12 @@ impl Handler for StateMachine {
     }
 
     fn reverse_index(&mut self) {
-        log::debug!("Reverse index");
+        tracing::debug!("Reverse index");
         self.send(ClientCommand::ReverseIndex);
     }
 
     fn terminal_attribute(&mut self, attr: Attr) {
-        log::debug!("Terminal attribute: {:?}", attr);
+        tracing::debug!("Terminal attribute: {:?}", attr);
         self.send(ClientCommand::SGR(SgrAttribute::from_vte_attr(attr)));
     }
 
@@ -312,7 +348,7 @@ impl Handler for StateMachine {
     }
 
     fn set_private_mode(&mut self, mode: PrivateMode) {
-        log::debug!("Set private mode: {:?}", mode);
+        tracing::debug!("Set private mode: {:?}", mode);
         match mode {
             PrivateMode::Named(NamedPrivateMode::ShowCursor) => {
                 self.send(ClientCommand::ShowCursor);
@@ -323,17 +359,24 @@ impl Handler for StateMachine {
             PrivateMode::Named(NamedPrivateMode::CursorKeys) => {
                 self.send(ClientCommand::CursorKeysMode(true));
             }
+            PrivateMode::Named(NamedPrivateMode::Origin) => {
+                self.send(ClientCommand::OriginMode(true));
+            }
             PrivateMode::Named(NamedPrivateMode::BracketedPaste) => {
                 self.send(ClientCommand::BracketedPasteMode(true));
             }
+            PrivateMode::Unknown(LEFT_RIGHT_MARGIN_MODE) => {
+                self.left_right_margin_mode = true;
+                self.send(ClientCommand::LeftRightMarginMode(true));
+            }
             _ => {
-                log::debug!("Unhandled set private mode: {:?}", mode);
+                tracing::debug!("Unhandled set private mode: {:?}", mode);
             }
         }
     }
 
     fn unset_private_mode(&mut self, mode: PrivateMode) {
-        log::debug!("Unset private mode: {:?}", mode);
+        tracing::debug!("Unset private mode: {:?}", mode);
         match mode {
             PrivateMode::Named(NamedPrivateMode::ShowCursor) => {
                 self.send(ClientCommand::HideCursor);
@@ -344,11 +387,18 @@ impl Handler for StateMachine {
             PrivateMode::Named(NamedPrivateMode::CursorKeys) => {
                 self.send(ClientCommand::CursorKeysMode(false));
             }
+            PrivateMode::Named(NamedPrivateMode::Origin) => {
+                self.send(ClientCommand::OriginMode(false));
+            }
             PrivateMode::Named(NamedPrivateMode::BracketedPaste) => {
                 self.send(ClientCommand::BracketedPasteMode(false));
             }
+            PrivateMode::Unknown(LEFT_RIGHT_MARGIN_MODE) => {
+                self.left_right_margin_mode = false;
+                self.send(ClientCommand::LeftRightMarginMode(false));
+            }
             _ => {
-                log::debug!("Unhandled unset private mode: {:?}", mode);
+                tracing::debug!("Unhandled unset private mode: {:?}", mode);
             }
         }
     }
@@ -358,7 +408,7 @@ impl Handler for StateMachine {
     }
 
     fn set_scrolling_region(&mut self, top: usize, bottom: Option<usize>) {
-        log::debug!("Set scrolling region: {} {:?}", top, bottom);
+        tracing::debug!("Set scrolling region: {} {:?}", top, bottom);
         self.send(ClientCommand::SetScrollingRegion(top, bottom));
     }
 
@@ -370,21 +420,27 @@ impl Handler for StateMachine {
         log::error!("Unset keypad application mode");
     }
 
-    fn set_active_charset(&mut self, _: CharsetIndex) {
-        // Character set switching is not implemented but is harmless to ignore
+    fn set_active_charset(&mut self, index: CharsetIndex) {
+        tracing::debug!("Set active charset: {:?}", index);
+        self.send(ClientCommand::SetActiveCharset(index as usize));
     }
 
-    fn configure_charset(&mut self, _: CharsetIndex, _: StandardCharset) {
-        // Character set configuration (e.g., G0 ASCII) is not implemented but is harmless to ignore
+    fn configure_charset(&mut self, index: CharsetIndex, charset: StandardCharset) {
+        tracing::debug!("Configure charset {:?}: {:?}", index, charset);
+        let charset = match charset {
+            StandardCharset::Ascii => Charset::Ascii,
+            StandardCharset::SpecialCharacterAndLineDrawing => Charset::LineDrawing,
+        };
+        self.send(ClientCommand::ConfigureCharset(index as usize, charset));
     }
 
     fn set_color(&mut self, i: usize, rgb: Rgb) {
-        log::debug!("Set color: {} {:?}", i, rgb);
+        tracing::debug!("Set color: {} {:?}", i, rgb);
         self.send(ClientCommand::SetColor(i, rgb.into()));
     }
 
     fn dynamic_color_sequence(&mut self, _prefix: String, index: usize, color: &str) {
-        log::debug!("Dynamic color sequence: index={}, color={}", index, color);
+        tracing::debug!("Dynamic color sequence: index={}, color={}", index, color);
 
         // Parse color string - formats like "#RRGGBB" or "rgb:RR/GG/BB" or "rgbi:R/G/B"
         let rgb = if let Some(hex) = color.strip_prefix('#') {
@@ -421,13 +477,13 @@ impl Handler for StateMachine {
                 10 => self.send(ClientCommand::SetDefaultForeground(rgb.into())),
                 11 => self.send(ClientCommand::SetDefaultBackground(rgb.into())),
                 // Index 12 is cursor color, not implemented yet
-                _ => log::debug!("Unhandled dynamic color index: {}", index),
+                _ => tracing::debug!("Unhandled dynamic color index: {}", index),
             }
         }
     }
 
     fn reset_color(&mut self, i: usize) {
-        log::debug!("Reset color: {}", i);
+        tracing::debug!("Reset color: {}", i);
         self.send(ClientCommand::ResetColor(i));
     }
 
@@ -452,17 +508,20 @@ impl Handler for StateMachine {
     }
 
     fn text_area_size_pixels(&mut self) {
-        log::debug!("Text area size pixels");
+        tracing::debug!("Text area size pixels");
         self.send(ClientCommand::ReportTextAreaSizePixels);
     }
 
     fn text_area_size_chars(&mut self) {
-        log::debug!("Text area size chars");
+        tracing::debug!("Text area size chars");
         self.send(ClientCommand::ReportTextAreaSizeChars);
     }
 
-    fn set_hyperlink(&mut self, _: Option<Hyperlink>) {
-        log::error!("Set hyperlink");
+    fn set_hyperlink(&mut self, hyperlink: Option<Hyperlink>) {
+        tracing::debug!("Set hyperlink: {:?}", hyperlink);
+        self.send(ClientCommand::SetHyperlink(
+            hyperlink.map(|hyperlink| hyperlink.uri),
+        ));
     }
 
     fn set_mouse_cursor_icon(&mut self, _: cursor_icon::CursorIcon) {
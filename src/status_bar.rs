@@ -0,0 +1,209 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+
+use crate::config::{Config, StatusBarPosition, StatusSegment};
+
+#[cfg(test)]
+mod tests;
+
+/// How often the shell's working directory and git branch are re-read
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+/// How long the bell indicator stays visible after the bell rings
+const BELL_DURATION: Duration = Duration::from_secs(3);
+const SEPARATOR: &str = " │ ";
+
+/// Shell state gathered off the UI thread
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ShellInfo {
+    cwd: Option<PathBuf>,
+    branch: Option<String>,
+}
+
+/// One-row bar outside the terminal grid with configurable segments
+pub struct StatusBar {
+    position: StatusBarPosition,
+    segments: Vec<StatusSegment>,
+    session_name: Option<String>,
+    git_command: String,
+    shell_pid: Option<u32>,
+    info: ShellInfo,
+    pending: Option<Receiver<ShellInfo>>,
+    last_refresh: Option<Instant>,
+    bell_at: Option<Instant>,
+    text: String,
+}
+
+impl StatusBar {
+    /// Build the status bar from the config, or None when it is disabled
+    pub fn new(config: &Config, shell_pid: Option<u32>) -> Option<Self> {
+        if !config.status_bar {
+            return None;
+        }
+        Some(Self {
+            position: config.status_bar_position,
+            segments: config.status_bar_segments.clone(),
+            session_name: config.session_name.clone(),
+            git_command: config.git_branch_command.clone(),
+            shell_pid,
+            info: ShellInfo::default(),
+            pending: None,
+            last_refresh: None,
+            bell_at: None,
+            text: String::new(),
+        })
+    }
+
+    pub fn position(&self) -> StatusBarPosition {
+        self.position
+    }
+
+    /// Current bar contents, as of the last `update`
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn ring_bell(&mut self) {
+        self.bell_at = Some(Instant::now());
+    }
+
+    /// Refresh the shell info when due and recompose the bar.
+    ///
+    /// Returns true when the text changed and the bar needs redrawing.
+    pub fn update(&mut self, title: &str) -> bool {
+        let now = Instant::now();
+        self.poll_shell_info(now);
+
+        let text = self.compose(title, Local::now(), now);
+        if text == self.text {
+            return false;
+        }
+        self.text = text;
+        true
+    }
+
+    /// Pick up a finished background refresh and start the next one when due
+    fn poll_shell_info(&mut self, now: Instant) {
+        if let Some(pending) = &self.pending {
+            match pending.try_recv() {
+                Ok(info) => {
+                    self.info = info;
+                    self.pending = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => self.pending = None,
+            }
+        }
+
+        let wants_info = self
+            .segments
+            .iter()
+            .any(|segment| matches!(segment, StatusSegment::Cwd | StatusSegment::GitBranch));
+        let due = self
+            .last_refresh
+            .is_none_or(|last| now.duration_since(last) >= REFRESH_INTERVAL);
+        let Some(pid) = self.shell_pid.filter(|_| wants_info && due) else {
+            return;
+        };
+
+        self.last_refresh = Some(now);
+        let git_command = self
+            .segments
+            .contains(&StatusSegment::GitBranch)
+            .then(|| self.git_command.clone());
+        let (tx, rx) = mpsc::channel();
+        self.pending = Some(rx);
+        thread::spawn(move || {
+            let cwd = process_cwd(pid);
+            let branch = match (&cwd, git_command) {
+                (Some(cwd), Some(command)) => git_branch(&command, cwd),
+                _ => None,
+            };
+            let _ = tx.send(ShellInfo { cwd, branch });
+        });
+    }
+
+    fn compose(&self, title: &str, time: DateTime<Local>, now: Instant) -> String {
+        let parts: Vec<String> = self
+            .segments
+            .iter()
+            .filter_map(|segment| match segment {
+                StatusSegment::Cwd => self.info.cwd.as_deref().map(display_path),
+                StatusSegment::GitBranch => self.info.branch.as_ref().map(|b| format!("⎇ {}", b)),
+                StatusSegment::Time => Some(time.format("%H:%M").to_string()),
+                StatusSegment::Session => Some(
+                    self.session_name
+                        .clone()
+                        .unwrap_or_else(|| title.to_string()),
+                ),
+                StatusSegment::Bell => self
+                    .bell_at
+                    .filter(|rang| now.duration_since(*rang) < BELL_DURATION)
+                    .map(|_| "🔔".to_string()),
+            })
+            .filter(|part| !part.is_empty())
+            .collect();
+        format!(" {}", parts.join(SEPARATOR))
+    }
+}
+
+/// Show paths under the home directory relative to `~`
+fn display_path(path: &Path) -> String {
+    match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+        Some(relative) if relative.as_os_str().is_empty() => "~".to_string(),
+        Some(relative) => format!("~/{}", relative.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Working directory of a running process
+fn process_cwd(pid: u32) -> Option<PathBuf> {
+    #[cfg(target_os = "linux")]
+    {
+        std::fs::read_link(format!("/proc/{}/cwd", pid)).ok()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        // lsof prints one field per line; the name field starts with 'n'
+        let output = Command::new("lsof")
+            .args(["-a", "-p", &pid.to_string(), "-d", "cwd", "-Fn"])
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.strip_prefix('n'))
+            .map(PathBuf::from)
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// First line printed by the git branch command, run in `cwd`
+fn git_branch(command: &str, cwd: &Path) -> Option<String> {
+    let output = Command::new("sh")
+        .args(["-c", command])
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(str::trim)
+        .filter(|branch| !branch.is_empty())
+        .map(str::to_string)
+}
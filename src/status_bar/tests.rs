@@ -0,0 +1,91 @@
+use std::{
+    env,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use chrono::{Local, TimeZone};
+
+use crate::config::{Config, StatusSegment};
+
+use super::{git_branch, ShellInfo, StatusBar};
+
+fn status_bar(segments: Vec<StatusSegment>) -> StatusBar {
+    let config = Config {
+        status_bar: true,
+        status_bar_segments: segments,
+        ..Config::default()
+    };
+    StatusBar::new(&config, None).unwrap()
+}
+
+#[test]
+fn disabled_status_bar_should_not_be_created() {
+    assert!(StatusBar::new(&Config::default(), None).is_none());
+}
+
+#[test]
+fn compose_should_join_segments_in_order() {
+    let mut bar = status_bar(vec![
+        StatusSegment::Session,
+        StatusSegment::Cwd,
+        StatusSegment::GitBranch,
+        StatusSegment::Time,
+    ]);
+    bar.info = ShellInfo {
+        cwd: Some(PathBuf::from("/srv/app")),
+        branch: Some("main".to_string()),
+    };
+    let time = Local.with_ymd_and_hms(2024, 5, 1, 9, 7, 0).unwrap();
+
+    let text = bar.compose("MTTY", time, Instant::now());
+
+    assert_eq!(text, " MTTY │ /srv/app │ ⎇ main │ 09:07");
+}
+
+#[test]
+fn missing_segments_should_be_skipped() {
+    let mut bar = status_bar(vec![
+        StatusSegment::Session,
+        StatusSegment::GitBranch,
+        StatusSegment::Bell,
+    ]);
+    bar.session_name = Some("work".to_string());
+
+    let text = bar.compose("MTTY", Local::now(), Instant::now());
+
+    assert_eq!(text, " work");
+}
+
+#[test]
+fn bell_indicator_should_expire() {
+    let mut bar = status_bar(vec![StatusSegment::Bell]);
+    bar.ring_bell();
+    let now = Instant::now();
+
+    assert_eq!(bar.compose("", Local::now(), now), " 🔔");
+    assert_eq!(
+        bar.compose("", Local::now(), now + Duration::from_secs(5)),
+        " "
+    );
+}
+
+#[test]
+fn git_branch_should_use_first_line_of_command_output() {
+    let cwd = env::temp_dir();
+
+    assert_eq!(
+        git_branch("printf 'feature\\nextra'", &cwd),
+        Some("feature".to_string())
+    );
+    assert_eq!(git_branch("exit 1", &cwd), None);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn process_cwd_should_read_working_directory() {
+    assert_eq!(
+        super::process_cwd(std::process::id()),
+        env::current_dir().ok()
+    );
+}
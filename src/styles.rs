@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use vte::ansi::Color as VteColor;
 
+#[cfg(test)]
+mod tests;
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Color {
     Black,
@@ -27,22 +30,24 @@ pub enum Color {
 
 impl Color {
     pub const DEFAULT_ARRAY: [Color; 256] = [
+        // 0-15: named colors, shared by `Color::Black..=Color::BrightWhite`
         Color::Rgb(0, 0, 0),
-        Color::Rgb(128, 0, 0),
-        Color::Rgb(0, 128, 0),
-        Color::Rgb(128, 128, 0),
-        Color::Rgb(0, 0, 128),
-        Color::Rgb(128, 0, 128),
-        Color::Rgb(0, 128, 128),
-        Color::Rgb(192, 192, 192),
-        Color::Rgb(128, 128, 128),
-        Color::Rgb(255, 0, 0),
-        Color::Rgb(0, 255, 0),
-        Color::Rgb(255, 255, 0),
-        Color::Rgb(0, 0, 255),
-        Color::Rgb(255, 0, 255),
-        Color::Rgb(0, 255, 255),
+        Color::Rgb(205, 49, 49),
+        Color::Rgb(13, 188, 121),
+        Color::Rgb(229, 229, 16),
+        Color::Rgb(36, 114, 200),
+        Color::Rgb(188, 63, 188),
+        Color::Rgb(17, 168, 205),
+        Color::Rgb(229, 229, 229),
+        Color::Rgb(102, 102, 102),
+        Color::Rgb(241, 76, 76),
+        Color::Rgb(35, 209, 139),
+        Color::Rgb(245, 245, 67),
+        Color::Rgb(59, 142, 234),
+        Color::Rgb(214, 112, 214),
+        Color::Rgb(41, 184, 219),
         Color::Rgb(255, 255, 255),
+        // 16-231: 6x6x6 color cube
         Color::Rgb(0, 0, 0),
         Color::Rgb(0, 0, 95),
         Color::Rgb(0, 0, 135),
@@ -285,6 +290,14 @@ impl Color {
         Color::Rgb(238, 238, 238),
     ];
 
+    /// Components of an `Rgb` color, black for anything that needs a palette lookup
+    fn rgb_components(self) -> (u8, u8, u8) {
+        match self {
+            Color::Rgb(r, g, b) => (r, g, b),
+            _ => (0, 0, 0),
+        }
+    }
+
     pub fn from_vte_color(color: VteColor) -> Self {
         match color {
             VteColor::Named(named) => match named {
@@ -322,6 +335,27 @@ impl Color {
     }
 }
 
+/// Convert an sRGB component (0-255) to linear light (0.0-1.0)
+pub fn srgb_to_linear(srgb: u8) -> f32 {
+    let s = srgb as f32 / 255.0;
+    if s <= 0.04045 {
+        s / 12.92
+    } else {
+        ((s + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert linear light (0.0-1.0) to an sRGB component, as an sRGB surface does on write
+pub fn linear_to_srgb(linear: f32) -> u8 {
+    let l = linear.clamp(0.0, 1.0);
+    let s = if l <= 0.0031308 {
+        l * 12.92
+    } else {
+        1.055 * l.powf(1.0 / 2.4) - 0.055
+    };
+    (s * 255.0).round() as u8
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum CursorShape {
     Block,
@@ -335,11 +369,17 @@ pub enum CursorShape {
 pub struct CursorState {
     pub shape: CursorShape,
     pub hidden: bool,
+    #[serde(default)]
+    pub blinking: bool,
 }
 
 impl CursorState {
-    pub fn new(shape: CursorShape, hidden: bool) -> Self {
-        Self { shape, hidden }
+    pub fn new(shape: CursorShape, blinking: bool) -> Self {
+        Self {
+            shape,
+            hidden: false,
+            blinking,
+        }
     }
 }
 
@@ -348,6 +388,7 @@ impl Default for CursorState {
         Self {
             shape: CursorShape::Beam,
             hidden: false,
+            blinking: false,
         }
     }
 }
@@ -369,80 +410,96 @@ pub struct Styles {
     pub default_background_color: Color,
     pub active_text_color: Color,
     pub default_text_color: Color,
+    /// Background of the cursor's row when the cursor line highlight is on
+    pub cursor_line_color: Color,
     pub font_size: u32,
     pub italic: bool,
     pub underline: bool,
     pub reverse: bool,
     pub color_array: [Color; 256],
     pub cursor_state: CursorState,
+    /// Cursor shape and blinking restored by DECSCUSR 0
+    pub default_cursor_state: CursorState,
 }
 
 impl Styles {
-    /// Convert a Color to RGBA float array [r, g, b, a] for wgpu
-    pub fn to_wgpu_color(&self, color: Color) -> [f32; 4] {
-        let (r, g, b) = match color {
-            Color::Black => (0, 0, 0),
-            Color::Red => (128, 0, 0),
-            Color::Green => (0, 128, 0),
-            Color::Yellow => (128, 128, 0),
-            Color::Blue => (0, 0, 128),
-            Color::Magenta => (128, 0, 128),
-            Color::Cyan => (0, 128, 128),
-            Color::White => (192, 192, 192),
-            Color::Gray => (128, 128, 128),
-            Color::BrightRed => (255, 0, 0),
-            Color::BrightGreen => (0, 255, 0),
-            Color::BrightYellow => (255, 255, 0),
-            Color::BrightBlue => (0, 0, 255),
-            Color::BrightMagenta => (255, 0, 255),
-            Color::BrightCyan => (0, 255, 255),
-            Color::BrightWhite => (255, 255, 255),
-            Color::Rgb(r, g, b) => (r, g, b),
-            Color::Foreground => return self.to_wgpu_color(self.default_text_color),
-            Color::Background => return self.to_wgpu_color(self.default_background_color),
-            Color::ColorIndex(i) => return self.to_wgpu_color(self.color_array[i as usize]),
+    /// Resolve a Color to its sRGB components using the current palette
+    pub fn to_rgb(&self, color: Color) -> (u8, u8, u8) {
+        let index = match color {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::Gray => 8,
+            Color::BrightRed => 9,
+            Color::BrightGreen => 10,
+            Color::BrightYellow => 11,
+            Color::BrightBlue => 12,
+            Color::BrightMagenta => 13,
+            Color::BrightCyan => 14,
+            Color::BrightWhite => 15,
+            Color::ColorIndex(i) => i,
+            Color::Rgb(r, g, b) => return (r, g, b),
+            Color::Foreground => return self.to_rgb(self.default_text_color),
+            Color::Background => return self.to_rgb(self.default_background_color),
         };
-        [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0]
+        match self.color_array[index as usize] {
+            Color::Rgb(r, g, b) => (r, g, b),
+            // Palette entries are always Rgb, but guard against self-references
+            _ => Color::DEFAULT_ARRAY[index as usize].rgb_components(),
+        }
     }
 
-    /// Convert a Color to RGB u8 tuple for glyphon
-    pub fn to_rgb(&self, color: Color) -> (u8, u8, u8) {
-        match color {
-            Color::Black => (0, 0, 0),
-            Color::Red => (128, 0, 0),
-            Color::Green => (0, 128, 0),
-            Color::Yellow => (128, 128, 0),
-            Color::Blue => (0, 0, 128),
-            Color::Magenta => (128, 0, 128),
-            Color::Cyan => (0, 128, 128),
-            Color::White => (192, 192, 192),
-            Color::Gray => (128, 128, 128),
-            Color::BrightRed => (255, 0, 0),
-            Color::BrightGreen => (0, 255, 0),
-            Color::BrightYellow => (255, 255, 0),
-            Color::BrightBlue => (0, 0, 255),
-            Color::BrightMagenta => (255, 0, 255),
-            Color::BrightCyan => (0, 255, 255),
-            Color::BrightWhite => (255, 255, 255),
-            Color::Rgb(r, g, b) => (r, g, b),
-            Color::Foreground => self.to_rgb(self.default_text_color),
-            Color::Background => self.to_rgb(self.default_background_color),
-            Color::ColorIndex(i) => self.to_rgb(self.color_array[i as usize]),
+    /// Convert a Color to an RGBA float array [r, g, b, a] for a wgpu render target.
+    ///
+    /// sRGB targets are encoded by the GPU on write, so they must be given linear values;
+    /// other targets store the sRGB components unchanged.
+    pub fn to_wgpu_color(&self, color: Color, srgb_target: bool) -> [f32; 4] {
+        let (r, g, b) = self.to_rgb(color);
+        if srgb_target {
+            [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), 1.0]
+        } else {
+            [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0]
         }
     }
 
+    /// Reset the SGR attributes (SGR 0), keeping the palette, default colors and cursor state
+    pub fn reset_attributes(&mut self) {
+        self.active_background_color = Color::Background;
+        self.active_text_color = Color::Foreground;
+        self.font_size = 16;
+        self.italic = false;
+        self.underline = false;
+        self.reverse = false;
+    }
+
     pub fn default() -> Self {
         Self {
             active_background_color: Color::Background,
             default_background_color: Color::Black,
             active_text_color: Color::Foreground,
             default_text_color: Color::White,
+            cursor_line_color: Color::Rgb(28, 28, 28),
             font_size: 16,
             italic: false,
             underline: false,
             reverse: false,
             color_array: Color::DEFAULT_ARRAY,
             cursor_state: CursorState::default(),
+            default_cursor_state: CursorState::default(),
+        }
+    }
+
+    /// Default styles with `cursor` as the configured cursor
+    pub fn with_cursor(cursor: CursorState) -> Self {
+        Self {
+            cursor_state: cursor,
+            default_cursor_state: cursor,
+            ..Self::default()
         }
     }
 }
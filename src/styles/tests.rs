@@ -0,0 +1,96 @@
+use crate::styles::{linear_to_srgb, srgb_to_linear, Color, Styles};
+
+#[test]
+fn named_colors_should_match_their_palette_index() {
+    let styles = Styles::default();
+
+    assert_eq!(
+        styles.to_rgb(Color::Red),
+        styles.to_rgb(Color::ColorIndex(1))
+    );
+    assert_eq!(
+        styles.to_rgb(Color::BrightWhite),
+        styles.to_rgb(Color::ColorIndex(15))
+    );
+    assert_eq!(
+        styles.to_rgb(Color::Gray),
+        styles.to_rgb(Color::ColorIndex(8))
+    );
+}
+
+#[test]
+fn set_palette_entry_should_change_named_color() {
+    let mut styles = Styles::default();
+
+    styles.color_array[1] = Color::Rgb(1, 2, 3);
+
+    assert_eq!(styles.to_rgb(Color::Red), (1, 2, 3));
+}
+
+#[test]
+fn default_colors_should_resolve_through_palette() {
+    let styles = Styles::default();
+
+    assert_eq!(
+        styles.to_rgb(Color::Foreground),
+        styles.to_rgb(styles.default_text_color)
+    );
+    assert_eq!(
+        styles.to_rgb(Color::Background),
+        styles.to_rgb(styles.default_background_color)
+    );
+}
+
+#[test]
+fn srgb_linear_conversion_should_round_trip_every_component() {
+    for c in 0..=255u8 {
+        assert_eq!(linear_to_srgb(srgb_to_linear(c)), c);
+    }
+}
+
+#[test]
+fn to_wgpu_color_for_srgb_target_should_encode_back_to_palette_values() {
+    let styles = Styles::default();
+
+    for color in Color::DEFAULT_ARRAY {
+        let (r, g, b) = styles.to_rgb(color);
+        let linear = styles.to_wgpu_color(color, true);
+
+        assert_eq!(
+            (
+                linear_to_srgb(linear[0]),
+                linear_to_srgb(linear[1]),
+                linear_to_srgb(linear[2])
+            ),
+            (r, g, b)
+        );
+        assert_eq!(linear[3], 1.0);
+    }
+}
+
+#[test]
+fn to_wgpu_color_for_non_srgb_target_should_not_linearize() {
+    let styles = Styles::default();
+
+    let rgba = styles.to_wgpu_color(Color::Rgb(128, 64, 255), false);
+
+    assert_eq!(rgba, [128.0 / 255.0, 64.0 / 255.0, 1.0, 1.0]);
+}
+
+#[test]
+fn reset_attributes_should_keep_palette_and_defaults() {
+    let mut styles = Styles::default();
+    styles.color_array[1] = Color::Rgb(1, 2, 3);
+    styles.default_background_color = Color::Rgb(4, 5, 6);
+    styles.active_text_color = Color::Green;
+    styles.italic = true;
+    styles.reverse = true;
+
+    styles.reset_attributes();
+
+    assert_eq!(styles.to_rgb(Color::Red), (1, 2, 3));
+    assert_eq!(styles.to_rgb(Color::Background), (4, 5, 6));
+    assert!(matches!(styles.active_text_color, Color::Foreground));
+    assert!(!styles.italic);
+    assert!(!styles.reverse);
+}
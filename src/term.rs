@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::env;
 use std::os::fd::{AsFd, AsRawFd};
 use std::os::unix::process::CommandExt;
@@ -7,21 +8,28 @@ use std::{
     io::Error,
     os::fd::{BorrowedFd, OwnedFd},
     process::{Child, Command},
+    time::Duration,
 };
 
+use nix::errno::Errno;
 use nix::libc::{self, c_int, TIOCSCTTY};
 use nix::unistd::read;
 use nix::unistd::write;
 use rustix::termios::{self, OptionalActions, Termios};
 use rustix_openpty::openpty;
-use tokio::sync::broadcast::{self, Receiver};
+use tokio::sync::broadcast::{self, error::RecvError, Receiver};
 
 use crate::app::{ClientChannel, ServerChannel};
 use crate::commands::{ClientCommand, ServerCommand};
 use crate::config::Config;
+use crate::inspector::Inspector;
+use crate::metrics::Metrics;
+use crate::parser::Parser;
+use crate::profiling::SPAN_TARGET;
 use crate::statemachine;
 
-use vte::ansi::Processor;
+#[cfg(test)]
+mod tests;
 
 // Steps to create a terminal
 // Call openpty to get a master and slave fd
@@ -63,18 +71,59 @@ pub fn read_from_raw_fd(fd: i32) -> ReadResult {
     }
 }
 
-pub fn write_to_fd(fd: BorrowedFd, data: &[u8]) -> bool {
-    let write_result = write(fd, data);
+/// How long to wait before retrying queued input the PTY could not take yet
+const WRITE_RETRY: Duration = Duration::from_millis(1);
+
+/// Most input kept waiting for the PTY. Pastes hold back well below this on their own; once
+/// it is reached the program has stopped reading, and further input is dropped like a full
+/// tty input buffer would, instead of piling up in memory.
+pub const MAX_QUEUED: usize = 1024 * 1024;
+
+/// Input waiting to be written to the PTY.
+///
+/// The master fd is non-blocking, so a write may take only part of the data or fail with
+/// `EAGAIN` while the program on the other side is not reading. What is left stays queued
+/// and is retried, in order, before any later input.
+#[derive(Debug, Default)]
+pub struct WriteQueue {
+    pending: VecDeque<u8>,
+}
 
-    match write_result {
-        Ok(size) => {
-            log::trace!("Wrote {} bytes", size);
-            true
+impl WriteQueue {
+    /// Queue `data` whole, or drop it when that would take the queue past `MAX_QUEUED`.
+    /// Returns whether it was queued.
+    pub fn push(&mut self, data: &[u8]) -> bool {
+        if self.pending.len() + data.len() > MAX_QUEUED {
+            return false;
         }
-        Err(e) => {
-            log::warn!("Failed to write to fd: {} (PTY may be closed)", e);
-            false
+        self.pending.extend(data);
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Write as much queued input as `write` accepts, stopping when it would block.
+    /// Errors other than `EAGAIN` and `EINTR` mean the PTY is gone.
+    pub fn flush(&mut self, mut write: impl FnMut(&[u8]) -> nix::Result<usize>) -> nix::Result<()> {
+        while !self.pending.is_empty() {
+            let (front, _) = self.pending.as_slices();
+            match write(front) {
+                Ok(0) | Err(Errno::EAGAIN) => break,
+                Ok(size) => {
+                    tracing::trace!(bytes = size, "pty write");
+                    self.pending.drain(..size);
+                }
+                Err(Errno::EINTR) => continue,
+                Err(e) => return Err(e),
+            }
         }
+        Ok(())
     }
 }
 
@@ -109,12 +158,20 @@ impl Term {
         let pty = openpty(None, Some(&winsize)).expect("Failed to open pty");
         let (master, slave) = (pty.controller, pty.user);
 
-        Self::from_fd(master, slave, &config.shell, &config.shell_args)
+        Self::from_fd(
+            master,
+            slave,
+            &config.shell,
+            &config.shell_args,
+            config.flow_control,
+        )
     }
 
     pub fn init(
         &self,
         is_running: Arc<AtomicBool>,
+        metrics: Arc<Metrics>,
+        inspector: Arc<Inspector>,
         client_channel: &ClientChannel,
         server_channel: &ServerChannel,
     ) {
@@ -122,6 +179,8 @@ impl Term {
         Self::spawn_read_thread(
             fd.as_raw_fd(),
             is_running.clone(),
+            metrics.clone(),
+            inspector,
             client_channel.output_transmitter.clone(),
         );
 
@@ -129,22 +188,32 @@ impl Term {
             fd,
             server_channel.input_receiver.resubscribe(),
             is_running.clone(),
+            metrics,
+            client_channel.output_transmitter.clone(),
         );
     }
 
     fn spawn_read_thread(
         fd: i32,
         read_exit_flag: Arc<AtomicBool>,
+        metrics: Arc<Metrics>,
+        inspector: Arc<Inspector>,
         output_tx: broadcast::Sender<ClientCommand>,
     ) {
         tokio::spawn(async move {
-            let mut processor: Processor = Processor::new();
-            let mut statemachine = statemachine::StateMachine::new(output_tx);
+            let mut parser = Parser::new();
+            let mut statemachine =
+                statemachine::StateMachine::new(output_tx).with_inspector(inspector);
 
             loop {
                 match read_from_raw_fd(fd) {
                     ReadResult::Data(data) => {
-                        processor.advance(&mut statemachine, &data);
+                        let _span =
+                            tracing::info_span!(target: SPAN_TARGET, "pty_read", bytes = data.len())
+                                .entered();
+                        metrics.add_bytes_read(data.len() as u64);
+                        tracing::info_span!(target: SPAN_TARGET, "parse")
+                            .in_scope(|| parser.advance(&mut statemachine, &data));
                     }
                     ReadResult::WouldBlock => {
                         // No data available, sleep briefly to avoid busy-looping
@@ -169,29 +238,77 @@ impl Term {
         write_fd: OwnedFd,
         mut input_rx: Receiver<ServerCommand>,
         exit_flag: Arc<AtomicBool>,
+        metrics: Arc<Metrics>,
+        output_tx: broadcast::Sender<ClientCommand>,
     ) {
         tokio::spawn(async move {
+            let mut queue = WriteQueue::default();
+            let mut output_paused = false;
+            // Input dropped since the queue last had room, reported once it has again
+            let mut dropped = 0;
             loop {
-                match input_rx.recv().await {
-                    Ok(ServerCommand::RawData(data)) => {
-                        if !write_to_fd(write_fd.as_fd(), &data) {
-                            // PTY is likely closed, exit the write thread
-                            exit_flag.store(true, Ordering::Relaxed);
-                            break;
+                let command = if queue.is_empty() {
+                    Some(input_rx.recv().await)
+                } else {
+                    // Keep accepting input while the PTY is full, retrying the queue
+                    // whenever nothing new arrives for a moment
+                    tokio::select! {
+                        command = input_rx.recv() => Some(command),
+                        _ = tokio::time::sleep(WRITE_RETRY) => None,
+                    }
+                };
+
+                let written = match command {
+                    Some(Ok(ServerCommand::RawData(data))) => {
+                        if queue.push(&data) {
+                            if dropped > 0 {
+                                log::warn!(
+                                    "Dropped {} bytes of input while the PTY was not reading",
+                                    dropped
+                                );
+                                dropped = 0;
+                            }
+                        } else {
+                            dropped += data.len();
                         }
+                        let written = queue.flush(|bytes| write(write_fd.as_fd(), bytes));
+                        if output_paused || data.contains(&XOFF) {
+                            match flow_control_change(write_fd.as_fd(), &data) {
+                                Some(paused) if paused != output_paused => {
+                                    output_paused = paused;
+                                    let _ = output_tx.send(ClientCommand::OutputPaused(paused));
+                                }
+                                _ => {}
+                            }
+                        }
+                        written
                     }
-                    Ok(ServerCommand::Resize(cols, rows, width, height)) => {
+                    Some(Ok(ServerCommand::Resize(cols, rows, width, height))) => {
                         if !resize_terminal(write_fd.as_fd(), cols, rows, width, height) {
                             // PTY is likely closed, exit the write thread
                             exit_flag.store(true, Ordering::Relaxed);
                             break;
                         }
+                        Ok(())
+                    }
+                    Some(Err(RecvError::Lagged(count))) => {
+                        log::warn!("Write thread lagged, {} input commands were lost", count);
+                        Ok(())
                     }
-                    Err(e) => {
-                        log::warn!("Write thread channel error: {}", e);
+                    Some(Err(RecvError::Closed)) => {
+                        log::warn!("Write thread channel closed");
                         break;
                     }
+                    None => queue.flush(|bytes| write(write_fd.as_fd(), bytes)),
+                };
+
+                if let Err(e) = written {
+                    // PTY is likely closed, exit the write thread
+                    log::warn!("Failed to write to fd: {} (PTY may be closed)", e);
+                    exit_flag.store(true, Ordering::Relaxed);
+                    break;
                 }
+                metrics.set_pty_write_queue(queue.len() as u64);
 
                 if exit_flag.load(Ordering::Relaxed) {
                     break;
@@ -205,11 +322,16 @@ impl Term {
         slave: OwnedFd,
         shell: &str,
         shell_args: &[String],
+        flow_control: bool,
     ) -> Result<Term, Error> {
         let master_fd = master.as_raw_fd();
         let slave_fd = slave.as_raw_fd();
         if let Ok(mut termios) = termios::tcgetattr(&master) {
             enable_raw_mode(&mut termios);
+            if flow_control {
+                // Let the line discipline act on Ctrl+S/Ctrl+Q
+                termios.input_modes.insert(termios::InputModes::IXON);
+            }
 
             termios.input_modes.insert(termios::InputModes::IUTF8);
 
@@ -328,6 +450,31 @@ fn enable_raw_mode(termios: &mut Termios) {
     termios.control_modes.remove(termios::ControlModes::CS8);
 }
 
+/// XOFF (Ctrl+S) stops PTY output and XON (Ctrl+Q) restarts it while IXON is set
+const XOFF: u8 = 0x13;
+const XON: u8 = 0x11;
+
+/// Whether writing `data` stopped (true) or restarted (false) output, if the PTY honors XON/XOFF
+fn flow_control_change(fd: BorrowedFd, data: &[u8]) -> Option<bool> {
+    let modes = termios::tcgetattr(fd).ok()?.input_modes;
+    if !modes.contains(termios::InputModes::IXON) {
+        return None;
+    }
+    paused_after(data, modes.contains(termios::InputModes::IXANY))
+}
+
+/// Output state after the last flow control byte in `data`.
+///
+/// With IXANY, any other byte restarts output as well.
+fn paused_after(data: &[u8], any_restarts: bool) -> Option<bool> {
+    data.iter().rev().find_map(|&byte| match byte {
+        XOFF => Some(true),
+        XON => Some(false),
+        _ if any_restarts => Some(false),
+        _ => None,
+    })
+}
+
 pub fn resize_terminal(fd: BorrowedFd, cols: u16, rows: u16, width: u16, height: u16) -> bool {
     log::info!(
         "Resizing terminal to {} cols, {} rows, {} width, {} height",
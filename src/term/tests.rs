@@ -0,0 +1,191 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use nix::errno::Errno;
+use tokio::sync::broadcast::error::TryRecvError;
+
+use crate::{app::App, commands::ServerCommand, config::Config, terminal::Terminal};
+
+use super::{paused_after, WriteQueue, MAX_QUEUED};
+
+/// Run `script` under `/bin/sh -c` in a real PTY and apply everything the read
+/// thread parses to a headless terminal until the shell exits
+async fn run_script(script: &str) -> Terminal {
+    run_script_with_input(script, Vec::new()).await
+}
+
+/// Like `run_script`, first sending `input` to the shell as if typed
+async fn run_script_with_input(script: &str, input: Vec<u8>) -> Terminal {
+    let config = Config {
+        cols: 20,
+        rows: 5,
+        shell: "/bin/sh".to_string(),
+        shell_args: vec!["-c".to_string(), script.to_string()],
+        ..Config::default()
+    };
+    let mut terminal = Terminal::new(&config);
+    let mut app = App::new(config, Arc::new(AtomicBool::new(false)));
+    let deadline = Instant::now() + Duration::from_secs(10);
+    if !input.is_empty() {
+        let _ = app
+            .server_channel
+            .input_transmitter
+            .send(ServerCommand::RawData(input));
+    }
+
+    loop {
+        match app.client_channel.output_receiver.try_recv() {
+            Ok(command) => {
+                terminal.handle_command(command);
+                let responses = terminal.take_responses();
+                if !responses.is_empty() {
+                    let _ = app
+                        .server_channel
+                        .input_transmitter
+                        .send(ServerCommand::RawData(responses));
+                }
+            }
+            // The read thread sets this flag once the PTY reaches EOF
+            Err(TryRecvError::Empty) if app.is_running.load(Ordering::Relaxed) => break,
+            Err(TryRecvError::Empty) => {
+                assert!(Instant::now() < deadline, "shell did not exit in time");
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+            Err(TryRecvError::Lagged(_)) => panic!("read thread outran the test"),
+            Err(TryRecvError::Closed) => break,
+        }
+    }
+
+    let _ = app.term.child.wait();
+    terminal
+}
+
+fn row_text(terminal: &Terminal, row: usize) -> String {
+    let width = terminal.grid.width as usize;
+    terminal.grid.active_grid_ref()[row * width..(row + 1) * width]
+        .iter()
+        .map(|cell| cell.char)
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn shell_output_should_reach_grid() {
+    let terminal = run_script(r"printf 'hello\r\nworld'").await;
+
+    assert_eq!(row_text(&terminal, 0), "hello");
+    assert_eq!(row_text(&terminal, 1), "world");
+    assert_eq!(terminal.grid.cursor_pos, (1, 5));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn escape_sequences_should_be_applied_from_pty() {
+    let terminal =
+        run_script(r"printf 'abcdef\033[3D\033[K\033[3;2H\033[1;31mx\033[0m\033[?2004h'").await;
+
+    assert_eq!(row_text(&terminal, 0), "abc");
+    assert_eq!(row_text(&terminal, 2), " x");
+    assert!(terminal.bracketed_paste_mode);
+    assert_eq!(terminal.grid.check_invariants(), Ok(()));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn query_responses_should_be_written_back_to_pty() {
+    // The cursor position report for the origin is exactly six bytes; echo it
+    // back without the leading ESC so it lands on screen as text
+    let terminal = run_script(r#"printf '\033[6n'; r=$(head -c 6); printf '%s' "${r#?}""#).await;
+
+    assert_eq!(row_text(&terminal, 0), "[1;1R");
+}
+
+#[test]
+fn last_flow_control_byte_should_decide_paused_state() {
+    assert_eq!(paused_after(b"ls\x13", false), Some(true));
+    assert_eq!(paused_after(b"\x13x\x11", false), Some(false));
+    assert_eq!(paused_after(b"\x13x", false), Some(true));
+    assert_eq!(paused_after(b"\x13x", true), Some(false));
+    assert_eq!(paused_after(b"ls", false), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn input_larger_than_pty_buffer_should_be_written_completely() {
+    let input = vec![b'a'; 200_000];
+    let terminal =
+        run_script_with_input(r#"n=$(head -c 200000 | wc -c); printf '%s' $n"#, input).await;
+
+    assert_eq!(row_text(&terminal, 0).trim(), "200000");
+}
+
+#[test]
+fn partial_writes_should_keep_the_rest_queued() {
+    let mut queue = WriteQueue::default();
+    queue.push(b"hello ");
+    queue.push(b"world");
+    let mut written = Vec::new();
+
+    let result = queue.flush(|bytes| {
+        let size = bytes.len().min(4);
+        written.extend_from_slice(&bytes[..size]);
+        Ok(size)
+    });
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(written, b"hello world");
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn would_block_should_leave_data_queued_in_order() {
+    let mut queue = WriteQueue::default();
+    queue.push(b"abcdef");
+    let mut accepted = 2;
+
+    let result = queue.flush(|bytes| {
+        if accepted == 0 {
+            return Err(Errno::EAGAIN);
+        }
+        let size = bytes.len().min(accepted);
+        accepted -= size;
+        Ok(size)
+    });
+    assert_eq!(result, Ok(()));
+    assert_eq!(queue.len(), 4);
+
+    queue.push(b"gh");
+    let mut written = Vec::new();
+    let result = queue.flush(|bytes| {
+        written.extend_from_slice(bytes);
+        Ok(bytes.len())
+    });
+    assert_eq!(result, Ok(()));
+    assert_eq!(written, b"cdefgh");
+}
+
+#[test]
+fn write_errors_should_be_reported() {
+    let mut queue = WriteQueue::default();
+    queue.push(b"abc");
+
+    assert_eq!(queue.flush(|_| Err(Errno::EIO)), Err(Errno::EIO));
+    assert_eq!(queue.len(), 3);
+}
+
+#[test]
+fn full_queue_should_drop_input_until_it_drains() {
+    let mut queue = WriteQueue::default();
+    assert!(queue.push(&vec![b'a'; MAX_QUEUED - 1]));
+
+    assert!(!queue.push(b"bc"));
+    assert!(queue.push(b"b"));
+    assert_eq!(queue.len(), MAX_QUEUED);
+
+    let result = queue.flush(|bytes| Ok(bytes.len().min(10)));
+    assert_eq!(result, Ok(()));
+    assert!(queue.push(b"cd"));
+}
@@ -0,0 +1,469 @@
+use tokio::sync::broadcast::{self, error::TryRecvError, Receiver};
+
+use crate::{
+    commands::{ClientCommand, IdentifyTerminalMode, SgrAttribute},
+    config::Config,
+    grid::{Cell, Grid},
+    parser::Parser,
+    statemachine::StateMachine,
+    styles::Color,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Headless terminal state: the grid plus the modes set by the application.
+///
+/// Applies `ClientCommand`s from the parser without any window or GPU, so the same logic drives
+/// the UI, replay, tests and fuzzing. Replies to queries are buffered in `responses` for the
+/// frontend to write back to the PTY.
+pub struct Terminal {
+    pub grid: Grid,
+    /// Cursor keys application mode (DECCKM)
+    pub cursor_keys_mode: bool,
+    /// Bracketed paste mode
+    pub bracketed_paste_mode: bool,
+    /// Replies to queries (DA, DSR, ...) not yet written to the PTY
+    responses: Vec<u8>,
+}
+
+impl Terminal {
+    pub fn new(config: &Config) -> Self {
+        Self::with_grid(Grid::new(config))
+    }
+
+    pub fn with_grid(grid: Grid) -> Self {
+        Self {
+            grid,
+            cursor_keys_mode: false,
+            bracketed_paste_mode: false,
+            responses: Vec::new(),
+        }
+    }
+
+    /// Take the bytes that should be written back to the PTY
+    pub fn take_responses(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.responses)
+    }
+
+    fn respond(&mut self, data: Vec<u8>) {
+        self.responses.extend_from_slice(&data);
+    }
+
+    fn handle_sgr_attribute(&mut self, attribute: SgrAttribute) {
+        match attribute {
+            SgrAttribute::Reset => {
+                self.grid.styles.reset_attributes();
+            }
+            SgrAttribute::Bold => {
+                self.grid.styles.font_size = 20;
+            }
+            SgrAttribute::Dim => {
+                self.grid.styles.font_size = 14;
+            }
+            SgrAttribute::Italic => {
+                self.grid.styles.italic = true;
+            }
+            SgrAttribute::Underline => {
+                self.grid.styles.underline = true;
+            }
+            SgrAttribute::DoubleUnderline => {}
+            SgrAttribute::Undercurl => {}
+            SgrAttribute::DottedUnderline => {}
+            SgrAttribute::DashedUnderline => {}
+            SgrAttribute::BlinkSlow => {}
+            SgrAttribute::BlinkFast => {}
+            SgrAttribute::Reverse => {
+                self.grid.styles.reverse = true;
+            }
+            SgrAttribute::Hidden => {}
+            SgrAttribute::Strike => {}
+            SgrAttribute::CancelBold => {
+                self.grid.styles.font_size = 16;
+            }
+            SgrAttribute::CancelBoldDim => {
+                self.grid.styles.font_size = 16;
+            }
+            SgrAttribute::CancelItalic => {
+                self.grid.styles.italic = false;
+            }
+            SgrAttribute::CancelUnderline => {
+                self.grid.styles.underline = false;
+            }
+            SgrAttribute::CancelBlink => {}
+            SgrAttribute::CancelReverse => {
+                self.grid.styles.reverse = false;
+            }
+            SgrAttribute::CancelHidden => {}
+            SgrAttribute::Foreground(color) => {
+                tracing::debug!("SGR Foreground: {:?}", color);
+                self.grid.styles.active_text_color = color;
+            }
+            SgrAttribute::Background(color) => {
+                self.grid.styles.active_background_color = color;
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply a command from the parser to the grid and terminal modes
+    pub fn handle_command(&mut self, command: ClientCommand) {
+        let cols = self.grid.width;
+        match command {
+            ClientCommand::Backspace => {
+                self.grid.delete_character();
+            }
+            ClientCommand::CarriageReturn => {
+                self.grid.place_character_in_grid(cols, '\r');
+            }
+            ClientCommand::ClearScreen => {
+                self.grid.clear_screen();
+            }
+            ClientCommand::ClearScrollback => {
+                self.grid.clear_scrollback();
+            }
+            ClientCommand::ClearLineAfterCursor => {
+                let (row, col) = self.grid.cursor_pos;
+                self.clear_cells(row, col..self.grid.width as usize);
+            }
+            ClientCommand::ClearLineBeforeCursor => {
+                let (row, col) = self.grid.cursor_pos;
+                self.clear_cells(row, 0..col);
+            }
+            ClientCommand::ClearLine => {
+                let (row, _) = self.grid.cursor_pos;
+                self.clear_cells(row, 0..self.grid.width as usize);
+            }
+            ClientCommand::ClearBelow => {
+                // first clear after cursor
+                let (row, col) = self.grid.cursor_pos;
+                self.clear_cells(row, col..self.grid.width as usize);
+
+                // then clear below
+                for i in row + 1..self.grid.height as usize {
+                    self.clear_cells(i, 0..self.grid.width as usize);
+                }
+            }
+            ClientCommand::ClearAbove => {
+                // first clear before cursor
+                let (row, col) = self.grid.cursor_pos;
+                self.clear_cells(row, 0..col);
+
+                // then clear above
+                for i in 0..row {
+                    self.clear_cells(i, 0..self.grid.width as usize);
+                }
+            }
+            ClientCommand::ClearCount(count) => {
+                // ECH stops at the end of the line
+                let (row, col) = self.grid.cursor_pos;
+                let end = col
+                    .saturating_add(count as usize)
+                    .min(self.grid.width as usize);
+                self.clear_cells(row, col..end);
+            }
+            ClientCommand::DeleteChars(count) => {
+                self.grid.delete_chars(count as usize);
+            }
+            ClientCommand::DeleteLines(count) => {
+                self.grid.delete_lines(count as usize);
+            }
+            ClientCommand::IdentifyTerminal(mode) => match mode {
+                IdentifyTerminalMode::Primary => {
+                    self.respond(b"\x1b[?6c".to_vec());
+                }
+                IdentifyTerminalMode::Secondary => {
+                    let version = "0.0.1";
+                    let text = format!("\x1b[>0;{version};1c");
+                    self.respond(text.as_bytes().to_vec());
+                }
+            },
+            ClientCommand::LineFeed => {
+                let start = self.grid.line_start();
+                self.grid.set_pos(self.grid.cursor_pos.0 + 1, start);
+            }
+            ClientCommand::MoveCursor(x, y) => {
+                self.grid.goto(x as usize, y as usize);
+            }
+            ClientCommand::MoveCursorAbsoluteHorizontal(y) => {
+                let last_col = self.grid.width as usize - 1;
+                self.grid
+                    .set_pos(self.grid.cursor_pos.0, (y.max(0) as usize).min(last_col));
+            }
+            ClientCommand::MoveCursorHorizontal(y) => {
+                let (left, right) = self.grid.cursor_margins();
+                let new_y = offset(self.grid.cursor_pos.1, y).clamp(left, right);
+                self.grid.set_pos(self.grid.cursor_pos.0, new_y);
+            }
+            ClientCommand::MoveCursorVertical(x) => {
+                let new_x = offset(self.grid.cursor_pos.0, x);
+                self.grid.set_pos(new_x, self.grid.cursor_pos.1);
+            }
+            ClientCommand::NewLine => {
+                self.grid.place_character_in_grid(cols, '\n');
+            }
+            ClientCommand::Print(c) => {
+                let c = self.grid.map_charset(c);
+                self.grid.place_character_in_grid(cols, c);
+            }
+            ClientCommand::PutTab => {
+                let (row, col) = self.grid.cursor_pos;
+                let grid_len = self.grid.active_grid().len();
+                let width = self.grid.width as usize;
+                if col < width.saturating_sub(5) {
+                    let (fg, bg) = if self.grid.styles.reverse {
+                        (
+                            self.grid.styles.active_background_color,
+                            self.grid.styles.active_text_color,
+                        )
+                    } else {
+                        (
+                            self.grid.styles.active_text_color,
+                            self.grid.styles.active_background_color,
+                        )
+                    };
+                    for i in col..col + 4 {
+                        let index = row * width + i;
+                        if index < grid_len {
+                            self.grid.active_grid()[index] = Cell::new(' ', fg, bg);
+                            self.grid.set_pos(row, i + 1);
+                        }
+                    }
+                }
+            }
+            ClientCommand::ReportCursorPosition => self.respond(
+                format!(
+                    "\x1b[{};{}R",
+                    self.grid.cursor_pos.0 + 1,
+                    self.grid.cursor_pos.1 + 1
+                )
+                .as_bytes()
+                .to_vec(),
+            ),
+            // Indices past the 256-color palette (the dynamic colors) are ignored
+            ClientCommand::ResetColor(index) => {
+                if let Some(color) = self.grid.styles.color_array.get_mut(index) {
+                    *color = Color::DEFAULT_ARRAY[index];
+                }
+            }
+            ClientCommand::RestoreCursor => {
+                self.grid.restore_cursor();
+            }
+            ClientCommand::ReportCondition(healthy) => {
+                if healthy {
+                    self.respond(b"\x1b[0n".to_vec());
+                } else {
+                    self.respond(b"\x1b[3n".to_vec());
+                }
+            }
+            ClientCommand::ShowCursor => {
+                self.grid.show_cursor();
+            }
+            ClientCommand::SGR(command) => {
+                self.handle_sgr_attribute(command);
+            }
+            ClientCommand::CopyRectangle { source, top, left } => {
+                self.grid.copy_rectangle(source, top, left);
+            }
+            ClientCommand::FillRectangle(c, rect) => {
+                self.grid.fill_rectangle(rect, c);
+            }
+            ClientCommand::EraseRectangle(rect) => {
+                self.grid.erase_rectangle(rect);
+            }
+            ClientCommand::ReportRectangleChecksum { id, rect } => {
+                let checksum = self.grid.rectangle_checksum(rect);
+                let response = format!("\x1bP{}!~{:04X}\x1b\\", id, checksum);
+                self.respond(response.into_bytes());
+            }
+            ClientCommand::SaveCursor => {
+                self.grid.save_cursor();
+            }
+            // The window title, pixel size, bell and paused indicator are owned by the frontend
+            ClientCommand::SetTitle(_)
+            | ClientCommand::ReportTextAreaSizePixels
+            | ClientCommand::Bell
+            | ClientCommand::OutputPaused(_) => {}
+            ClientCommand::SwapScreenAndSetRestoreCursor(enter) => {
+                if enter {
+                    // Entering alternate screen: save cursor, switch, clear
+                    self.grid.save_cursor();
+                    self.grid.swap_active_grid();
+                    self.grid.clear_screen();
+                    self.grid.set_pos(0, 0);
+                } else {
+                    // Exiting alternate screen: switch back, restore cursor
+                    self.grid.swap_active_grid();
+                    self.grid.restore_cursor();
+                    self.grid.mark_all_dirty();
+                }
+            }
+            ClientCommand::SetColor(index, color) => {
+                if let Some(entry) = self.grid.styles.color_array.get_mut(index) {
+                    *entry = Color::Rgb(color.r, color.g, color.b);
+                }
+            }
+            ClientCommand::MoveCursorVerticalWithCarriageReturn(x) => {
+                let new_x = offset(self.grid.cursor_pos.0, x);
+                self.grid.set_pos(new_x, 0);
+            }
+            ClientCommand::HideCursor => {
+                self.grid.hide_cursor();
+            }
+            ClientCommand::SetCursorState(state) => {
+                // DECSCUSR leaves cursor visibility (DECTCEM) alone
+                self.grid.styles.cursor_state.shape = state.shape;
+                self.grid.styles.cursor_state.blinking = state.blinking;
+                self.grid.mark_cursor_row_dirty();
+            }
+            ClientCommand::SetCursorShape(shape) => {
+                self.grid.styles.cursor_state.shape = shape;
+            }
+            ClientCommand::SetHyperlink(uri) => {
+                self.grid.set_hyperlink(uri.as_deref());
+            }
+            ClientCommand::ResetCursorState => {
+                let default = self.grid.styles.default_cursor_state;
+                self.grid.styles.cursor_state.shape = default.shape;
+                self.grid.styles.cursor_state.blinking = default.blinking;
+                self.grid.mark_cursor_row_dirty();
+            }
+            ClientCommand::CursorKeysMode(enabled) => {
+                self.cursor_keys_mode = enabled;
+            }
+            ClientCommand::BracketedPasteMode(enabled) => {
+                self.bracketed_paste_mode = enabled;
+            }
+            ClientCommand::ScrollUp(count) => {
+                self.grid.scroll_up(count as usize);
+            }
+            ClientCommand::ScrollDown(count) => {
+                self.grid.scroll_down(count as usize);
+            }
+            ClientCommand::InsertBlankLines(count) => {
+                self.grid.insert_blank_lines(count as usize);
+            }
+            ClientCommand::SetScrollingRegion(top, bottom) => {
+                self.grid.set_scroll_region(top, bottom);
+            }
+            ClientCommand::LeftRightMarginMode(enabled) => {
+                self.grid.set_left_right_margin_mode(enabled);
+            }
+            ClientCommand::SetLeftRightMargins(left, right) => {
+                self.grid.set_left_right_margins(left, right);
+            }
+            ClientCommand::OriginMode(enabled) => {
+                self.grid.set_origin_mode(enabled);
+            }
+            ClientCommand::ConfigureCharset(index, charset) => {
+                self.grid.configure_charset(index, charset);
+            }
+            ClientCommand::SetActiveCharset(index) => {
+                self.grid.set_active_charset(index);
+            }
+            ClientCommand::ReverseIndex => {
+                self.grid.reverse_index();
+            }
+            ClientCommand::InsertBlanks(count) => {
+                self.grid.insert_blanks(count as usize);
+            }
+            ClientCommand::SetDefaultForeground(rgb) => {
+                self.grid.styles.default_text_color = Color::Rgb(rgb.r, rgb.g, rgb.b);
+                // Also update active if it's currently using the default
+                if matches!(self.grid.styles.active_text_color, Color::Foreground) {
+                    self.grid.styles.active_text_color = Color::Foreground;
+                }
+                self.grid.mark_all_dirty();
+            }
+            ClientCommand::SetDefaultBackground(rgb) => {
+                self.grid.styles.default_background_color = Color::Rgb(rgb.r, rgb.g, rgb.b);
+                // Also update active if it's currently using the default
+                if matches!(self.grid.styles.active_background_color, Color::Background) {
+                    self.grid.styles.active_background_color = Color::Background;
+                }
+                self.grid.mark_all_dirty();
+            }
+            ClientCommand::ReportTextAreaSizeChars => {
+                // CSI 8 ; rows ; cols t - Report text area size in characters
+                let response = format!("\x1b[8;{};{}t", self.grid.height, self.grid.width);
+                self.respond(response.as_bytes().to_vec());
+            }
+            _ => {
+                log::info!("Unsupported command: {:?}", command);
+            }
+        }
+    }
+
+    fn clear_cells(&mut self, row: usize, col_range: std::ops::Range<usize>) {
+        let grid_len = self.grid.active_grid().len();
+        let width = self.grid.width as usize;
+
+        let start_index = row * width + col_range.start;
+        let end_index = row * width + col_range.end;
+
+        // Bounds check to prevent panics after resize
+        if start_index >= grid_len {
+            return;
+        }
+        let end_index = std::cmp::min(end_index, grid_len);
+
+        let (fg, bg) = if self.grid.styles.reverse {
+            (
+                self.grid.styles.active_background_color,
+                self.grid.styles.active_text_color,
+            )
+        } else {
+            (
+                self.grid.styles.active_text_color,
+                self.grid.styles.active_background_color,
+            )
+        };
+
+        for i in start_index..end_index {
+            self.grid.active_grid()[i] = Cell::new(' ', fg, bg);
+        }
+    }
+}
+
+/// Apply a relative cursor move without going above row or column zero
+fn offset(pos: usize, delta: i16) -> usize {
+    pos.saturating_add_signed(delta as isize)
+}
+
+/// Bytes parsed before draining the command channel, keeping it well below capacity
+const FEED_CHUNK: usize = 256;
+
+/// Runs PTY output through the parser into a `Terminal` synchronously, without a PTY or threads
+pub struct HeadlessPipeline {
+    parser: Parser,
+    statemachine: StateMachine,
+    rx: Receiver<ClientCommand>,
+    pub terminal: Terminal,
+}
+
+impl HeadlessPipeline {
+    pub fn new(config: &Config) -> Self {
+        let (tx, rx) = broadcast::channel(FEED_CHUNK * 16);
+        Self {
+            parser: Parser::new(),
+            statemachine: StateMachine::new(tx),
+            rx,
+            terminal: Terminal::new(config),
+        }
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(FEED_CHUNK) {
+            self.parser.advance(&mut self.statemachine, chunk);
+            loop {
+                match self.rx.try_recv() {
+                    Ok(command) => self.terminal.handle_command(command),
+                    Err(TryRecvError::Lagged(n)) => {
+                        log::warn!("Headless pipeline lagged, {} commands dropped", n)
+                    }
+                    Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => break,
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use crate::{
+    commands::ClientCommand,
+    config::{Config, CursorStyle},
+    styles::CursorShape,
+};
+
+use super::{HeadlessPipeline, Terminal};
+
+fn config() -> Config {
+    Config {
+        cols: 20,
+        rows: 5,
+        ..Config::default()
+    }
+}
+
+fn row_text(terminal: &Terminal, row: usize) -> String {
+    let width = terminal.grid.width as usize;
+    terminal.grid.active_grid_ref()[row * width..(row + 1) * width]
+        .iter()
+        .map(|cell| cell.char)
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+#[test]
+fn print_should_place_characters_at_cursor() {
+    let mut terminal = Terminal::new(&config());
+
+    for c in "hi".chars() {
+        terminal.handle_command(ClientCommand::Print(c));
+    }
+
+    assert_eq!(row_text(&terminal, 0), "hi");
+    assert_eq!(terminal.grid.cursor_pos, (0, 2));
+}
+
+#[test]
+fn queries_should_buffer_responses() {
+    let mut terminal = Terminal::new(&config());
+    terminal.handle_command(ClientCommand::MoveCursor(1, 3));
+
+    terminal.handle_command(ClientCommand::ReportCursorPosition);
+
+    assert_eq!(terminal.take_responses(), b"\x1b[2;4R");
+    assert!(terminal.take_responses().is_empty());
+}
+
+#[test]
+fn pipeline_should_apply_parsed_output() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+
+    pipeline.feed(b"one\r\ntwo\x1b[1;6Hx\x1b[?1h");
+
+    assert_eq!(row_text(&pipeline.terminal, 0), "one  x");
+    assert_eq!(row_text(&pipeline.terminal, 1), "two");
+    assert!(pipeline.terminal.cursor_keys_mode);
+    assert_eq!(pipeline.terminal.grid.check_invariants(), Ok(()));
+}
+
+#[test]
+fn random_input_should_keep_grid_invariants() {
+    const ALPHABET: &[u8] =
+        b"\x1b[]();?0123456789ABCDHJKLMPSTXZfhlmnrsu\r\n\x08\x07\x09 x\xe2\x94\x80";
+    let mut pipeline = HeadlessPipeline::new(&config());
+    // Small xorshift generator so failures are reproducible
+    let mut state: u32 = 0x9e37_79b9;
+
+    for _ in 0..200 {
+        let chunk: Vec<u8> = (0..64)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                ALPHABET[state as usize % ALPHABET.len()]
+            })
+            .collect();
+        pipeline.feed(&chunk);
+
+        assert_eq!(pipeline.terminal.grid.check_invariants(), Ok(()));
+    }
+}
+
+#[test]
+fn relative_moves_should_stay_inside_screen() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+
+    pipeline.feed(b"\x1b[5A\x1b[99C");
+    assert_eq!(pipeline.terminal.grid.cursor_pos, (0, 19));
+
+    pipeline.feed(b"\x1b[99D\x1b[F");
+    assert_eq!(pipeline.terminal.grid.cursor_pos, (0, 0));
+    assert_eq!(pipeline.terminal.grid.check_invariants(), Ok(()));
+}
+
+#[test]
+fn rectangle_operations_should_edit_screen_area() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+    pipeline.feed(b"abcd\r\nefgh\r\nijkl");
+
+    // Fill a 2x2 block with '*', copy the top-left 2x2 block to row 3 column 5, erase 'k'
+    pipeline.feed(b"\x1b[42;2;2;3;3$x\x1b[1;1;2;2;1;3;5;1$v\x1b[3;3;3;3$z");
+
+    assert_eq!(row_text(&pipeline.terminal, 0), "abcd");
+    assert_eq!(row_text(&pipeline.terminal, 1), "e**h");
+    assert_eq!(row_text(&pipeline.terminal, 2), "i* lab");
+    assert_eq!(row_text(&pipeline.terminal, 3), "    e*");
+    assert_eq!(pipeline.terminal.grid.check_invariants(), Ok(()));
+}
+
+#[test]
+fn rectangle_checksum_should_report_negated_character_sum() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+    pipeline.feed(b"AB");
+
+    // 'A' + 'B' = 0x83, negated in 16 bits
+    pipeline.feed(b"\x1b[7;1;1;1;1;2*y");
+
+    assert_eq!(pipeline.terminal.take_responses(), b"\x1bP7!~FF7D\x1b\\");
+}
+
+#[test]
+fn left_right_margins_should_confine_wrapping_and_scrolling() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+    pipeline.feed(b"\x1b[2;1HX");
+
+    // Margins at columns 3-5, then print past the right margin
+    pipeline.feed(b"\x1b[?69h\x1b[3;5s\x1b[1;3Habcdef");
+
+    assert_eq!(row_text(&pipeline.terminal, 0), "  abc");
+    assert_eq!(row_text(&pipeline.terminal, 1), "X def");
+
+    // Scrolling only moves the cells between the margins
+    pipeline.feed(b"\x1b[S");
+
+    assert_eq!(row_text(&pipeline.terminal, 0), "  def");
+    assert_eq!(row_text(&pipeline.terminal, 1), "X");
+    assert_eq!(pipeline.terminal.grid.check_invariants(), Ok(()));
+}
+
+#[test]
+fn restore_cursor_should_restore_attributes_and_charsets() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+
+    // Save with reverse video and line drawing in G0, then reset both
+    pipeline.feed(b"\x1b[2;3H\x1b[7m\x1b(0\x1b7\x1b[m\x1b(B\x1b[H");
+    pipeline.feed(b"\x1b8q");
+
+    let grid = &pipeline.terminal.grid;
+    assert_eq!(row_text(&pipeline.terminal, 1), "  ─");
+    assert_eq!(grid.cursor_pos, (1, 3));
+    assert!(grid.styles.reverse);
+}
+
+#[test]
+fn saved_cursor_should_be_kept_per_screen() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+    pipeline.feed(b"\x1b[3;4H\x1b7");
+
+    // The alternate screen has its own saved state
+    pipeline.feed(b"\x1b[?1049h\x1b[2;2H\x1b7\x1b[H\x1b8");
+    assert_eq!(pipeline.terminal.grid.cursor_pos, (1, 1));
+
+    pipeline.feed(b"\x1b[?1049l\x1b[H\x1b8");
+    assert_eq!(pipeline.terminal.grid.cursor_pos, (2, 3));
+}
+
+#[test]
+fn absolute_moves_should_stay_inside_screen() {
+    let mut pipeline = HeadlessPipeline::new(&Config {
+        cols: 5,
+        rows: 3,
+        ..Config::default()
+    });
+
+    pipeline.feed(b"\x1b[11`");
+    assert_eq!(pipeline.terminal.grid.cursor_pos, (0, 4));
+    assert_eq!(pipeline.terminal.grid.check_invariants(), Ok(()));
+
+    pipeline.feed(b"\x1b[9;9H");
+    assert_eq!(pipeline.terminal.grid.cursor_pos, (2, 4));
+
+    pipeline.feed(b"\x1b[1;1H\x1b[99d");
+    assert_eq!(pipeline.terminal.grid.cursor_pos.0, 2);
+    assert_eq!(pipeline.terminal.grid.check_invariants(), Ok(()));
+}
+
+#[test]
+fn scrolling_region_below_screen_should_be_ignored() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+
+    pipeline.feed(b"\x1b[6r\x1b[S\x1b[7r\x1b[20S\x1b[3;3r\x1b[T");
+
+    assert_eq!(pipeline.terminal.grid.get_scroll_region(), (0, 4));
+    assert_eq!(pipeline.terminal.grid.check_invariants(), Ok(()));
+}
+
+#[test]
+fn deleting_every_line_should_clear_the_region() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+    pipeline.feed(b"one\r\ntwo");
+
+    pipeline.feed(b"\x1b[H\x1b[9M");
+
+    assert_eq!(row_text(&pipeline.terminal, 0), "");
+    assert_eq!(row_text(&pipeline.terminal, 1), "");
+    assert_eq!(pipeline.terminal.grid.check_invariants(), Ok(()));
+}
+
+#[test]
+fn huge_insert_count_should_blank_the_rest_of_the_line() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+    pipeline.feed(b"abcdef");
+
+    pipeline.feed(b"\x1b[1;3H\x1b[54633333333@");
+
+    assert_eq!(row_text(&pipeline.terminal, 0), "ab");
+    assert_eq!(pipeline.terminal.grid.check_invariants(), Ok(()));
+}
+
+#[test]
+fn dynamic_color_resets_should_not_touch_the_palette() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+
+    // OSC 110-112 reset the foreground, background and cursor colors, past the 256 entries
+    pipeline.feed(b"\x1b]110\x07\x1b]111\x07\x1b]112\x07\x1b]10;rgb:ff/00/00\x07");
+
+    assert_eq!(pipeline.terminal.grid.check_invariants(), Ok(()));
+}
+
+#[test]
+fn erase_characters_should_stop_at_the_end_of_the_line() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+    pipeline.feed(b"0123456789012345678\r\nnext");
+
+    pipeline.feed(b"\x1b[1;16H\x1b[99X\x1b[2;3H\x1b[133333333333X");
+
+    assert_eq!(row_text(&pipeline.terminal, 0), "012345678901234");
+    assert_eq!(row_text(&pipeline.terminal, 1), "ne");
+    assert_eq!(pipeline.terminal.grid.check_invariants(), Ok(()));
+}
+
+#[test]
+fn origin_mode_should_address_inside_scrolling_region() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+
+    pipeline.feed(b"\x1b[2;4r\x1b[?6h\x1b[2;5H");
+    assert_eq!(pipeline.terminal.grid.cursor_pos, (2, 4));
+
+    pipeline.feed(b"\x1b[9;1H");
+    assert_eq!(pipeline.terminal.grid.cursor_pos, (3, 0));
+}
+
+#[test]
+fn cursor_style_should_set_blinking_without_showing_cursor() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+
+    pipeline.feed(b"\x1b[?25l\x1b[5 q");
+    let state = pipeline.terminal.grid.styles.cursor_state;
+    assert!(state.blinking);
+    assert!(state.hidden);
+
+    pipeline.feed(b"\x1b[2 q");
+    assert!(!pipeline.terminal.grid.styles.cursor_state.blinking);
+}
+
+#[test]
+fn clear_scrollback_should_keep_the_screen() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+    pipeline.feed(b"1\r\n2\r\n3\r\n4\r\n5\r\n6\r\n7\r\n8\r\n9");
+    assert_eq!(pipeline.terminal.grid.scrollback_rows(), 4);
+
+    pipeline.feed(b"\x1b[3J");
+
+    let terminal = &pipeline.terminal;
+    assert_eq!(terminal.grid.scrollback_rows(), 0);
+    assert_eq!(row_text(terminal, 0), "5");
+    assert_eq!(row_text(terminal, 4), "9");
+    assert_eq!(terminal.grid.cursor_pos, (4, 1));
+    assert_eq!(terminal.grid.check_invariants(), Ok(()));
+}
+
+#[test]
+fn scroll_view_should_stay_inside_the_buffer() {
+    let mut pipeline = HeadlessPipeline::new(&config());
+    pipeline.feed(b"1\r\n2\r\n3\r\n4\r\n5\r\n6\r\n7");
+    let grid = &mut pipeline.terminal.grid;
+
+    grid.scroll_view(-1);
+    assert_eq!(grid.bottom_row(), 5);
+    grid.scroll_view(-10);
+    assert_eq!(grid.bottom_row(), 4);
+    grid.scroll_view(10);
+    assert_eq!(grid.bottom_row(), 6);
+    assert_eq!(grid.check_invariants(), Ok(()));
+}
+
+#[test]
+fn decscusr_zero_should_restore_the_configured_cursor() {
+    let mut pipeline = HeadlessPipeline::new(&Config {
+        cursor_style: CursorStyle::Underline,
+        cursor_blinking: true,
+        ..config()
+    });
+    let cursor = |pipeline: &HeadlessPipeline| pipeline.terminal.grid.styles.cursor_state;
+    assert!(matches!(cursor(&pipeline).shape, CursorShape::Underline));
+    assert!(cursor(&pipeline).blinking);
+
+    pipeline.feed(b"\x1b[2 q");
+    assert!(matches!(cursor(&pipeline).shape, CursorShape::Block));
+    assert!(!cursor(&pipeline).blinking);
+
+    pipeline.feed(b"\x1b[0 q");
+    assert!(matches!(cursor(&pipeline).shape, CursorShape::Underline));
+    assert!(cursor(&pipeline).blinking);
+}
@@ -0,0 +1,194 @@
+use crate::styles::{Color, Styles};
+
+#[cfg(test)]
+mod tests;
+
+/// A built-in color scheme: default colors plus the 16 named palette entries
+pub struct Theme {
+    pub name: &'static str,
+    pub foreground: (u8, u8, u8),
+    pub background: (u8, u8, u8),
+    /// Background of the cursor's row when the cursor line highlight is on
+    pub cursor_line: (u8, u8, u8),
+    pub palette: [(u8, u8, u8); 16],
+}
+
+impl Theme {
+    /// Replace the default colors and named palette entries in `styles`.
+    ///
+    /// Cells store palette references rather than resolved colors, so existing content picks up
+    /// the theme on the next redraw.
+    pub fn apply(&self, styles: &mut Styles) {
+        for (entry, &(r, g, b)) in styles.color_array.iter_mut().zip(&self.palette) {
+            *entry = Color::Rgb(r, g, b);
+        }
+        let (r, g, b) = self.foreground;
+        styles.default_text_color = Color::Rgb(r, g, b);
+        let (r, g, b) = self.background;
+        styles.default_background_color = Color::Rgb(r, g, b);
+        let (r, g, b) = self.cursor_line;
+        styles.cursor_line_color = Color::Rgb(r, g, b);
+    }
+}
+
+/// Look up a built-in theme by name (case-insensitive)
+pub fn find(name: &str) -> Option<&'static Theme> {
+    THEMES
+        .iter()
+        .find(|theme| theme.name.eq_ignore_ascii_case(name))
+}
+
+/// Names of all built-in themes
+pub fn names() -> Vec<&'static str> {
+    THEMES.iter().map(|theme| theme.name).collect()
+}
+
+/// Pure black and white with saturated colors, used by high-contrast mode
+pub const HIGH_CONTRAST: Theme = Theme {
+    name: "high-contrast",
+    foreground: (255, 255, 255),
+    background: (0, 0, 0),
+    cursor_line: (40, 40, 40),
+    palette: [
+        (0, 0, 0),
+        (255, 80, 80),
+        (0, 255, 0),
+        (255, 255, 0),
+        (90, 160, 255),
+        (255, 100, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+        (200, 200, 200),
+        (255, 130, 130),
+        (120, 255, 120),
+        (255, 255, 140),
+        (140, 190, 255),
+        (255, 150, 255),
+        (140, 255, 255),
+        (255, 255, 255),
+    ],
+};
+
+pub const THEMES: &[Theme] = &[
+    Theme {
+        name: "default",
+        foreground: (229, 229, 229),
+        background: (0, 0, 0),
+        cursor_line: (28, 28, 28),
+        palette: [
+            (0, 0, 0),
+            (205, 49, 49),
+            (13, 188, 121),
+            (229, 229, 16),
+            (36, 114, 200),
+            (188, 63, 188),
+            (17, 168, 205),
+            (229, 229, 229),
+            (102, 102, 102),
+            (241, 76, 76),
+            (35, 209, 139),
+            (245, 245, 67),
+            (59, 142, 234),
+            (214, 112, 214),
+            (41, 184, 219),
+            (255, 255, 255),
+        ],
+    },
+    Theme {
+        name: "gruvbox",
+        foreground: (235, 219, 178),
+        background: (40, 40, 40),
+        cursor_line: (60, 56, 54),
+        palette: [
+            (40, 40, 40),
+            (204, 36, 29),
+            (152, 151, 26),
+            (215, 153, 33),
+            (69, 133, 136),
+            (177, 98, 134),
+            (104, 157, 106),
+            (168, 153, 132),
+            (146, 131, 116),
+            (251, 73, 52),
+            (184, 187, 38),
+            (250, 189, 47),
+            (131, 165, 152),
+            (211, 134, 155),
+            (142, 192, 124),
+            (235, 219, 178),
+        ],
+    },
+    Theme {
+        name: "solarized-dark",
+        foreground: (131, 148, 150),
+        background: (0, 43, 54),
+        cursor_line: (7, 54, 66),
+        palette: [
+            (7, 54, 66),
+            (220, 50, 47),
+            (133, 153, 0),
+            (181, 137, 0),
+            (38, 139, 210),
+            (211, 54, 130),
+            (42, 161, 152),
+            (238, 232, 213),
+            (0, 43, 54),
+            (203, 75, 22),
+            (88, 110, 117),
+            (101, 123, 131),
+            (131, 148, 150),
+            (108, 113, 196),
+            (147, 161, 161),
+            (253, 246, 227),
+        ],
+    },
+    Theme {
+        name: "dracula",
+        foreground: (248, 248, 242),
+        background: (40, 42, 54),
+        cursor_line: (68, 71, 90),
+        palette: [
+            (33, 34, 44),
+            (255, 85, 85),
+            (80, 250, 123),
+            (241, 250, 140),
+            (189, 147, 249),
+            (255, 121, 198),
+            (139, 233, 253),
+            (248, 248, 242),
+            (98, 114, 164),
+            (255, 110, 110),
+            (105, 255, 148),
+            (255, 255, 165),
+            (214, 172, 255),
+            (255, 146, 223),
+            (164, 255, 255),
+            (255, 255, 255),
+        ],
+    },
+    Theme {
+        name: "nord",
+        foreground: (216, 222, 233),
+        background: (46, 52, 64),
+        cursor_line: (59, 66, 82),
+        palette: [
+            (59, 66, 82),
+            (191, 97, 106),
+            (163, 190, 140),
+            (235, 203, 139),
+            (129, 161, 193),
+            (180, 142, 173),
+            (136, 192, 208),
+            (229, 233, 240),
+            (76, 86, 106),
+            (191, 97, 106),
+            (163, 190, 140),
+            (235, 203, 139),
+            (129, 161, 193),
+            (180, 142, 173),
+            (143, 188, 187),
+            (236, 239, 244),
+        ],
+    },
+    HIGH_CONTRAST,
+];
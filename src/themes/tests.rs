@@ -0,0 +1,60 @@
+use crate::styles::{Color, Styles};
+
+use super::{find, THEMES};
+
+#[test]
+fn find_should_ignore_case() {
+    assert_eq!(find("Gruvbox").map(|theme| theme.name), Some("gruvbox"));
+    assert!(find("no-such-theme").is_none());
+}
+
+#[test]
+fn apply_should_recolor_defaults_and_named_colors() {
+    let mut styles = Styles::default();
+
+    find("gruvbox").unwrap().apply(&mut styles);
+
+    assert_eq!(styles.to_rgb(Color::Background), (40, 40, 40));
+    assert_eq!(styles.to_rgb(Color::Foreground), (235, 219, 178));
+    assert_eq!(styles.to_rgb(Color::Red), (204, 36, 29));
+    // The 256-color cube is left alone
+    assert_eq!(
+        styles.to_rgb(Color::ColorIndex(16)),
+        Styles::default().to_rgb(Color::ColorIndex(16))
+    );
+}
+
+#[test]
+fn default_theme_should_match_builtin_palette() {
+    let mut styles = Styles::default();
+    find("dracula").unwrap().apply(&mut styles);
+
+    find("default").unwrap().apply(&mut styles);
+
+    let builtin = Styles::default();
+    for index in 0..16 {
+        assert_eq!(
+            styles.to_rgb(Color::ColorIndex(index)),
+            builtin.to_rgb(Color::ColorIndex(index))
+        );
+    }
+    assert_eq!(
+        styles.to_rgb(Color::Foreground),
+        builtin.to_rgb(Color::Foreground)
+    );
+    assert_eq!(
+        styles.to_rgb(Color::Background),
+        builtin.to_rgb(Color::Background)
+    );
+}
+
+#[test]
+fn cursor_line_should_stand_out_from_background_in_every_theme() {
+    for theme in THEMES {
+        assert_ne!(theme.cursor_line, theme.background, "{}", theme.name);
+
+        let mut styles = Styles::default();
+        theme.apply(&mut styles);
+        assert_eq!(styles.to_rgb(styles.cursor_line_color), theme.cursor_line);
+    }
+}
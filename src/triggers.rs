@@ -0,0 +1,227 @@
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use regex::Regex;
+
+use crate::config::{TriggerAction, TriggerConfig};
+
+#[cfg(test)]
+mod tests;
+
+/// Shortest time between two notifications, sounds or commands started by the same trigger.
+/// Matches in between are dropped, so a build log full of errors starts one, not thousands.
+pub const TRIGGER_COOLDOWN: Duration = Duration::from_secs(1);
+
+/// Notification, sound and command processes allowed to run at once
+const MAX_RUNNING: usize = 8;
+
+static RUNNING: ProcessLimit = ProcessLimit::new(MAX_RUNNING);
+
+struct Trigger {
+    regex: Regex,
+    action: TriggerAction,
+    /// When this trigger last started a process
+    last_started: Option<Instant>,
+}
+
+/// Compiled output triggers from the config
+#[derive(Default)]
+pub struct Triggers {
+    rules: Vec<Trigger>,
+}
+
+impl Triggers {
+    /// Compile the configured triggers, skipping (and logging) invalid patterns
+    pub fn new(configs: &[TriggerConfig]) -> Self {
+        let rules = configs
+            .iter()
+            .filter_map(|config| match Regex::new(&config.pattern) {
+                Ok(regex) => Some(Trigger {
+                    regex,
+                    action: config.action.clone(),
+                    last_started: None,
+                }),
+                Err(e) => {
+                    log::warn!(
+                        "Ignoring trigger with invalid pattern {:?}: {}",
+                        config.pattern,
+                        e
+                    );
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Actions of every trigger matching `line`, in config order
+    pub fn matches<'a>(&'a self, line: &'a str) -> impl Iterator<Item = &'a TriggerAction> {
+        self.rules
+            .iter()
+            .filter(move |rule| rule.regex.is_match(line))
+            .map(|rule| &rule.action)
+    }
+
+    /// Actions to run for `line`: like `matches`, but leaving out actions that start a process
+    /// when their trigger started one less than `TRIGGER_COOLDOWN` ago
+    pub fn fire(&mut self, line: &str, now: Instant) -> Vec<&TriggerAction> {
+        let mut actions = Vec::new();
+        for rule in &mut self.rules {
+            if !rule.regex.is_match(line) {
+                continue;
+            }
+            if !matches!(rule.action, TriggerAction::Highlight { .. }) {
+                if rule
+                    .last_started
+                    .is_some_and(|last| now.duration_since(last) < TRIGGER_COOLDOWN)
+                {
+                    log::debug!("Trigger {:?} cooling down, skipping", rule.regex.as_str());
+                    continue;
+                }
+                rule.last_started = Some(now);
+            }
+            actions.push(&rule.action);
+        }
+        actions
+    }
+}
+
+/// Counts running processes against a maximum
+pub struct ProcessLimit {
+    running: AtomicUsize,
+    max: usize,
+}
+
+impl ProcessLimit {
+    pub const fn new(max: usize) -> Self {
+        Self {
+            running: AtomicUsize::new(0),
+            max,
+        }
+    }
+
+    /// Take a slot, or return false when all are taken
+    pub fn acquire(&self) -> bool {
+        self.running
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |running| {
+                (running < self.max).then_some(running + 1)
+            })
+            .is_ok()
+    }
+
+    pub fn release(&self) {
+        self.running.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Show a desktop notification for a matched line or a monitor alert
+pub fn notify(line: &str) {
+    #[cfg(target_os = "macos")]
+    let command = {
+        let mut command = Command::new("osascript");
+        // Pass the line as an argument so it never needs AppleScript quoting
+        command.args([
+            "-e",
+            "on run argv",
+            "-e",
+            "display notification (item 1 of argv) with title \"MTTY\"",
+            "-e",
+            "end run",
+            "--",
+            line,
+        ]);
+        command
+    };
+    #[cfg(not(target_os = "macos"))]
+    let command = {
+        let mut command = Command::new("notify-send");
+        // The line comes from program output; `--` keeps a leading `-` from reading as an option
+        command.args(["--", "MTTY", line]);
+        command
+    };
+
+    spawn_limited(command);
+}
+
+/// Play `file`, or the system alert sound when no file is given
+pub fn play_sound(file: Option<&Path>) {
+    #[cfg(target_os = "macos")]
+    let command = {
+        let mut command = Command::new("afplay");
+        match file {
+            Some(file) => command.arg(file),
+            None => command.arg("/System/Library/Sounds/Glass.aiff"),
+        };
+        command
+    };
+    #[cfg(not(target_os = "macos"))]
+    let command = match file {
+        Some(file) => {
+            let mut command = Command::new("paplay");
+            command.arg(file);
+            command
+        }
+        None => {
+            let mut command = Command::new("canberra-gtk-play");
+            command.args(["-i", "bell"]);
+            command
+        }
+    };
+
+    spawn_limited(command);
+}
+
+/// Run a user command through `sh -c`, exposing the line as `MTTY_TRIGGER_LINE`
+pub fn run_command(command: &str, line: &str) {
+    let mut shell = Command::new("sh");
+    shell.args(["-c", command]).env("MTTY_TRIGGER_LINE", line);
+    spawn_limited(shell);
+}
+
+/// Start `command` without blocking the UI and reap it from a helper thread
+pub fn spawn_detached(command: Command) {
+    spawn(command, || {});
+}
+
+/// Like `spawn_detached`, but dropped when `MAX_RUNNING` trigger processes are still running
+fn spawn_limited(command: Command) {
+    if !RUNNING.acquire() {
+        log::warn!(
+            "Not running {:?}: {} trigger processes are still running",
+            command,
+            MAX_RUNNING
+        );
+        return;
+    }
+    spawn(command, || RUNNING.release());
+}
+
+/// Start `command` and call `on_exit` once it has been reaped or failed to start
+fn spawn(mut command: Command, on_exit: impl FnOnce() + Send + 'static) {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    match command.spawn() {
+        Ok(mut child) => {
+            thread::spawn(move || {
+                let _ = child.wait();
+                on_exit();
+            });
+        }
+        Err(e) => {
+            log::warn!("Failed to run {:?}: {}", command, e);
+            on_exit();
+        }
+    }
+}
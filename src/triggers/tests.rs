@@ -0,0 +1,113 @@
+use std::{path::PathBuf, time::Instant};
+
+use crate::config::{TriggerAction, TriggerConfig};
+
+use super::{ProcessLimit, Triggers, TRIGGER_COOLDOWN};
+
+fn trigger(pattern: &str, action: TriggerAction) -> TriggerConfig {
+    TriggerConfig {
+        pattern: pattern.to_string(),
+        action,
+    }
+}
+
+#[test]
+fn config_should_parse_every_action() {
+    let configs: Vec<TriggerConfig> = toml::from_str::<toml::Table>(
+        r#"
+        [[triggers]]
+        pattern = "error"
+        action = "highlight"
+        color = [200, 0, 0]
+
+        [[triggers]]
+        pattern = "done"
+        action = "notify"
+
+        [[triggers]]
+        pattern = "beep"
+        action = "sound"
+        file = "/tmp/beep.wav"
+
+        [[triggers]]
+        pattern = "panic"
+        action = "command"
+        command = "true"
+        "#,
+    )
+    .unwrap()["triggers"]
+        .clone()
+        .try_into()
+        .unwrap();
+
+    let actions: Vec<_> = configs.into_iter().map(|config| config.action).collect();
+    assert_eq!(
+        actions,
+        vec![
+            TriggerAction::Highlight { color: (200, 0, 0) },
+            TriggerAction::Notify,
+            TriggerAction::Sound {
+                file: Some(PathBuf::from("/tmp/beep.wav"))
+            },
+            TriggerAction::Command {
+                command: "true".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn matches_should_return_actions_in_config_order() {
+    let triggers = Triggers::new(&[
+        trigger("warn", TriggerAction::Notify),
+        trigger("(?i)error", TriggerAction::Highlight { color: (1, 2, 3) }),
+        trigger("error: \\d+", TriggerAction::Sound { file: None }),
+    ]);
+
+    let matched: Vec<_> = triggers.matches("error: 7").cloned().collect();
+
+    assert_eq!(
+        matched,
+        vec![
+            TriggerAction::Highlight { color: (1, 2, 3) },
+            TriggerAction::Sound { file: None },
+        ]
+    );
+    assert_eq!(triggers.matches("ERROR: none").count(), 1);
+    assert_eq!(triggers.matches("all good").count(), 0);
+}
+
+#[test]
+fn invalid_patterns_should_be_skipped() {
+    let triggers = Triggers::new(&[trigger("(unclosed", TriggerAction::Notify)]);
+
+    assert!(triggers.is_empty());
+}
+
+#[test]
+fn process_actions_should_wait_for_the_cooldown() {
+    let mut triggers = Triggers::new(&[
+        trigger("error", TriggerAction::Notify),
+        trigger("error", TriggerAction::Highlight { color: (1, 2, 3) }),
+    ]);
+    let now = Instant::now();
+
+    assert_eq!(triggers.fire("error 1", now).len(), 2);
+    // Highlighting starts no process, so it keeps up with every line
+    assert_eq!(
+        triggers.fire("error 2", now),
+        vec![&TriggerAction::Highlight { color: (1, 2, 3) }]
+    );
+    assert_eq!(triggers.fire("error 3", now + TRIGGER_COOLDOWN).len(), 2);
+}
+
+#[test]
+fn process_limit_should_refuse_once_full() {
+    let limit = ProcessLimit::new(2);
+
+    assert!(limit.acquire());
+    assert!(limit.acquire());
+    assert!(!limit.acquire());
+    limit.release();
+    assert!(limit.acquire());
+}
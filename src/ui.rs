@@ -1,5 +1,4 @@
 use std::{
-    cmp::{max, min},
     sync::{atomic::AtomicBool, Arc},
     time::{Duration, Instant},
 };
@@ -7,75 +6,83 @@ use std::{
 use tokio::sync::broadcast::{Receiver, Sender};
 use winit::{
     application::ApplicationHandler,
-    dpi::PhysicalSize,
-    event::{ElementState, KeyEvent, MouseScrollDelta, WindowEvent},
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent},
     event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
-    keyboard::{Key, KeyCode, PhysicalKey},
-    window::{Window, WindowAttributes, WindowId},
+    keyboard::{Key, KeyCode, ModifiersKeyState, ModifiersState, PhysicalKey},
+    window::{UserAttentionType, Window, WindowAttributes, WindowId},
 };
 
+#[cfg(target_os = "macos")]
+use crate::menu::MenuBar;
 use crate::{
-    commands::{ClientCommand, IdentifyTerminalMode, ServerCommand, SgrAttribute},
-    config::Config,
-    grid::{Cell, Grid},
+    accessibility,
+    actions::{self, Action},
+    blink::Blink,
+    clipboard::{self, PendingPaste},
+    command_mode::{self, CommandLine, InternalCommand, MonitorSetting, RecordAction, Setting},
+    commands::{ClientCommand, Progress, ServerCommand, ShellMark},
+    config::{Config, StatusBarPosition, TriggerAction},
+    debug_socket::{self, DebugServer, ModeFlags},
+    frontend::{Frontend, Session},
+    grid::Grid,
+    inspector::{Inspector, InspectorView},
+    keys,
+    latency::{LatencyProbe, LatencyStats},
+    links::{self, HintAction, Hints},
+    logging::{self, LogViewer},
+    metrics::Metrics,
+    minimap,
+    monitor::{Alert, Monitor},
+    prediction::Predictor,
+    profiling::SPAN_TARGET,
     recording::{Player, Recorder},
-    renderer::Renderer,
-    snapshot,
-    styles::{Color, Styles},
+    renderer::{Overlay, OverlayAnchor, Renderer},
+    selection::{AutoScroll, Selection},
+    shell_integration::CommandTracker,
+    signals, snapshot,
+    status_bar::StatusBar,
+    styles::Color,
+    terminal::Terminal,
+    themes::{self, Theme},
+    triggers::{self, Triggers},
 };
 
 #[cfg(test)]
 mod tests;
 
-// Trait defining a runner that can execute the UI
-// This allows for different implementations of the UI
-pub trait Runner {
-    fn run(self);
-}
-
+/// The winit + wgpu frontend
 pub struct WgpuRunner {
-    pub exit_flag: Arc<AtomicBool>,
-    pub config: Config,
-    pub tx: Sender<ServerCommand>,
-    pub rx: Receiver<ClientCommand>,
     pub player: Option<Player>,
     pub auto_record: bool,
 }
 
 impl WgpuRunner {
-    pub fn new(
-        exit_flag: Arc<AtomicBool>,
-        config: Config,
-        tx: Sender<ServerCommand>,
-        rx: Receiver<ClientCommand>,
-        player: Option<Player>,
-        auto_record: bool,
-    ) -> Self {
+    pub fn new(player: Option<Player>, auto_record: bool) -> Self {
         Self {
-            exit_flag,
-            config,
-            tx,
-            rx,
             player,
             auto_record,
         }
     }
 }
 
-impl Runner for WgpuRunner {
-    fn run(self) {
+impl Frontend for WgpuRunner {
+    fn run(&mut self, session: Session) {
         let event_loop = EventLoop::new().expect("Failed to create event loop");
         // Use Wait instead of Poll to reduce CPU usage when idle
         event_loop.set_control_flow(ControlFlow::Wait);
 
         let mut app = WgpuApp::new(
             "MTTY",
-            &self.config,
-            self.exit_flag.clone(),
-            self.tx.clone(),
-            self.rx.resubscribe(),
-            self.player,
+            &session.config,
+            session.exit_flag,
+            session.metrics,
+            session.inspector,
+            session.tx,
+            session.rx,
+            self.player.take(),
             self.auto_record,
+            session.shell_pid,
         );
 
         event_loop.run_app(&mut app).expect("Event loop failed");
@@ -85,24 +92,77 @@ impl Runner for WgpuRunner {
 pub struct WgpuApp {
     title: String,
     exit_flag: Arc<AtomicBool>,
-    input: String,
+    input: Vec<u8>,
     tx: Sender<ServerCommand>,
     rx: Receiver<ClientCommand>,
     config: Config,
-    grid: Grid,
+    terminal: Terminal,
+    triggers: Triggers,
+    status_bar: Option<StatusBar>,
+    /// Output is stopped by XOFF (Ctrl+S) until XON (Ctrl+Q)
+    output_paused: bool,
+    /// Paste still being sent to the PTY
+    paste: Option<PendingPaste>,
+    /// Local echo predictions (when predictive echo is enabled)
+    predictor: Option<Predictor>,
+    /// Blink phase of the cursor, held on with reduce-motion
+    cursor_blink: Blink,
+    /// Activity and silence monitors set with the `monitor` command
+    monitor: Monitor,
+    /// Whether the window has keyboard focus
+    focused: bool,
+    /// Command durations and exit statuses from the shell's OSC 133 marks
+    commands: CommandTracker,
+    /// Keypress-to-screen measurement, while enabled with `latency on`
+    latency: Option<LatencyProbe>,
+    /// Last mouse position inside the window
+    mouse_position: PhysicalPosition<f64>,
+    /// Text selected with the mouse
+    selection: Option<Selection>,
+    /// Labelled links while link hint mode is active (Ctrl+Shift+U)
+    hints: Option<Hints>,
+    /// Keys the hint labels are made from
+    hint_alphabet: Vec<char>,
+    /// Whether the left button is held, extending the selection
+    selecting: bool,
+    /// Scrolling while the selection is dragged past the grid's edge
+    auto_scroll: AutoScroll,
+    /// Whether the left button went down on the minimap, so moving the mouse scrolls
+    minimap_drag: bool,
+    /// Theme picked with the `theme` command
+    theme: &'static Theme,
+    /// High-contrast palette and minimum text contrast, overriding the theme
+    high_contrast: bool,
     window: Option<Arc<Window>>,
     renderer: Option<Renderer>,
-    modifiers: winit::keyboard::ModifiersState,
+    modifiers: ModifiersState,
+    /// Whether the left and right Option (Alt) keys are held
+    option_keys: (bool, bool),
+    /// Font size from the config, restored by Cmd+0
+    default_font_size: f32,
+    /// Native menu bar, installed once the event loop runs
+    #[cfg(target_os = "macos")]
+    menu_bar: Option<MenuBar>,
     /// Pending resize to be applied after debounce period
     pending_resize: Option<PhysicalSize<u32>>,
     /// Deadline after which the pending resize should be applied
     resize_deadline: Option<Instant>,
     /// Debug overlay information
     debug_info: DebugInfo,
-    /// Cursor keys application mode (DECCKM)
-    cursor_keys_mode: bool,
-    /// Bracketed paste mode
-    bracketed_paste_mode: bool,
+    /// Runtime counters (shared with the PTY threads)
+    metrics: Arc<Metrics>,
+    /// Internal `:` command line (toggled with Ctrl+Shift+;)
+    command_line: CommandLine,
+    /// Log viewer overlay (toggled with Ctrl+Shift+L)
+    log_viewer: LogViewer,
+    /// Sequences recorded by the PTY read thread while the inspector is shown
+    inspector: Arc<Inspector>,
+    /// Escape sequence inspector overlay (toggled with Ctrl+Shift+E)
+    inspector_view: InspectorView,
+    /// Set by the SIGUSR1 handler to request a snapshot
+    snapshot_requested: Arc<AtomicBool>,
+    /// Debug socket server (if enabled)
+    debug_server: Option<DebugServer>,
     /// Active recording session (if recording)
     recorder: Option<Recorder>,
     /// Replay player (if in replay mode)
@@ -124,6 +184,23 @@ impl ApplicationHandler for WgpuApp {
                     self.config.width as u32,
                     self.config.height as u32,
                 ));
+            #[cfg(target_os = "macos")]
+            let window_attributes = {
+                use winit::platform::macos::{OptionAsAlt, WindowAttributesExtMacOS};
+                window_attributes.with_option_as_alt(match self.config.option_as_alt {
+                    crate::config::OptionAsAlt::None => OptionAsAlt::None,
+                    crate::config::OptionAsAlt::Left => OptionAsAlt::OnlyLeft,
+                    crate::config::OptionAsAlt::Right => OptionAsAlt::OnlyRight,
+                    crate::config::OptionAsAlt::Both => OptionAsAlt::Both,
+                })
+            };
+
+            #[cfg(target_os = "macos")]
+            {
+                self.menu_bar = MenuBar::install()
+                    .map_err(|e| log::warn!("Failed to install the menu bar: {}", e))
+                    .ok();
+            }
 
             let window = Arc::new(
                 event_loop
@@ -131,12 +208,22 @@ impl ApplicationHandler for WgpuApp {
                     .expect("Failed to create window"),
             );
 
-            let renderer = Renderer::new(window.clone(), &self.config);
+            let mut renderer = {
+                let _span = tracing::info_span!(target: SPAN_TARGET, "create_renderer").entered();
+                Renderer::new(window.clone(), &self.config)
+            };
+            if self
+                .status_bar
+                .as_ref()
+                .is_some_and(|bar| bar.position() == StatusBarPosition::Top)
+            {
+                renderer.set_top_rows(1);
+            }
 
             // Get actual cell dimensions from renderer and recalculate grid size
             let (cell_width, cell_height) = renderer.cell_dimensions();
-            let new_cols = (self.config.width / cell_width).floor() as u16;
-            let new_rows = (self.config.height / cell_height).floor() as u16;
+            let new_cols = self.grid_cols(self.config.width, cell_width);
+            let new_rows = self.grid_rows(self.config.height, cell_height);
 
             if new_cols != self.config.cols || new_rows != self.config.rows {
                 log::info!(
@@ -151,7 +238,7 @@ impl ApplicationHandler for WgpuApp {
 
                 // In replay mode, don't recreate the grid (it's restored from snapshot)
                 if self.player.is_none() {
-                    self.grid = Grid::new(&self.config);
+                    self.terminal.grid = Grid::new(&self.config);
 
                     // Notify the PTY of the correct size
                     if let Err(e) = self.tx.send(ServerCommand::Resize(
@@ -167,6 +254,16 @@ impl ApplicationHandler for WgpuApp {
 
             self.window = Some(window);
             self.renderer = Some(renderer);
+            if self.high_contrast {
+                self.apply_palette();
+            }
+
+            // Replace whatever the window system shows until the shell's first output arrives
+            if let Some(renderer) = &mut self.renderer {
+                if let Err(e) = renderer.present_blank(&self.terminal.grid) {
+                    log::warn!("Failed to present first frame: {:?}", e);
+                }
+            }
         }
     }
 
@@ -183,6 +280,7 @@ impl ApplicationHandler for WgpuApp {
                 event_loop.exit();
             }
             WindowEvent::Resized(new_size) => {
+                self.metrics.add_resize_event();
                 self.handle_resize(new_size);
             }
             WindowEvent::KeyboardInput { event, .. } => {
@@ -190,15 +288,63 @@ impl ApplicationHandler for WgpuApp {
             }
             WindowEvent::ModifiersChanged(modifiers) => {
                 self.modifiers = modifiers.state();
+                self.option_keys = (
+                    modifiers.lalt_state() == ModifiersKeyState::Pressed,
+                    modifiers.ralt_state() == ModifiersKeyState::Pressed,
+                );
+            }
+            WindowEvent::Focused(focused) => {
+                self.focused = focused;
+                if focused {
+                    self.monitor.focus();
+                }
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                self.handle_cursor_moved(position);
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => {
+                self.handle_left_button(state);
             }
             WindowEvent::MouseWheel { delta, .. } => {
                 self.handle_mouse_wheel(delta);
             }
             WindowEvent::RedrawRequested => {
+                // Sample stats before rendering clears the dirty state
+                self.debug_info.dirty_rows = self.terminal.grid.dirty_row_count();
+                self.debug_info.channel_depth = self.rx.len();
+                // Measuring the grid walks every cell, so only do it while the HUD is shown
+                if self.debug_info.grid_sample_due(Instant::now()) {
+                    self.debug_info.scrollback_rows = self.terminal.grid.scrollback_rows();
+                    self.debug_info.grid_memory = self.terminal.grid.memory_usage();
+                }
+                let overlays = self.overlays();
+                if let Some(layout) = self.minimap_layout() {
+                    let blocks = layout.blocks(&self.terminal.grid);
+                    if let Some(renderer) = &mut self.renderer {
+                        renderer.set_minimap(&blocks);
+                    }
+                }
+
+                // A probe echoed before this frame is on screen once the frame is drawn
+                let echo_shown = self
+                    .latency
+                    .as_mut()
+                    .is_some_and(|probe| probe.check_echo(&self.terminal.grid));
+                let mut erase_probe = None;
+
                 if let Some(renderer) = &mut self.renderer {
-                    match renderer.render(&mut self.grid, &self.debug_info) {
+                    let frame_start = Instant::now();
+                    match renderer.render(&mut self.terminal.grid, &overlays) {
                         Ok(_) => {
-                            self.debug_info.update();
+                            self.metrics.add_frame_rendered();
+                            self.debug_info.update(frame_start.elapsed());
+                            if let Some(probe) = self.latency.as_mut().filter(|_| echo_shown) {
+                                erase_probe = probe.presented(renderer.wait_for_gpu());
+                            }
                         }
                         Err(wgpu::SurfaceError::Lost) => {
                             renderer.resize(renderer.size());
@@ -212,6 +358,10 @@ impl ApplicationHandler for WgpuApp {
                         }
                     }
                 }
+                if let Some(keypress) = erase_probe {
+                    self.send_raw_data(keypress);
+                }
+                self.debug_info.latency = self.latency.as_ref().map(LatencyProbe::stats);
             }
             _ => {}
         }
@@ -224,6 +374,21 @@ impl ApplicationHandler for WgpuApp {
             return;
         }
 
+        if self
+            .snapshot_requested
+            .swap(false, std::sync::atomic::Ordering::Relaxed)
+        {
+            log::info!("SIGUSR1 received, taking snapshot");
+            self.take_snapshot();
+        }
+
+        self.answer_debug_queries();
+
+        #[cfg(target_os = "macos")]
+        while let Some(action) = self.menu_bar.as_ref().and_then(MenuBar::poll) {
+            self.perform(action);
+        }
+
         // Handle replay mode
         if self.player.is_some() {
             if self.replay_playing {
@@ -239,10 +404,32 @@ impl ApplicationHandler for WgpuApp {
         } else {
             // Normal mode: Process incoming commands from PTY
             self.process_commands();
+            self.probe_latency();
+
+            if let Some(alert) = self.monitor.update(Instant::now()) {
+                self.show_alert(alert);
+            }
+
+            let predictions_changed = match &mut self.predictor {
+                Some(predictor) => predictor.reconcile(&self.terminal.grid, Instant::now()),
+                None => false,
+            };
+            if predictions_changed {
+                self.show_predictions();
+            }
+
+            if self.cursor_blink.update(Instant::now()) {
+                self.show_blink_phase();
+            }
 
             // Process buffered input
+            self.send_paste_chunks();
             self.process_input();
 
+            if self.selecting {
+                self.auto_scroll_selection();
+            }
+
             // Apply debounced resize if deadline has passed
             if let Some(deadline) = self.resize_deadline {
                 if Instant::now() >= deadline {
@@ -251,8 +438,31 @@ impl ApplicationHandler for WgpuApp {
             }
         }
 
-        // Request redraw when content has changed or debug overlay is shown (for FPS updates)
-        if self.grid.is_dirty() || self.debug_info.show {
+        let status_changed = match &mut self.status_bar {
+            Some(status_bar) => status_bar.update(&self.title),
+            None => false,
+        };
+
+        let shader_reloaded = self
+            .renderer
+            .as_mut()
+            .is_some_and(|renderer| renderer.reload_shader_if_changed());
+
+        // Request redraw when content has changed, the cursor is animating or an overlay is
+        // shown (for FPS updates and to clear command line messages once they time out)
+        if self.terminal.grid.is_dirty()
+            || shader_reloaded
+            || status_changed
+            || self.debug_info.show
+            || self.log_viewer.show
+            || self.inspector_view.show
+            || self.command_line.is_visible()
+            || self.paste.as_ref().is_some_and(PendingPaste::is_large)
+            || self
+                .renderer
+                .as_ref()
+                .is_some_and(|renderer| renderer.is_animating())
+        {
             if let Some(window) = &self.window {
                 window.request_redraw();
             }
@@ -268,14 +478,18 @@ impl ApplicationHandler for WgpuApp {
 }
 
 impl WgpuApp {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         title: &str,
         config: &Config,
         exit_flag: Arc<AtomicBool>,
+        metrics: Arc<Metrics>,
+        inspector: Arc<Inspector>,
         tx: Sender<ServerCommand>,
         rx: Receiver<ClientCommand>,
         player: Option<Player>,
         auto_record: bool,
+        shell_pid: Option<u32>,
     ) -> Self {
         log::info!("Grid size: {} x {}", config.rows, config.cols);
 
@@ -300,22 +514,62 @@ impl WgpuApp {
             None
         };
 
+        let debug_server =
+            config
+                .debug_socket
+                .as_ref()
+                .and_then(|path| match DebugServer::bind(path) {
+                    Ok(server) => Some(server),
+                    Err(e) => {
+                        log::error!("Failed to start debug socket at {:?}: {}", path, e);
+                        None
+                    }
+                });
+
         Self {
             title,
             exit_flag,
-            input: String::new(),
+            input: Vec::new(),
             tx,
             rx,
             config: config.clone(),
-            grid,
+            terminal: Terminal::with_grid(grid),
+            triggers: Triggers::new(&config.triggers),
+            status_bar: StatusBar::new(config, shell_pid),
+            output_paused: false,
+            paste: None,
+            predictor: config.predictive_echo.then(Predictor::default),
+            cursor_blink: Blink::new(!config.reduce_motion, Instant::now()),
+            monitor: Monitor::new(Instant::now()),
+            focused: true,
+            commands: CommandTracker::new(),
+            latency: None,
+            mouse_position: PhysicalPosition::default(),
+            selection: None,
+            hints: None,
+            hint_alphabet: links::hint_alphabet(&config.hint_alphabet),
+            selecting: false,
+            auto_scroll: AutoScroll::default(),
+            minimap_drag: false,
+            theme: &themes::THEMES[0],
+            high_contrast: accessibility::high_contrast_enabled(config.high_contrast),
             window: None,
             renderer: None,
-            modifiers: winit::keyboard::ModifiersState::empty(),
+            modifiers: ModifiersState::empty(),
+            option_keys: (false, false),
+            default_font_size: config.font_size,
+            #[cfg(target_os = "macos")]
+            menu_bar: None,
             pending_resize: None,
             resize_deadline: None,
-            debug_info: DebugInfo::new(),
-            cursor_keys_mode: false,
-            bracketed_paste_mode: false,
+            debug_info: DebugInfo::new(metrics.clone()),
+            metrics,
+            command_line: CommandLine::default(),
+            log_viewer: LogViewer::default(),
+            inspector,
+            inspector_view: InspectorView::default(),
+            snapshot_requested: signals::snapshot_on_sigusr1(),
+            debug_server,
             recorder,
             player,
             replay_playing: false,
@@ -324,7 +578,130 @@ impl WgpuApp {
         }
     }
 
-    fn send_raw_data(&self, data: Vec<u8>) {
+    /// Overlay panels to draw above the grid this frame
+    fn overlays(&mut self) -> Vec<Overlay> {
+        let mut overlays = Vec::new();
+        if let Some(status_bar) = &self.status_bar {
+            overlays.push(Overlay {
+                text: status_bar.text().to_string(),
+                anchor: match status_bar.position() {
+                    StatusBarPosition::Top => OverlayAnchor::StatusTop,
+                    StatusBarPosition::Bottom => OverlayAnchor::StatusBottom,
+                },
+                color: (229, 229, 229),
+            });
+        }
+        let rows = match &self.renderer {
+            Some(renderer) => {
+                (renderer.size().height as f32 / renderer.cell_dimensions().1) as usize
+            }
+            None => self.terminal.grid.height as usize,
+        };
+        if self.log_viewer.show {
+            let entries =
+                logging::buffer().filtered(self.log_viewer.min_level, &self.log_viewer.query);
+            overlays.push(Overlay {
+                // Leave a row for the panel padding and margins
+                text: self.log_viewer.text(&entries, rows.saturating_sub(1)),
+                anchor: OverlayAnchor::Fill,
+                color: (229, 229, 229),
+            });
+        } else if self.inspector_view.show {
+            let entries = self.inspector.filtered(&self.inspector_view.query);
+            overlays.push(Overlay {
+                text: self.inspector_view.text(&entries, rows.saturating_sub(1)),
+                anchor: OverlayAnchor::Fill,
+                color: (229, 229, 229),
+            });
+        }
+        if self.debug_info.show {
+            overlays.push(Overlay {
+                text: self.debug_info.hud_text(),
+                anchor: OverlayAnchor::TopRight,
+                color: (0, 255, 0),
+            });
+        }
+        if self.command_line.is_active() {
+            overlays.push(Overlay {
+                text: self.command_line.prompt(),
+                anchor: OverlayAnchor::Bottom,
+                color: (229, 229, 229),
+            });
+        } else if self.hints.is_some() {
+            overlays.push(Overlay {
+                text: "Type a label to open the link, in upper case to copy it  Esc: cancel"
+                    .to_string(),
+                anchor: OverlayAnchor::Bottom,
+                color: (229, 229, 229),
+            });
+        } else if let Some(paste) = self.paste.as_ref().filter(|paste| paste.is_large()) {
+            let (sent, total) = paste.progress();
+            overlays.push(Overlay {
+                text: format!(
+                    "Pasting {}% ({} of {})  Esc: cancel",
+                    sent * 100 / total,
+                    format_bytes(sent as u64),
+                    format_bytes(total as u64)
+                ),
+                anchor: OverlayAnchor::Bottom,
+                color: (229, 229, 229),
+            });
+        } else if self.output_paused {
+            overlays.push(Overlay {
+                text: "Output paused (Ctrl+Q to resume)".to_string(),
+                anchor: OverlayAnchor::Bottom,
+                color: (245, 245, 67),
+            });
+        } else if let Some((message, is_error)) = self.command_line.message() {
+            overlays.push(Overlay {
+                text: message.to_string(),
+                anchor: OverlayAnchor::Bottom,
+                color: if is_error {
+                    (241, 76, 76)
+                } else {
+                    (229, 229, 229)
+                },
+            });
+        }
+        overlays
+    }
+
+    /// Answer pending debug socket requests from the current state
+    fn answer_debug_queries(&mut self) {
+        let Some(server) = &self.debug_server else {
+            return;
+        };
+        let modes = ModeFlags {
+            cursor_keys_application: self.terminal.cursor_keys_mode,
+            bracketed_paste: self.terminal.bracketed_paste_mode,
+            alternate_screen: self.terminal.grid.is_alternate(),
+            cursor_hidden: self.terminal.grid.styles.cursor_state.hidden,
+            recording: self.recorder.is_some(),
+            replay: self.player.is_some(),
+        };
+        while let Some(query) = server.try_recv() {
+            let response =
+                debug_socket::response(query.request, &self.terminal.grid, &modes, &self.metrics);
+            query.respond(response);
+        }
+    }
+
+    fn send_raw_data(&mut self, data: Vec<u8>) {
+        // Keep typed text in order behind a paste that is still being sent
+        if let Some(paste) = self
+            .paste
+            .as_mut()
+            .filter(|_| clipboard::queues_behind_paste(&data))
+        {
+            paste.append(&data);
+            return;
+        }
+        self.write_pty(data);
+    }
+
+    /// Write straight to the PTY, ahead of any pending paste. Used for the terminal's replies
+    /// to queries, which programs wait for.
+    fn write_pty(&mut self, data: Vec<u8>) {
         // Don't send data in replay mode (no PTY)
         if self.player.is_some() {
             return;
@@ -334,205 +711,59 @@ impl WgpuApp {
         }
     }
 
-    fn handle_sgr_attribute(&mut self, attribute: SgrAttribute) {
-        match attribute {
-            SgrAttribute::Reset => {
-                self.grid.styles = Styles::default();
-            }
-            SgrAttribute::Bold => {
-                self.grid.styles.font_size = 20;
-            }
-            SgrAttribute::Dim => {
-                self.grid.styles.font_size = 14;
-            }
-            SgrAttribute::Italic => {
-                self.grid.styles.italic = true;
-            }
-            SgrAttribute::Underline => {
-                self.grid.styles.underline = true;
-            }
-            SgrAttribute::DoubleUnderline => {}
-            SgrAttribute::Undercurl => {}
-            SgrAttribute::DottedUnderline => {}
-            SgrAttribute::DashedUnderline => {}
-            SgrAttribute::BlinkSlow => {}
-            SgrAttribute::BlinkFast => {}
-            SgrAttribute::Reverse => {
-                self.grid.styles.reverse = true;
-            }
-            SgrAttribute::Hidden => {}
-            SgrAttribute::Strike => {}
-            SgrAttribute::CancelBold => {
-                self.grid.styles.font_size = 16;
-            }
-            SgrAttribute::CancelBoldDim => {
-                self.grid.styles.font_size = 16;
-            }
-            SgrAttribute::CancelItalic => {
-                self.grid.styles.italic = false;
-            }
-            SgrAttribute::CancelUnderline => {
-                self.grid.styles.underline = false;
-            }
-            SgrAttribute::CancelBlink => {}
-            SgrAttribute::CancelReverse => {
-                self.grid.styles.reverse = false;
-            }
-            SgrAttribute::CancelHidden => {}
-            SgrAttribute::Foreground(color) => {
-                log::debug!("SGR Foreground: {:?}", color);
-                self.grid.styles.active_text_color = color;
-            }
-            SgrAttribute::Background(color) => {
-                self.grid.styles.active_background_color = color;
-            }
-            _ => {}
+    /// Drop the rest of a pending paste, closing a bracketed paste the program has seen start
+    fn cancel_paste(&mut self) {
+        let Some(mut paste) = self.paste.take() else {
+            return;
+        };
+        let (sent, total) = paste.progress();
+        log::info!("Paste cancelled after {} of {} bytes", sent, total);
+        let closing = paste.cancel();
+        if !closing.is_empty() {
+            self.write_pty(closing);
         }
     }
 
-    fn handle_command(&mut self, command: ClientCommand) {
-        let cols = self.grid.width;
-        match command {
-            ClientCommand::Backspace => {
-                self.grid.delete_character();
-            }
-            ClientCommand::CarriageReturn => {
-                self.grid.place_character_in_grid(cols, '\r');
-            }
-            ClientCommand::ClearScreen => {
-                self.grid.clear_screen();
-            }
-            ClientCommand::ClearLineAfterCursor => {
-                let (row, col) = self.grid.cursor_pos;
-                self.clear_cells(row, col..self.grid.width as usize);
-            }
-            ClientCommand::ClearLineBeforeCursor => {
-                let (row, col) = self.grid.cursor_pos;
-                self.clear_cells(row, 0..col);
-            }
-            ClientCommand::ClearLine => {
-                let (row, _) = self.grid.cursor_pos;
-                self.clear_cells(row, 0..self.grid.width as usize);
+    /// Send keyboard input to the PTY, predicting its echo first
+    fn send_input(&mut self, data: Vec<u8>) {
+        // Ctrl+C interrupts a paste along with the program receiving it
+        if data == [0x03] {
+            self.cancel_paste();
+        }
+        self.cancel_latency_probe();
+        if let Some(predictor) = &mut self.predictor {
+            // Full-screen programs don't echo input where it was typed
+            if !self.terminal.grid.is_alternate() {
+                predictor.input(&data, &self.terminal.grid, Instant::now());
+                self.show_predictions();
             }
-            ClientCommand::ClearBelow => {
-                // first clear after cursor
-                let (row, col) = self.grid.cursor_pos;
-                self.clear_cells(row, col..self.grid.width as usize);
+        }
+        if self.cursor_blink.reset(Instant::now()) {
+            self.show_blink_phase();
+        }
+        self.send_raw_data(data);
+    }
 
-                // then clear below
-                for i in row + 1..self.grid.height as usize {
-                    self.clear_cells(i, 0..self.grid.width as usize);
-                }
-            }
-            ClientCommand::ClearAbove => {
-                // first clear before cursor
-                let (row, col) = self.grid.cursor_pos;
-                self.clear_cells(row, 0..col);
+    /// Redraw a blinking cursor in the current blink phase
+    fn show_blink_phase(&mut self) {
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_blink_visible(self.cursor_blink.visible());
+        }
+        if self.terminal.grid.styles.cursor_state.blinking {
+            self.terminal.grid.mark_cursor_row_dirty();
+        }
+    }
 
-                // then clear above
-                for i in 0..row {
-                    self.clear_cells(i, 0..self.grid.width as usize);
-                }
-            }
-            ClientCommand::ClearCount(count) => {
-                let (row, col) = self.grid.cursor_pos;
-                self.clear_cells(row, col..col + count as usize);
-            }
-            ClientCommand::DeleteChars(count) => {
-                self.grid.delete_chars(count as usize);
-            }
-            ClientCommand::DeleteLines(count) => {
-                self.grid.delete_lines(count as usize);
-            }
-            ClientCommand::IdentifyTerminal(mode) => match mode {
-                IdentifyTerminalMode::Primary => {
-                    self.send_raw_data(b"\x1b[?6c".to_vec());
-                }
-                IdentifyTerminalMode::Secondary => {
-                    let version = "0.0.1";
-                    let text = format!("\x1b[>0;{version};1c");
-                    self.send_raw_data(text.as_bytes().to_vec());
-                }
-            },
-            ClientCommand::LineFeed => {
-                self.grid.set_pos(self.grid.cursor_pos.0 + 1, 0);
-            }
-            ClientCommand::MoveCursor(x, y) => {
-                self.grid.set_pos(x as usize, y as usize);
-            }
-            ClientCommand::MoveCursorAbsoluteHorizontal(y) => {
-                self.grid.set_pos(self.grid.cursor_pos.0, y as usize);
-            }
-            ClientCommand::MoveCursorHorizontal(y) => {
-                let new_y = self.grid.cursor_pos.1 as i16 + y;
-                self.grid.set_pos(self.grid.cursor_pos.0, new_y as usize);
-            }
-            ClientCommand::MoveCursorVertical(x) => {
-                let new_x = self.grid.cursor_pos.0 as i16 + x;
-                self.grid.set_pos(new_x as usize, self.grid.cursor_pos.1);
-            }
-            ClientCommand::NewLine => {
-                self.grid.place_character_in_grid(cols, '\n');
-            }
-            ClientCommand::Print(c) => {
-                self.grid.place_character_in_grid(cols, c);
-            }
-            ClientCommand::PutTab => {
-                let (row, col) = self.grid.cursor_pos;
-                let grid_len = self.grid.active_grid().len();
-                let width = self.grid.width as usize;
-                if col < width.saturating_sub(5) {
-                    let (fg, bg) = if self.grid.styles.reverse {
-                        (
-                            self.grid.styles.active_background_color,
-                            self.grid.styles.active_text_color,
-                        )
-                    } else {
-                        (
-                            self.grid.styles.active_text_color,
-                            self.grid.styles.active_background_color,
-                        )
-                    };
-                    for i in col..col + 4 {
-                        let index = row * width + i;
-                        if index < grid_len {
-                            self.grid.active_grid()[index] = Cell::new(' ', fg, bg);
-                            self.grid.set_pos(row, i + 1);
-                        }
-                    }
-                }
-            }
-            ClientCommand::ReportCursorPosition => self.send_raw_data(
-                format!(
-                    "\x1b[{};{}R",
-                    self.grid.cursor_pos.0 + 1,
-                    self.grid.cursor_pos.1 + 1
-                )
-                .as_bytes()
-                .to_vec(),
-            ),
-            ClientCommand::ResetColor(index) => {
-                self.grid.styles.color_array[index] = Color::DEFAULT_ARRAY[index];
-            }
-            ClientCommand::RestoreCursor => {
-                self.grid.restore_cursor();
-            }
-            ClientCommand::ReportCondition(healthy) => {
-                if healthy {
-                    self.send_raw_data(b"\x1b[0n".to_vec());
-                } else {
-                    self.send_raw_data(b"\x1b[3n".to_vec());
-                }
-            }
-            ClientCommand::ShowCursor => {
-                self.grid.show_cursor();
-            }
-            ClientCommand::SGR(command) => {
-                self.handle_sgr_attribute(command);
-            }
-            ClientCommand::SaveCursor => {
-                self.grid.save_cursor();
-            }
+    /// Hand the visible predictions to the renderer and redraw them
+    fn show_predictions(&mut self) {
+        if let (Some(predictor), Some(renderer)) = (&self.predictor, &mut self.renderer) {
+            renderer.set_predictions(predictor.visible());
+            self.terminal.grid.mark_all_dirty();
+        }
+    }
+
+    fn handle_command(&mut self, command: ClientCommand) {
+        match command {
             ClientCommand::SetTitle(title) => {
                 if let Some(title_str) = title {
                     self.title = title_str.clone();
@@ -542,122 +773,126 @@ impl WgpuApp {
                     window.set_title(&self.title);
                 }
             }
-            ClientCommand::SwapScreenAndSetRestoreCursor(enter) => {
-                if enter {
-                    // Entering alternate screen: save cursor, switch, clear
-                    self.grid.saved_cursor_pos = self.grid.cursor_pos;
-                    self.grid.swap_active_grid();
-                    self.grid.clear_screen();
-                    self.grid.set_pos(0, 0);
-                } else {
-                    // Exiting alternate screen: switch back, restore cursor
-                    self.grid.swap_active_grid();
-                    self.grid.cursor_pos = self.grid.saved_cursor_pos;
-                    self.grid.mark_all_dirty();
-                }
-            }
-            ClientCommand::SetColor(index, color) => {
-                self.grid.styles.color_array[index] = Color::Rgb(color.r, color.g, color.b);
-            }
-            ClientCommand::MoveCursorVerticalWithCarriageReturn(x) => {
-                let new_x = self.grid.cursor_pos.0 as i16 + x;
-                self.grid.set_pos(new_x as usize, 0);
-            }
-            ClientCommand::HideCursor => {
-                self.grid.hide_cursor();
-            }
-            ClientCommand::SetCursorState(state) => {
-                self.grid.styles.cursor_state = state;
-            }
-            ClientCommand::SetCursorShape(shape) => {
-                self.grid.styles.cursor_state.shape = shape;
-            }
-            ClientCommand::CursorKeysMode(enabled) => {
-                self.cursor_keys_mode = enabled;
-            }
-            ClientCommand::BracketedPasteMode(enabled) => {
-                self.bracketed_paste_mode = enabled;
-            }
-            ClientCommand::ScrollUp(count) => {
-                self.grid.scroll_up(count as usize);
-            }
-            ClientCommand::ScrollDown(count) => {
-                self.grid.scroll_down(count as usize);
-            }
-            ClientCommand::InsertBlankLines(count) => {
-                self.grid.insert_blank_lines(count as usize);
-            }
-            ClientCommand::SetScrollingRegion(top, bottom) => {
-                self.grid.set_scroll_region(top, bottom);
-            }
-            ClientCommand::ReverseIndex => {
-                self.grid.reverse_index();
-            }
-            ClientCommand::InsertBlanks(count) => {
-                self.grid.insert_blanks(count as usize);
-            }
-            ClientCommand::SetDefaultForeground(rgb) => {
-                self.grid.styles.default_text_color = Color::Rgb(rgb.r, rgb.g, rgb.b);
-                // Also update active if it's currently using the default
-                if matches!(self.grid.styles.active_text_color, Color::Foreground) {
-                    self.grid.styles.active_text_color = Color::Foreground;
+            ClientCommand::Bell => {
+                if let Some(status_bar) = &mut self.status_bar {
+                    status_bar.ring_bell();
                 }
-                self.grid.mark_all_dirty();
             }
-            ClientCommand::SetDefaultBackground(rgb) => {
-                self.grid.styles.default_background_color = Color::Rgb(rgb.r, rgb.g, rgb.b);
-                // Also update active if it's currently using the default
-                if matches!(self.grid.styles.active_background_color, Color::Background) {
-                    self.grid.styles.active_background_color = Color::Background;
+            ClientCommand::OutputPaused(paused) => {
+                self.output_paused = paused;
+                if let Some(window) = &self.window {
+                    window.request_redraw();
                 }
-                self.grid.mark_all_dirty();
             }
-            ClientCommand::ReportTextAreaSizeChars => {
-                // CSI 8 ; rows ; cols t - Report text area size in characters
-                let response = format!("\x1b[8;{};{}t", self.grid.height, self.grid.width);
-                self.send_raw_data(response.as_bytes().to_vec());
+            ClientCommand::SetProgress(progress) => self.show_progress(progress),
+            ClientCommand::ShellMark(mark) => self.shell_mark(mark),
+            command @ (ClientCommand::ClearScreen | ClientCommand::ClearScrollback) => {
+                // The annotated prompt lines are gone
+                self.clear_annotations();
+                self.terminal.handle_command(command);
             }
             ClientCommand::ReportTextAreaSizePixels => {
                 // CSI 4 ; height ; width t - Report text area size in pixels
-                if let Some(renderer) = &self.renderer {
-                    let size = renderer.size();
+                if let Some(size) = self.renderer.as_ref().map(|renderer| renderer.size()) {
                     let response = format!("\x1b[4;{};{}t", size.height, size.width);
-                    self.send_raw_data(response.as_bytes().to_vec());
+                    self.write_pty(response.as_bytes().to_vec());
                 }
             }
-            _ => {
-                log::info!("Unsupported command: {:?}", command);
+            command @ (ClientCommand::LineFeed | ClientCommand::NewLine) => {
+                self.fire_triggers();
+                self.terminal.handle_command(command);
             }
+            command => self.terminal.handle_command(command),
+        }
+
+        let responses = self.terminal.take_responses();
+        if !responses.is_empty() {
+            self.write_pty(responses);
         }
     }
 
-    fn clear_cells(&mut self, row: usize, col_range: std::ops::Range<usize>) {
-        let grid_len = self.grid.active_grid().len();
-        let width = self.grid.width as usize;
+    /// Progress reported by the shell. Nothing shows it yet; the Windows taskbar button
+    /// will once there is a Windows build to test it with.
+    fn show_progress(&self, progress: Progress) {
+        log::trace!("Progress {:?} has no taskbar to show on", progress);
+    }
+
+    /// Type the next latency probe when one is due, or erase one that was never echoed
+    fn probe_latency(&mut self) {
+        let Some(probe) = &mut self.latency else {
+            return;
+        };
+        if let Some(erase) = probe.expire(Instant::now()) {
+            self.send_raw_data(erase);
+            return;
+        }
+        // Only type at the shell's prompt, not into full-screen programs or over other input
+        if self.terminal.grid.is_alternate() || self.paste.is_some() || !self.input.is_empty() {
+            return;
+        }
+        if let Some(keypress) = probe.start(&self.terminal.grid, Instant::now()) {
+            self.send_raw_data(keypress);
+        }
+    }
+
+    /// Erase a probe still waiting for its echo, so that input sent next doesn't land after it
+    fn cancel_latency_probe(&mut self) {
+        if let Some(erase) = self
+            .latency
+            .as_mut()
+            .and_then(|probe| probe.cancel(Instant::now()))
+        {
+            self.send_raw_data(erase);
+        }
+    }
 
-        let start_index = row * width + col_range.start;
-        let end_index = row * width + col_range.end;
+    /// Time commands from the shell's marks and annotate their prompt lines
+    fn shell_mark(&mut self, mark: ShellMark) {
+        if let Some(probe) = &mut self.latency {
+            probe.shell_mark(mark);
+        }
+        if !self.config.command_annotations || self.terminal.grid.is_alternate() {
+            return;
+        }
+        let row = self.terminal.grid.cursor_pos.0;
+        if self.commands.mark(mark, row, Instant::now()) {
+            if let Some(renderer) = &mut self.renderer {
+                renderer.set_annotations(self.commands.annotations());
+            }
+            self.terminal.grid.mark_all_dirty();
+        }
+    }
 
-        // Bounds check to prevent panics after resize
-        if start_index >= grid_len {
+    fn clear_annotations(&mut self) {
+        if self.commands.annotations().is_empty() {
             return;
         }
-        let end_index = std::cmp::min(end_index, grid_len);
+        self.commands.clear();
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_annotations(&[]);
+        }
+    }
 
-        let (fg, bg) = if self.grid.styles.reverse {
-            (
-                self.grid.styles.active_background_color,
-                self.grid.styles.active_text_color,
-            )
-        } else {
-            (
-                self.grid.styles.active_text_color,
-                self.grid.styles.active_background_color,
-            )
-        };
+    /// Match the line the cursor is leaving against the configured triggers
+    fn fire_triggers(&mut self) {
+        // Full-screen programs redraw lines constantly, so only watch the main screen
+        if self.triggers.is_empty() || self.terminal.grid.is_alternate() {
+            return;
+        }
 
-        for i in start_index..end_index {
-            self.grid.active_grid()[i] = Cell::new(' ', fg, bg);
+        let row = self.terminal.grid.cursor_pos.0;
+        let line = self.terminal.grid.row_text(row);
+        for action in self.triggers.fire(&line, Instant::now()) {
+            match action {
+                TriggerAction::Highlight { color: (r, g, b) } => {
+                    self.terminal
+                        .grid
+                        .set_row_background(row, Color::Rgb(*r, *g, *b));
+                }
+                TriggerAction::Notify => triggers::notify(&line),
+                TriggerAction::Sound { file } => triggers::play_sound(file.as_deref()),
+                TriggerAction::Command { command } => triggers::run_command(command, &line),
+            }
         }
     }
 
@@ -666,6 +901,36 @@ impl WgpuApp {
             return;
         }
 
+        // The internal command line captures all keys while it is open
+        if self.command_line.is_active() {
+            self.handle_command_line_input(event);
+            return;
+        }
+        if let Some(action) = actions::action_for_key(self.modifiers, event.physical_key) {
+            self.perform(action);
+            return;
+        }
+        // The log viewer captures keys for filtering and scrolling while it is shown
+        if self.log_viewer.show {
+            self.handle_log_viewer_input(event);
+            return;
+        }
+        if self.inspector_view.show {
+            self.handle_inspector_input(event);
+            return;
+        }
+        if self.hints.is_some() {
+            self.handle_hint_input(event);
+            return;
+        }
+        if self.paste.is_some()
+            && event.physical_key == PhysicalKey::Code(KeyCode::Escape)
+            && self.modifiers.is_empty()
+        {
+            self.cancel_paste();
+            return;
+        }
+
         // Handle replay mode controls FIRST (before normal key handling)
         if self.player.is_some() {
             match event.physical_key {
@@ -692,16 +957,6 @@ impl WgpuApp {
                         .store(true, std::sync::atomic::Ordering::Relaxed);
                     return;
                 }
-                // Toggle debug overlay even in replay mode
-                PhysicalKey::Code(KeyCode::KeyI)
-                    if self.modifiers.control_key() && self.modifiers.shift_key() =>
-                {
-                    self.debug_info.show = !self.debug_info.show;
-                    if let Some(window) = &self.window {
-                        window.request_redraw();
-                    }
-                    return;
-                }
                 // Replay speed controls: 1-9
                 PhysicalKey::Code(KeyCode::Digit1) => {
                     self.replay_speed = 1;
@@ -754,142 +1009,429 @@ impl WgpuApp {
             return;
         }
 
-        // Handle special keys (normal mode only)
-        match event.physical_key {
-            PhysicalKey::Code(KeyCode::Backspace) => {
-                // Send DEL (127) for xterm-256color compatibility, not Ctrl+H (8)
-                self.send_raw_data(vec![127]);
+        // Alt is Meta, except for Option keys that compose characters on macOS
+        let meta = if cfg!(target_os = "macos") {
+            let (left, right) = self.option_keys;
+            actions::option_is_meta(self.config.option_as_alt, left, right)
+        } else {
+            self.modifiers.alt_key()
+        };
+        let mut modifiers = self.modifiers;
+        modifiers.set(ModifiersState::ALT, meta);
+
+        if let PhysicalKey::Code(key) = event.physical_key {
+            if let Some(data) = keys::encode(key, modifiers, self.terminal.cursor_keys_mode) {
+                self.send_input(data);
                 return;
             }
-            PhysicalKey::Code(KeyCode::Escape) => {
-                self.send_raw_data(vec![27]);
-                return;
+        }
+
+        // Handle regular text input (Ctrl and Cmd combinations are not text)
+        if !self.modifiers.control_key() && !self.modifiers.super_key() {
+            if let Key::Character(ref text) = event.logical_key {
+                self.input.extend(keys::encode_text(text, meta));
             }
-            PhysicalKey::Code(KeyCode::ArrowUp) => {
-                // Application mode: ESC O A, Normal mode: ESC [ A
-                let seq = if self.cursor_keys_mode {
-                    vec![27, 79, 65]
+        }
+    }
+
+    /// Run an action picked from a key binding or the menu bar
+    fn perform(&mut self, action: Action) {
+        match action {
+            Action::OpenCommandLine => self.command_line.open(),
+            Action::ToggleLogViewer => self.log_viewer.show = !self.log_viewer.show,
+            Action::ToggleInspector => self.show_inspector(!self.inspector_view.show),
+            Action::ShowLinkHints => {
+                if self.hints.is_some() {
+                    self.end_hints();
                 } else {
-                    vec![27, 91, 65]
-                };
-                self.send_raw_data(seq);
-                return;
+                    self.start_hints();
+                }
             }
-            PhysicalKey::Code(KeyCode::ArrowDown) => {
-                let seq = if self.cursor_keys_mode {
-                    vec![27, 79, 66]
-                } else {
-                    vec![27, 91, 66]
-                };
-                self.send_raw_data(seq);
-                return;
+            Action::ToggleDebugOverlay => self.debug_info.show = !self.debug_info.show,
+            Action::TakeSnapshot => self.take_snapshot(),
+            Action::ToggleRecording => {
+                // Only in normal mode, not replay
+                if self.player.is_none() {
+                    self.toggle_recording();
+                }
             }
-            PhysicalKey::Code(KeyCode::ArrowLeft) => {
-                let seq = if self.cursor_keys_mode {
-                    vec![27, 79, 68]
-                } else {
-                    vec![27, 91, 68]
-                };
-                self.send_raw_data(seq);
-                return;
+            Action::ClearScrollback => self.clear_scrollback(),
+            Action::Copy => self.copy_selection(),
+            Action::Paste => {
+                if let Some(text) = clipboard::read().filter(|text| !text.is_empty()) {
+                    let data = clipboard::encode_paste(&text, self.terminal.bracketed_paste_mode);
+                    match &mut self.paste {
+                        Some(paste) => paste.append_paste(&data),
+                        None if self.player.is_none() => {
+                            self.cancel_latency_probe();
+                            self.paste = Some(PendingPaste::new(data));
+                            self.send_paste_chunks();
+                        }
+                        None => {}
+                    }
+                }
             }
-            PhysicalKey::Code(KeyCode::ArrowRight) => {
-                let seq = if self.cursor_keys_mode {
-                    vec![27, 79, 67]
-                } else {
-                    vec![27, 91, 67]
-                };
-                self.send_raw_data(seq);
-                return;
+            // Tabs are not supported, so a new tab opens as another window
+            Action::NewWindow | Action::NewTab => open_window(),
+            Action::CloseWindow | Action::Quit => self
+                .exit_flag
+                .store(true, std::sync::atomic::Ordering::Relaxed),
+            Action::IncreaseFontSize => self.set_font_size(self.config.font_size + 1.0),
+            Action::DecreaseFontSize => {
+                self.set_font_size((self.config.font_size - 1.0).max(MIN_FONT_SIZE))
             }
-            PhysicalKey::Code(KeyCode::Enter) => {
-                self.send_raw_data(vec![13]);
-                return;
+            Action::ResetFontSize => self.set_font_size(self.default_font_size),
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    fn handle_command_line_input(&mut self, event: &KeyEvent) {
+        match event.physical_key {
+            PhysicalKey::Code(KeyCode::Escape) => self.command_line.close(),
+            PhysicalKey::Code(KeyCode::Enter) | PhysicalKey::Code(KeyCode::NumpadEnter) => {
+                let line = self.command_line.submit();
+                if !line.trim().is_empty() {
+                    match self.run_internal_command(&line) {
+                        Ok(message) => {
+                            log::info!("Command '{}': {}", line, message);
+                            self.command_line.set_message(message, false);
+                        }
+                        Err(message) => {
+                            log::warn!("Command '{}' failed: {}", line, message);
+                            self.command_line.set_message(message, true);
+                        }
+                    }
+                }
             }
-            PhysicalKey::Code(KeyCode::Tab) => {
-                self.send_raw_data(vec![9]);
-                return;
+            PhysicalKey::Code(KeyCode::Backspace) => self.command_line.backspace(),
+            PhysicalKey::Code(KeyCode::Space) => self.command_line.push_str(" "),
+            _ => {
+                if !self.modifiers.control_key() {
+                    if let Key::Character(ref text) = event.logical_key {
+                        self.command_line.push_str(text);
+                    }
+                }
+            }
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    fn handle_log_viewer_input(&mut self, event: &KeyEvent) {
+        match event.physical_key {
+            PhysicalKey::Code(KeyCode::Escape) => self.log_viewer.show = false,
+            PhysicalKey::Code(KeyCode::Tab) => self.log_viewer.cycle_level(),
+            PhysicalKey::Code(KeyCode::ArrowUp) => self.log_viewer.scroll_up(1),
+            PhysicalKey::Code(KeyCode::ArrowDown) => self.log_viewer.scroll_down(1),
+            PhysicalKey::Code(KeyCode::PageUp) => self
+                .log_viewer
+                .scroll_up(self.terminal.grid.height as usize),
+            PhysicalKey::Code(KeyCode::PageDown) => self
+                .log_viewer
+                .scroll_down(self.terminal.grid.height as usize),
+            PhysicalKey::Code(KeyCode::Backspace) => {
+                self.log_viewer.query.pop();
+                self.log_viewer.scroll = 0;
             }
             PhysicalKey::Code(KeyCode::Space) => {
-                self.send_raw_data(vec![32]);
-                return;
+                self.log_viewer.query.push(' ');
+                self.log_viewer.scroll = 0;
             }
-            _ => {}
+            _ => {
+                if !self.modifiers.control_key() {
+                    if let Key::Character(ref text) = event.logical_key {
+                        self.log_viewer.query.push_str(text);
+                        self.log_viewer.scroll = 0;
+                    }
+                }
+            }
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
         }
+    }
 
-        // Handle Ctrl+Shift shortcuts
-        if self.modifiers.control_key() && self.modifiers.shift_key() {
-            match event.physical_key {
-                PhysicalKey::Code(KeyCode::KeyI) => {
-                    // Toggle debug overlay
-                    self.debug_info.show = !self.debug_info.show;
-                    if let Some(window) = &self.window {
-                        window.request_redraw();
+    /// Show or hide the inspector; sequences are only recorded while it is shown
+    fn show_inspector(&mut self, show: bool) {
+        self.inspector_view.show = show;
+        self.inspector.set_enabled(show);
+    }
+
+    fn handle_inspector_input(&mut self, event: &KeyEvent) {
+        match event.physical_key {
+            PhysicalKey::Code(KeyCode::Escape) => self.show_inspector(false),
+            PhysicalKey::Code(KeyCode::ArrowUp) => self.inspector_view.scroll_up(1),
+            PhysicalKey::Code(KeyCode::ArrowDown) => self.inspector_view.scroll_down(1),
+            PhysicalKey::Code(KeyCode::PageUp) => self
+                .inspector_view
+                .scroll_up(self.terminal.grid.height as usize),
+            PhysicalKey::Code(KeyCode::PageDown) => self
+                .inspector_view
+                .scroll_down(self.terminal.grid.height as usize),
+            PhysicalKey::Code(KeyCode::Backspace) => {
+                self.inspector_view.query.pop();
+                self.inspector_view.scroll = 0;
+            }
+            PhysicalKey::Code(KeyCode::Space) => {
+                self.inspector_view.query.push(' ');
+                self.inspector_view.scroll = 0;
+            }
+            _ => {
+                if !self.modifiers.control_key() {
+                    if let Key::Character(ref text) = event.logical_key {
+                        self.inspector_view.query.push_str(text);
+                        self.inspector_view.scroll = 0;
                     }
-                    return;
-                }
-                PhysicalKey::Code(KeyCode::KeyS) => {
-                    // Take snapshot
-                    self.take_snapshot();
-                    return;
                 }
-                PhysicalKey::Code(KeyCode::KeyR) => {
-                    // Toggle recording (only in normal mode, not replay)
-                    if self.player.is_none() {
-                        self.toggle_recording();
+            }
+        }
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Label the links on screen so one can be picked from the keyboard
+    fn start_hints(&mut self) {
+        let hints = Hints::new(
+            links::visible_links(&self.terminal.grid),
+            &self.hint_alphabet,
+        );
+        if hints.is_empty() {
+            self.command_line
+                .set_message("No links on screen".to_string(), false);
+            return;
+        }
+        self.hints = Some(hints);
+        self.show_hints();
+    }
+
+    fn end_hints(&mut self) {
+        self.hints = None;
+        self.show_hints();
+    }
+
+    fn show_hints(&mut self) {
+        let matching = self.hints.as_ref().map(Hints::matching).unwrap_or_default();
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_hints(&matching);
+        }
+        self.terminal.grid.mark_all_dirty();
+    }
+
+    fn handle_hint_input(&mut self, event: &KeyEvent) {
+        let Some(hints) = &mut self.hints else {
+            return;
+        };
+        let mut action = None;
+        match event.physical_key {
+            PhysicalKey::Code(KeyCode::Escape) => {
+                self.end_hints();
+                return;
+            }
+            PhysicalKey::Code(KeyCode::Backspace) => hints.backspace(),
+            _ => {
+                if !self.modifiers.control_key() {
+                    if let Key::Character(ref text) = event.logical_key {
+                        action = text.chars().find_map(|c| hints.input(c));
                     }
-                    return;
                 }
-                _ => {}
             }
         }
 
-        // Handle Ctrl+key combinations using physical key codes
-        // Ctrl+A=1, Ctrl+B=2, ..., Ctrl+Z=26
-        if self.modifiers.control_key() {
-            let ctrl_code = match event.physical_key {
-                PhysicalKey::Code(KeyCode::KeyA) => Some(1),
-                PhysicalKey::Code(KeyCode::KeyB) => Some(2),
-                PhysicalKey::Code(KeyCode::KeyC) => Some(3),
-                PhysicalKey::Code(KeyCode::KeyD) => Some(4),
-                PhysicalKey::Code(KeyCode::KeyE) => Some(5),
-                PhysicalKey::Code(KeyCode::KeyF) => Some(6),
-                PhysicalKey::Code(KeyCode::KeyG) => Some(7),
-                PhysicalKey::Code(KeyCode::KeyH) => Some(8),
-                PhysicalKey::Code(KeyCode::KeyI) => Some(9),
-                PhysicalKey::Code(KeyCode::KeyJ) => Some(10),
-                PhysicalKey::Code(KeyCode::KeyK) => Some(11),
-                PhysicalKey::Code(KeyCode::KeyL) => Some(12),
-                PhysicalKey::Code(KeyCode::KeyM) => Some(13),
-                PhysicalKey::Code(KeyCode::KeyN) => Some(14),
-                PhysicalKey::Code(KeyCode::KeyO) => Some(15),
-                PhysicalKey::Code(KeyCode::KeyP) => Some(16),
-                PhysicalKey::Code(KeyCode::KeyQ) => Some(17),
-                PhysicalKey::Code(KeyCode::KeyR) => Some(18),
-                PhysicalKey::Code(KeyCode::KeyS) => Some(19),
-                PhysicalKey::Code(KeyCode::KeyT) => Some(20),
-                PhysicalKey::Code(KeyCode::KeyU) => Some(21),
-                PhysicalKey::Code(KeyCode::KeyV) => Some(22),
-                PhysicalKey::Code(KeyCode::KeyW) => Some(23),
-                PhysicalKey::Code(KeyCode::KeyX) => Some(24),
-                PhysicalKey::Code(KeyCode::KeyY) => Some(25),
-                PhysicalKey::Code(KeyCode::KeyZ) => Some(26),
-                _ => None,
-            };
-            if let Some(code) = ctrl_code {
-                self.send_raw_data(vec![code]);
-                return;
+        match action {
+            Some(HintAction::Open(target)) => {
+                log::info!("Opening {}", target);
+                links::open(&target);
+                self.end_hints();
             }
+            Some(HintAction::Copy(target)) => {
+                if clipboard::write(&target) {
+                    self.command_line
+                        .set_message(format!("Copied {}", target), false);
+                } else {
+                    self.command_line
+                        .set_message("Failed to copy the link".to_string(), true);
+                }
+                self.end_hints();
+            }
+            None => self.show_hints(),
         }
+    }
 
-        // Handle regular text input
-        if !self.modifiers.control_key() {
-            if let Key::Character(ref text) = event.logical_key {
-                self.input.push_str(text);
+    /// Run a command from the internal command line, returning a message to display
+    fn run_internal_command(&mut self, line: &str) -> Result<String, String> {
+        match command_mode::parse(line)? {
+            InternalCommand::Set(Setting::FontSize(size)) => {
+                self.set_font_size(size);
+                Ok(format!("font_size = {}", size))
+            }
+            InternalCommand::Set(Setting::HighContrast(enabled)) => {
+                self.high_contrast = enabled;
+                self.apply_palette();
+                Ok(format!(
+                    "high_contrast = {}",
+                    if enabled { "on" } else { "off" }
+                ))
+            }
+            InternalCommand::Set(Setting::ColorFilter(filter)) => {
+                let renderer = self
+                    .renderer
+                    .as_mut()
+                    .ok_or_else(|| "no window to filter".to_string())?;
+                renderer.set_color_filter(filter);
+                Ok(format!("color_filter = {}", filter.name()))
+            }
+            InternalCommand::Theme(name) => {
+                let theme = themes::find(&name).ok_or_else(|| {
+                    format!(
+                        "unknown theme: {} (available: {})",
+                        name,
+                        themes::names().join(", ")
+                    )
+                })?;
+                self.theme = theme;
+                self.apply_palette();
+                if self.high_contrast {
+                    return Ok(format!(
+                        "theme = {} (shown once high_contrast is off)",
+                        theme.name
+                    ));
+                }
+                Ok(format!("theme = {}", theme.name))
+            }
+            InternalCommand::Memory => Ok(self.memory_report()),
+            InternalCommand::Monitor(MonitorSetting::Activity(enabled)) => {
+                self.monitor.set_activity(enabled);
+                Ok(format!(
+                    "monitor activity = {}",
+                    if enabled { "on" } else { "off" }
+                ))
+            }
+            InternalCommand::Monitor(MonitorSetting::Silence(seconds)) => {
+                self.monitor
+                    .set_silence(seconds.map(Duration::from_secs), Instant::now());
+                Ok(match seconds {
+                    Some(seconds) => format!("monitor silence = {}s", seconds),
+                    None => "monitor silence = off".to_string(),
+                })
+            }
+            InternalCommand::Latency(enabled) => {
+                if self.player.is_some() {
+                    return Err("latency probes need a shell".to_string());
+                }
+                // Erase an outstanding probe rather than dropping it at the prompt
+                self.cancel_latency_probe();
+                self.latency = enabled.then(|| LatencyProbe::new(Instant::now()));
+                self.debug_info.latency = None;
+                if enabled {
+                    self.debug_info.show = true;
+                    Ok(
+                        "latency probes on; they type and erase a character at the prompt"
+                            .to_string(),
+                    )
+                } else {
+                    Ok("latency probes off".to_string())
+                }
+            }
+            InternalCommand::ClearScrollback => {
+                self.clear_scrollback();
+                Ok("scrollback cleared".to_string())
+            }
+            InternalCommand::Snapshot => snapshot::take_snapshot(&self.terminal.grid)
+                .map(|path| format!("snapshot saved to {}", path.display()))
+                .map_err(|e| format!("failed to save snapshot: {}", e)),
+            InternalCommand::Record(action) => {
+                if self.player.is_some() {
+                    return Err("recording is not available in replay mode".to_string());
+                }
+                match (action, self.recorder.is_some()) {
+                    (RecordAction::Start, true) => Err("already recording".to_string()),
+                    (RecordAction::Stop, false) => Err("not recording".to_string()),
+                    (RecordAction::Start, false) => {
+                        self.toggle_recording();
+                        Ok("recording started".to_string())
+                    }
+                    (RecordAction::Stop, true) => {
+                        self.toggle_recording();
+                        Ok("recording stopped".to_string())
+                    }
+                }
             }
         }
     }
 
+    /// One-line breakdown of where memory goes, to help tune scrollback and spot leaks
+    fn memory_report(&self) -> String {
+        let grid = &self.terminal.grid;
+        let mut report = format!(
+            "grid {} ({} scrollback rows)",
+            format_bytes(grid.memory_usage() as u64),
+            grid.scrollback_rows()
+        );
+        if let Some(renderer) = &self.renderer {
+            let memory = renderer.memory_usage();
+            report.push_str(&format!(
+                ", shaping {}, glyph atlas ~{} ({} glyphs), row caches {}, GPU buffers {}",
+                format_bytes(memory.shaping as u64),
+                format_bytes(memory.glyph_atlas as u64),
+                memory.glyphs,
+                format_bytes(memory.row_caches as u64),
+                format_bytes(memory.gpu_buffers as u64)
+            ));
+        }
+        report
+    }
+
+    /// Apply the current theme, or the high-contrast palette in its place
+    fn apply_palette(&mut self) {
+        let theme = if self.high_contrast {
+            &themes::HIGH_CONTRAST
+        } else {
+            self.theme
+        };
+        theme.apply(&mut self.terminal.grid.styles);
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_minimum_contrast(
+                self.high_contrast
+                    .then_some(accessibility::HIGH_CONTRAST_RATIO),
+            );
+        }
+        self.terminal.grid.mark_all_dirty();
+    }
+
+    fn set_font_size(&mut self, font_size: f32) {
+        let Some(renderer) = &mut self.renderer else {
+            return;
+        };
+        renderer.set_font_size(font_size);
+        let size = renderer.size();
+        self.config.font_size = font_size;
+
+        // Refit the grid to the new cell size and redraw every row
+        self.handle_resize(size);
+        self.terminal.grid.mark_all_dirty();
+    }
+
+    /// Grid rows fitting in `height` pixels, leaving room for the status bar
+    /// Columns that fit beside the minimap
+    fn grid_cols(&self, width: f32, cell_width: f32) -> u16 {
+        let reserved = if self.config.minimap {
+            minimap::WIDTH
+        } else {
+            0.0
+        };
+        (((width - reserved) / cell_width).floor() as u16).max(1)
+    }
+
+    fn grid_rows(&self, height: f32, cell_height: f32) -> u16 {
+        let rows = (height / cell_height).floor() as u16;
+        let reserved = u16::from(self.status_bar.is_some());
+        rows.saturating_sub(reserved).max(1)
+    }
+
     fn handle_resize(&mut self, new_size: PhysicalSize<u32>) {
         // Immediately resize the renderer for visual feedback
         if let Some(renderer) = &mut self.renderer {
@@ -900,11 +1442,11 @@ impl WgpuApp {
 
             // Immediately resize grid to match renderer (prevents visual artifacts)
             let (cell_width, cell_height) = renderer.cell_dimensions();
-            let new_cols = (new_width / cell_width).floor() as u16;
-            let new_rows = (new_height / cell_height).floor() as u16;
+            let new_cols = self.grid_cols(new_width, cell_width);
+            let new_rows = self.grid_rows(new_height, cell_height);
 
-            if new_cols != self.grid.width || new_rows != self.grid.height {
-                self.grid.resize(new_cols, new_rows);
+            if new_cols != self.terminal.grid.width || new_rows != self.terminal.grid.height {
+                self.terminal.grid.resize(new_cols, new_rows);
                 self.config.cols = new_cols;
                 self.config.rows = new_rows;
                 self.config.width = new_width;
@@ -946,31 +1488,170 @@ impl WgpuApp {
         }
     }
 
+    /// Buffer point (absolute row, column boundary) under a position in the window
+    fn selection_point(&self, position: PhysicalPosition<f64>) -> Option<(usize, usize)> {
+        let renderer = self.renderer.as_ref()?;
+        let (cell_width, cell_height) = renderer.cell_dimensions();
+        let grid = &self.terminal.grid;
+        let start_row = grid.top_row();
+        let row = (position.y as f32 / cell_height - renderer.top_rows() as f32).max(0.0) as usize;
+        let col = (position.x as f32 / cell_width).round().max(0.0) as usize;
+        Some((
+            start_row + row.min(grid.height as usize - 1),
+            col.min(grid.width as usize),
+        ))
+    }
+
+    /// Where the minimap goes, when it is enabled
+    fn minimap_layout(&self) -> Option<minimap::Layout> {
+        if !self.config.minimap {
+            return None;
+        }
+        let renderer = self.renderer.as_ref()?;
+        let (_, cell_height) = renderer.cell_dimensions();
+        let grid = &self.terminal.grid;
+        let total_rows = grid.active_grid_ref().len() / grid.width.max(1) as usize;
+        Some(minimap::Layout::new(
+            renderer.size().width as f32,
+            renderer.top_rows() as f32 * cell_height,
+            grid.height as f32 * cell_height,
+            total_rows,
+            grid.height as usize,
+        ))
+    }
+
+    /// Scroll so the rows under `y` on the minimap are in view
+    fn scroll_to_minimap(&mut self, y: f32) {
+        if let Some(layout) = self.minimap_layout() {
+            self.terminal.grid.scroll_view_to(layout.bottom_row_at(y));
+        }
+    }
+
+    fn handle_cursor_moved(&mut self, position: PhysicalPosition<f64>) {
+        self.mouse_position = position;
+        if self.minimap_drag {
+            self.scroll_to_minimap(position.y as f32);
+            return;
+        }
+        if !self.selecting {
+            return;
+        }
+        if let Some(point) = self.selection_point(position) {
+            if let Some(selection) = &mut self.selection {
+                selection.extend(point);
+            }
+            self.show_selection();
+        }
+    }
+
+    fn handle_left_button(&mut self, state: ElementState) {
+        match state {
+            ElementState::Pressed => {
+                self.selection = self
+                    .selection_point(self.mouse_position)
+                    .map(Selection::new);
+                self.selecting = true;
+                self.show_selection();
+            }
+            ElementState::Released if self.minimap_drag => self.minimap_drag = false,
+            ElementState::Released => {
+                self.selecting = false;
+                if self.selection.is_some_and(|selection| selection.is_empty()) {
+                    self.selection = None;
+                    self.show_selection();
+                } else if self.config.copy_on_select {
+                    self.copy_selection();
+                }
+            }
+        }
+    }
+
+    /// Scroll while the selection is dragged above or below the grid, extending it into the
+    /// rows that come into view
+    fn auto_scroll_selection(&mut self) {
+        let Some(renderer) = &self.renderer else {
+            return;
+        };
+        let (_, cell_height) = renderer.cell_dimensions();
+        let top = renderer.top_rows() as f32 * cell_height;
+        let bottom = top + self.terminal.grid.height as f32 * cell_height;
+        let y = self.mouse_position.y as f32;
+        let overshoot = if y < top {
+            Some((y - top) / cell_height)
+        } else if y >= bottom {
+            Some((y - bottom) / cell_height)
+        } else {
+            None
+        };
+
+        let rows = self.auto_scroll.update(overshoot, Instant::now());
+        if rows == 0 {
+            return;
+        }
+        self.terminal.grid.scroll_view(rows);
+        let point = self.selection_point(self.mouse_position);
+        if let (Some(point), Some(selection)) = (point, &mut self.selection) {
+            selection.extend(point);
+        }
+        self.show_selection();
+    }
+
+    /// Hand the selection to the renderer and redraw
+    fn show_selection(&mut self) {
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_selection(self.selection);
+        }
+        self.terminal.grid.mark_all_dirty();
+    }
+
+    fn copy_selection(&self) {
+        match self.selection.filter(|selection| !selection.is_empty()) {
+            Some(selection) => {
+                if !clipboard::write(&selection.text(&self.terminal.grid)) {
+                    log::warn!("Failed to copy the selection");
+                }
+            }
+            None => log::debug!("Nothing to copy"),
+        }
+    }
+
+    /// Drop the scrollback; a selection would point at the wrong rows afterwards
+    fn clear_scrollback(&mut self) {
+        self.terminal.grid.clear_scrollback();
+        self.clear_annotations();
+        if self.selection.take().is_some() {
+            self.show_selection();
+        }
+    }
+
     fn handle_mouse_wheel(&mut self, delta: MouseScrollDelta) {
         let y = match delta {
             MouseScrollDelta::LineDelta(_, y) => y,
             MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 20.0,
         };
 
-        if y > 0.0 {
-            self.grid.scroll_pos = max(
-                self.grid.height as usize - 1,
-                self.grid.scroll_pos.saturating_sub(1),
-            );
-        } else {
-            self.grid.scroll_pos = min(
-                self.grid.active_grid().len().saturating_sub(1),
-                self.grid.scroll_pos + 1,
-            );
-        }
+        self.terminal.grid.scroll_view(if y > 0.0 { -1 } else { 1 });
     }
 
     fn process_commands(&mut self) {
+        // Skip the span entirely when idle so traces aren't flooded with empty slices
+        if self.rx.is_empty() {
+            return;
+        }
+        let span = tracing::info_span!(
+            target: SPAN_TARGET,
+            "grid_apply",
+            commands = tracing::field::Empty
+        );
+        let _entered = span.enter();
+        let mut applied = 0;
+
         // Process commands for a limited time to avoid blocking the UI
         let now = std::time::Instant::now();
         while now.elapsed().as_millis() < 50 {
             match self.rx.try_recv() {
                 Ok(command) => {
+                    applied += 1;
                     // Record command if recording is active
                     if let Some(ref mut recorder) = self.recorder {
                         recorder.record_command(&command);
@@ -982,6 +1663,7 @@ impl WgpuApp {
                 }
                 Err(tokio::sync::broadcast::error::TryRecvError::Lagged(n)) => {
                     log::warn!("UI receiver lagged, {} messages dropped", n);
+                    self.metrics.add_dropped_messages(n);
                     // Continue processing - don't break
                 }
                 Err(tokio::sync::broadcast::error::TryRecvError::Closed) => {
@@ -990,17 +1672,52 @@ impl WgpuApp {
                 }
             }
         }
+        span.record("commands", applied);
+        self.metrics.add_commands_processed(applied);
+
+        if applied > 0 {
+            if let Some(alert) = self.monitor.output(self.focused, Instant::now()) {
+                self.show_alert(alert);
+            }
+        }
+    }
+
+    /// Tell the user about a monitor alert with a notification and the window's attention
+    fn show_alert(&self, alert: Alert) {
+        let message = alert.message();
+        log::info!("Monitor: {}", message);
+        triggers::notify(&message);
+        if let Some(window) = &self.window {
+            window.request_user_attention(Some(UserAttentionType::Informational));
+        }
+    }
+
+    /// Send the pending paste on as the PTY drains, a bounded chunk at a time
+    fn send_paste_chunks(&mut self) {
+        let Some(paste) = &mut self.paste else {
+            return;
+        };
+        if let Some(chunk) = paste.next_chunk(self.metrics.pty_write_queue()) {
+            if let Err(e) = self.tx.send(ServerCommand::RawData(chunk)) {
+                log::warn!("Failed to send paste: {}", e);
+                self.paste = None;
+                return;
+            }
+        }
+        if paste.is_done() {
+            self.paste = None;
+        }
     }
 
     fn process_input(&mut self) {
-        while !self.input.is_empty() {
-            let c = self.input.remove(0);
-            self.send_raw_data(vec![c as u8]);
+        if !self.input.is_empty() {
+            let data = std::mem::take(&mut self.input);
+            self.send_input(data);
         }
     }
 
     fn take_snapshot(&mut self) {
-        match snapshot::take_snapshot(&self.grid) {
+        match snapshot::take_snapshot(&self.terminal.grid) {
             Ok(path) => {
                 log::info!("Snapshot saved to: {:?}", path);
             }
@@ -1013,7 +1730,7 @@ impl WgpuApp {
     fn toggle_recording(&mut self) {
         if let Some(recorder) = self.recorder.take() {
             // Stop recording
-            match recorder.finish(&self.grid) {
+            match recorder.finish(&self.terminal.grid) {
                 Ok(path) => {
                     log::info!("Recording saved to: {:?}", path);
                     self.title = "MTTY".to_string();
@@ -1024,7 +1741,7 @@ impl WgpuApp {
             }
         } else {
             // Start recording
-            self.recorder = Some(Recorder::new(&self.grid));
+            self.recorder = Some(Recorder::new(&self.terminal.grid));
             self.title = "MTTY - Recording".to_string();
             log::info!("Recording started");
         }
@@ -1105,7 +1822,7 @@ impl WgpuApp {
 
         // Now replay with full ownership of self
         if let Some((initial, commands)) = replay_data {
-            self.grid.restore_from_snapshot(&initial);
+            self.terminal.grid.restore_from_snapshot(&initial);
             // Clear last command if going back to start
             if commands.is_empty() {
                 self.last_replay_command = None;
@@ -1123,7 +1840,7 @@ impl WgpuApp {
     fn replay_reset(&mut self) {
         if let Some(ref mut player) = self.player {
             let initial = player.initial_state().clone();
-            self.grid.restore_from_snapshot(&initial);
+            self.terminal.grid.restore_from_snapshot(&initial);
             player.reset();
             self.replay_playing = false;
             self.last_replay_command = None;
@@ -1171,6 +1888,32 @@ impl WgpuApp {
 /// Debounce duration for window resize events to avoid excessive grid/PTY updates
 const RESIZE_DEBOUNCE_MS: u64 = 50;
 
+/// Smallest font size reachable with the shrink shortcut
+const MIN_FONT_SIZE: f32 = 4.0;
+
+/// Start another mtty process, which opens its own window and shell
+fn open_window() {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            log::warn!("Failed to find the mtty executable: {}", e);
+            return;
+        }
+    };
+    match std::process::Command::new(&exe).spawn() {
+        Ok(mut child) => {
+            // Reap it when it exits so it doesn't linger as a zombie
+            std::thread::spawn(move || {
+                let _ = child.wait();
+            });
+        }
+        Err(e) => log::warn!("Failed to open a new window with {:?}: {}", exe, e),
+    }
+}
+
+/// How often the HUD measures the grid's scrollback and memory
+const GRID_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Debug information displayed as an overlay
 pub struct DebugInfo {
     /// Whether to show debug overlay (toggled with Ctrl+Shift+I)
@@ -1181,25 +1924,140 @@ pub struct DebugInfo {
     frame_count: u32,
     /// Current FPS value
     pub fps: f32,
+    /// Time spent in the last render call
+    pub frame_time: Duration,
+    /// Rows that were dirty going into the last frame
+    pub dirty_rows: usize,
+    /// Commands waiting in the UI channel
+    pub channel_depth: usize,
+    /// Rows of scrollback in the active buffer
+    pub scrollback_rows: usize,
+    /// Approximate memory held by the grid buffers, in bytes
+    pub grid_memory: usize,
+    /// When the scrollback and memory figures were last measured
+    last_grid_sample: Option<Instant>,
+    /// Runtime counters (shared with the PTY threads)
+    metrics: Arc<Metrics>,
+    /// Byte count at the last throughput sample
+    last_bytes_read: u64,
+    /// Parser throughput in bytes per second
+    pub bytes_per_sec: f64,
+    /// Keypress-to-screen latency, while probes are on
+    pub latency: Option<LatencyStats>,
 }
 
 impl DebugInfo {
-    fn new() -> Self {
+    fn new(metrics: Arc<Metrics>) -> Self {
         Self {
             show: false,
             last_update: Instant::now(),
             frame_count: 0,
             fps: 0.0,
+            frame_time: Duration::ZERO,
+            dirty_rows: 0,
+            channel_depth: 0,
+            scrollback_rows: 0,
+            grid_memory: 0,
+            last_grid_sample: None,
+            metrics,
+            last_bytes_read: 0,
+            bytes_per_sec: 0.0,
+            latency: None,
+        }
+    }
+
+    /// Whether the grid figures should be measured again: only while the HUD is shown, and
+    /// at most once per `GRID_SAMPLE_INTERVAL`
+    fn grid_sample_due(&mut self, now: Instant) -> bool {
+        let due = self.show
+            && self
+                .last_grid_sample
+                .is_none_or(|last| now.duration_since(last) >= GRID_SAMPLE_INTERVAL);
+        if due {
+            self.last_grid_sample = Some(now);
         }
+        due
     }
 
-    fn update(&mut self) {
+    fn update(&mut self, frame_time: Duration) {
+        self.frame_time = frame_time;
         self.frame_count += 1;
         let elapsed = self.last_update.elapsed();
         if elapsed >= Duration::from_secs(1) {
             self.fps = self.frame_count as f32 / elapsed.as_secs_f32();
             self.frame_count = 0;
+
+            let bytes_read = self.metrics.bytes_read();
+            self.bytes_per_sec = (bytes_read - self.last_bytes_read) as f64 / elapsed.as_secs_f64();
+            self.last_bytes_read = bytes_read;
+
             self.last_update = Instant::now();
         }
     }
+
+    /// Text shown in the debug HUD panel
+    pub fn hud_text(&self) -> String {
+        let metrics = self.metrics.snapshot();
+        let mut text = format!(
+            "FPS        {:.1}\n\
+             Frame      {:.2} ms\n\
+             Dirty rows {}\n\
+             Parser     {}/s\n\
+             Channel    {}\n\
+             PTY queue  {}\n\
+             Commands   {} ({} dropped)\n\
+             Resizes    {}\n\
+             Scrollback {} rows, {}",
+            self.fps,
+            self.frame_time.as_secs_f64() * 1000.0,
+            self.dirty_rows,
+            format_bytes(self.bytes_per_sec as u64),
+            self.channel_depth,
+            format_bytes(metrics.pty_write_queue),
+            metrics.commands_processed,
+            metrics.dropped_messages,
+            metrics.resize_events,
+            self.scrollback_rows,
+            format_bytes(self.grid_memory as u64),
+        );
+        if let Some(latency) = &self.latency {
+            text.push('\n');
+            text.push_str(&format_latency(latency));
+        }
+        text
+    }
+}
+
+/// HUD lines for keypress-to-screen latency statistics
+pub fn format_latency(stats: &LatencyStats) -> String {
+    if stats.samples == 0 {
+        return format!("Latency    measuring ({} missed)", stats.missed);
+    }
+    let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    format!(
+        "Latency    {:.1} ms median, {:.1} ms p95\n           \
+         {:.1}-{:.1} ms, {} samples ({} missed)",
+        ms(stats.median),
+        ms(stats.p95),
+        ms(stats.min),
+        ms(stats.max),
+        stats.samples,
+        stats.missed,
+    )
+}
+
+/// Format a byte count with a binary unit suffix
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
 }
@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod tests;
+
+/// Which rows of the buffer are on screen.
+///
+/// The view is kept as an offset from the bottom of the buffer, so it stays on the newest
+/// rows as output adds more. Scrolling into the scrollback raises the offset; output then
+/// either jumps back to the bottom or, with `follow_output` off, leaves the view where it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    height: usize,
+    /// Rows between the bottom of the view and the bottom of the buffer
+    display_offset: usize,
+    follow_output: bool,
+}
+
+impl Viewport {
+    pub fn new(height: usize, follow_output: bool) -> Self {
+        Self {
+            height,
+            display_offset: 0,
+            follow_output,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn display_offset(&self) -> usize {
+        self.display_offset
+    }
+
+    /// Whether the newest rows are shown
+    pub fn is_at_bottom(&self) -> bool {
+        self.display_offset == 0
+    }
+
+    /// Change the number of visible rows, returning to the bottom
+    pub fn set_height(&mut self, height: usize) {
+        self.height = height;
+        self.display_offset = 0;
+    }
+
+    /// First visible row of a buffer with `total_rows` rows
+    pub fn top(&self, total_rows: usize) -> usize {
+        total_rows.saturating_sub(self.height + self.display_offset)
+    }
+
+    /// Last visible row of a buffer with `total_rows` rows
+    pub fn bottom(&self, total_rows: usize) -> usize {
+        self.top(total_rows) + self.height.saturating_sub(1)
+    }
+
+    /// Absolute buffer row shown on `screen_row`
+    pub fn to_absolute(&self, screen_row: usize, total_rows: usize) -> usize {
+        self.top(total_rows) + screen_row
+    }
+
+    /// Screen row showing absolute buffer `row`, if it is visible
+    pub fn to_screen(&self, row: usize, total_rows: usize) -> Option<usize> {
+        row.checked_sub(self.top(total_rows))
+            .filter(|&screen_row| screen_row < self.height)
+    }
+
+    /// Move the view by `rows` (negative is up, into the scrollback), staying inside the
+    /// buffer. Returns whether it moved.
+    pub fn scroll(&mut self, rows: isize, total_rows: usize) -> bool {
+        let max_offset = total_rows.saturating_sub(self.height);
+        let offset = self
+            .display_offset
+            .saturating_add_signed(-rows)
+            .min(max_offset);
+        let moved = offset != self.display_offset;
+        self.display_offset = offset;
+        moved
+    }
+
+    /// Move the view so its last row is `bottom`. Returns whether it moved.
+    pub fn scroll_to(&mut self, bottom: usize, total_rows: usize) -> bool {
+        let current = self.bottom(total_rows) as isize;
+        self.scroll(bottom as isize - current, total_rows)
+    }
+
+    pub fn scroll_to_bottom(&mut self) -> bool {
+        let moved = self.display_offset != 0;
+        self.display_offset = 0;
+        moved
+    }
+
+    /// `rows` were added to the bottom of the buffer. A view that stays put over new
+    /// output moves further from the bottom to keep showing the same rows.
+    pub fn rows_added(&mut self, rows: usize) {
+        if !self.follow_output && self.display_offset > 0 {
+            self.display_offset += rows;
+        }
+    }
+
+    /// `rows` were removed from the top of the buffer, which now has `total_rows` rows
+    pub fn rows_removed(&mut self, total_rows: usize) {
+        self.display_offset = self
+            .display_offset
+            .min(total_rows.saturating_sub(self.height));
+    }
+
+    /// Output was written on absolute `row`. When following output, a view that does not
+    /// show the row jumps to the bottom. Returns whether it moved.
+    pub fn output(&mut self, row: usize, total_rows: usize) -> bool {
+        if !self.follow_output || row <= self.bottom(total_rows) {
+            return false;
+        }
+        self.scroll_to_bottom()
+    }
+}
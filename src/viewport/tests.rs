@@ -0,0 +1,70 @@
+use super::Viewport;
+
+#[test]
+fn viewport_should_show_the_bottom_rows_by_default() {
+    let viewport = Viewport::new(5, true);
+
+    assert!(viewport.is_at_bottom());
+    assert_eq!(viewport.top(12), 7);
+    assert_eq!(viewport.bottom(12), 11);
+    assert_eq!(viewport.to_absolute(2, 12), 9);
+    assert_eq!(viewport.to_screen(9, 12), Some(2));
+    assert_eq!(viewport.to_screen(6, 12), None);
+}
+
+#[test]
+fn scroll_should_stay_inside_the_buffer() {
+    let mut viewport = Viewport::new(5, true);
+
+    assert!(viewport.scroll(-3, 12));
+    assert_eq!(viewport.display_offset(), 3);
+    assert!(viewport.scroll(-10, 12));
+    assert_eq!(viewport.top(12), 0);
+    assert!(!viewport.scroll(-1, 12));
+    assert!(viewport.scroll(100, 12));
+    assert!(viewport.is_at_bottom());
+
+    // A buffer no taller than the screen cannot scroll
+    assert!(!viewport.scroll(-1, 5));
+}
+
+#[test]
+fn scroll_to_should_place_the_last_visible_row() {
+    let mut viewport = Viewport::new(5, true);
+
+    assert!(viewport.scroll_to(6, 20));
+    assert_eq!(viewport.bottom(20), 6);
+    assert!(viewport.scroll_to(2, 20));
+    assert_eq!(viewport.bottom(20), 4);
+}
+
+#[test]
+fn following_viewport_should_jump_to_the_bottom_on_output() {
+    let mut viewport = Viewport::new(5, true);
+    viewport.scroll(-4, 20);
+
+    viewport.rows_added(1);
+    assert!(!viewport.output(14, 21));
+    assert!(viewport.output(20, 21));
+    assert!(viewport.is_at_bottom());
+}
+
+#[test]
+fn viewport_not_following_output_should_keep_its_rows() {
+    let mut viewport = Viewport::new(5, false);
+    viewport.scroll(-4, 20);
+    let top = viewport.top(20);
+
+    viewport.rows_added(3);
+    assert!(!viewport.output(22, 23));
+    assert_eq!(viewport.top(23), top);
+}
+
+#[test]
+fn removing_rows_should_keep_the_offset_inside_the_buffer() {
+    let mut viewport = Viewport::new(5, true);
+    viewport.scroll(-10, 20);
+
+    viewport.rows_removed(8);
+    assert_eq!(viewport.top(8), 0);
+}